@@ -1,20 +1,15 @@
-use std::fs::File;
 use std::hint::black_box;
-use std::io::{BufRead, BufReader};
 
 use binggan::plugins::{BPUTrasher, CacheTrasher};
 use binggan::{BenchRunner, PeakMemAlloc, INSTRUMENTED_SYSTEM};
+use serde_json_borrow::bench_utils::{
+    access_borrowed, access_serde_json, file_size, lines_for_file, load_borrowed, load_serde_json,
+};
 use serde_json_borrow::OwnedValue;
 
 #[global_allocator]
 pub static GLOBAL: &PeakMemAlloc<std::alloc::System> = &INSTRUMENTED_SYSTEM;
 
-fn lines_for_file(file: &str) -> impl Iterator<Item = String> {
-    BufReader::new(File::open(file).unwrap())
-        .lines()
-        .map(|line| line.unwrap())
-}
-
 fn main() {
     access_bench();
     parse_bench();
@@ -27,8 +22,8 @@ fn parse_bench() {
         named_data.push((
             name,
             (
-                move || lines_for_file(path),
-                File::open(path).unwrap().metadata().unwrap().len(),
+                move || lines_for_file(path).unwrap().map(Result::unwrap),
+                file_size(path).unwrap(),
             ),
         ));
     };
@@ -64,7 +59,7 @@ fn parse_bench() {
             let mut total_size = 0;
             for line in input_gen() {
                 let json: serde_json::Value = serde_json::from_str(&line).unwrap();
-                total_size += access_json(&json, access);
+                total_size += access_serde_json(&json, access);
             }
             black_box(total_size);
         });
@@ -81,11 +76,20 @@ fn parse_bench() {
             let mut total_size = 0;
             for line in input_gen() {
                 let json: OwnedValue = OwnedValue::parse_from(line).unwrap();
-                total_size += access_json_borrowed(&json, access);
+                total_size += access_borrowed(json.as_value(), access);
             }
             black_box(total_size);
         });
 
+        #[cfg(feature = "simd")]
+        runner.register("serde_json_borrow SIMD in situ", move |_data| {
+            for line in input_gen() {
+                let mut data: Vec<u8> = line.into();
+                let v = serde_json_borrow::Value::from_slice_in_situ(&mut data).unwrap();
+                black_box(v);
+            }
+        });
+
         runner.register("SIMD_json_borrow", move |_data| {
             for line in input_gen() {
                 let mut data: Vec<u8> = line.into();
@@ -134,13 +138,9 @@ fn access_bench() {
 
     for (name, path) in &file_name_path_and_access {
         let access = get_access_for_input_name(name);
-        let file_size = File::open(path).unwrap().metadata().unwrap().len();
-        let serde_jsons: Vec<serde_json::Value> = lines_for_file(path)
-            .map(|line| serde_json::from_str(&line).unwrap())
-            .collect();
-        let serde_json_borrows: Vec<OwnedValue> = lines_for_file(path)
-            .map(|line| OwnedValue::parse_from(line).unwrap())
-            .collect();
+        let file_size = file_size(path).unwrap();
+        let serde_jsons = load_serde_json(path).unwrap();
+        let serde_json_borrows = load_borrowed(path).unwrap();
 
         let mut group = runner.new_group();
         group.set_name(name);
@@ -149,7 +149,7 @@ fn access_bench() {
             let mut total_size = 0;
             for el in data.iter() {
                 // walk the access keys until the end. return 0 if value does not exist
-                total_size += access_json(el, access);
+                total_size += access_serde_json(el, access);
             }
             total_size
         });
@@ -159,7 +159,7 @@ fn access_bench() {
             move |data| {
                 let mut total_size = 0;
                 for el in data.iter() {
-                    total_size += access_json_borrowed(el, access);
+                    total_size += access_borrowed(el.as_value(), access);
                 }
                 total_size
             },
@@ -167,33 +167,3 @@ fn access_bench() {
         group.run();
     }
 }
-
-fn access_json(el: &serde_json::Value, access: &[&[&str]]) -> usize {
-    let mut total_size = 0;
-    // walk the access keys until the end. return 0 if value does not exist
-    for access in access {
-        let mut val = Some(el);
-        for key in *access {
-            val = val.and_then(|v| v.get(key));
-        }
-        if let Some(v) = val {
-            total_size += v.as_str().map(|s| s.len()).unwrap_or(0);
-        }
-    }
-    total_size
-}
-
-fn access_json_borrowed(el: &OwnedValue, access: &[&[&str]]) -> usize {
-    let mut total_size = 0;
-    for access in access {
-        // walk the access keys until the end. return 0 if value does not exist
-        let mut val = el.get_value();
-        for key in *access {
-            val = val.get(*key);
-        }
-        if let Some(v) = val.as_str() {
-            total_size += v.len();
-        }
-    }
-    total_size
-}
@@ -0,0 +1,140 @@
+//! Helpers to benchmark JSON deserialization on your own datasets, with the same methodology as
+//! the benchmarks of this crate.
+//!
+//! A corpus is a file with one JSON document per line (NDJSON). An access path is a list of
+//! keys, which is walked from the root of a document. The access runners return the total length
+//! of the strings found at the end of the paths, which should be passed to
+//! [`std::hint::black_box`] so the work isn't optimized away.
+//!
+//! Requires the `bench_utils` feature flag.
+//!
+//! # Example
+//! ```
+//! use serde_json_borrow::bench_utils::{access_borrowed, access_serde_json};
+//! use serde_json_borrow::Value;
+//!
+//! let line = r#"{"actor": {"login": "john"}, "id": "1"}"#;
+//! let paths: &[&[&str]] = &[&["actor", "login"], &["id"], &["missing"]];
+//!
+//! let value: Value = serde_json::from_str(line).unwrap();
+//! assert_eq!(access_borrowed(&value, paths), 5);
+//! let value: serde_json::Value = serde_json::from_str(line).unwrap();
+//! assert_eq!(access_serde_json(&value, paths), 5);
+//! ```
+
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::Path;
+
+use crate::{OwnedValue, Value};
+
+/// Returns the lines of the corpus at `path`.
+///
+/// The file is read lazily, so reading is part of the measurement, if this is called in the
+/// benchmarked function. Reading a line fails e.g. if it isn't valid UTF-8.
+pub fn lines_for_file(
+    path: impl AsRef<Path>,
+) -> io::Result<impl Iterator<Item = io::Result<String>>> {
+    let file = File::open(path)?;
+    Ok(BufReader::new(file).lines())
+}
+
+/// Returns the size of the corpus at `path` in bytes, to report the throughput.
+pub fn file_size(path: impl AsRef<Path>) -> io::Result<u64> {
+    Ok(std::fs::metadata(path)?.len())
+}
+
+/// Parses every line of the corpus at `path` into an [`OwnedValue`].
+pub fn load_borrowed(path: impl AsRef<Path>) -> io::Result<Vec<OwnedValue>> {
+    lines_for_file(path)?
+        .map(|line| OwnedValue::parse_from(line?))
+        .collect()
+}
+
+/// Parses every line of the corpus at `path` into a `serde_json::Value`.
+pub fn load_serde_json(path: impl AsRef<Path>) -> io::Result<Vec<serde_json::Value>> {
+    lines_for_file(path)?
+        .map(|line| Ok(serde_json::from_str(&line?)?))
+        .collect()
+}
+
+/// Walks the access `paths` in `value` and returns the total length of the strings found.
+/// Missing keys and non-string values count as 0.
+pub fn access_borrowed(value: &Value, paths: &[&[&str]]) -> usize {
+    let mut total_size = 0;
+    for path in paths {
+        let mut val = value;
+        for key in *path {
+            val = val.get(*key);
+        }
+        if let Some(v) = val.as_str() {
+            total_size += v.len();
+        }
+    }
+    total_size
+}
+
+/// Walks the access `paths` in `value` like [`access_borrowed`], for comparison with
+/// `serde_json`.
+pub fn access_serde_json(value: &serde_json::Value, paths: &[&[&str]]) -> usize {
+    let mut total_size = 0;
+    for path in paths {
+        let mut val = Some(value);
+        for key in *path {
+            val = val.and_then(|v| v.get(key));
+        }
+        if let Some(v) = val.and_then(|v| v.as_str()) {
+            total_size += v.len();
+        }
+    }
+    total_size
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bench_utils_corpus() {
+        let path = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/benches/simple-parse-bench.json"
+        );
+        let paths: &[&[&str]] = &[&["last_name"]];
+        let borrowed = load_borrowed(path).unwrap();
+        let serde_jsons = load_serde_json(path).unwrap();
+        assert_eq!(borrowed.len(), 1000);
+        assert_eq!(lines_for_file(path).unwrap().count(), 1000);
+        assert!(file_size(path).unwrap() > 0);
+
+        let total: usize = borrowed
+            .iter()
+            .map(|v| access_borrowed(v.as_value(), paths))
+            .sum();
+        let total_serde: usize = serde_jsons
+            .iter()
+            .map(|v| access_serde_json(v, paths))
+            .sum();
+        assert_eq!(total, total_serde);
+        assert_eq!(
+            access_borrowed(borrowed[0].as_value(), paths),
+            "Chaplain".len()
+        );
+        assert!(load_borrowed("does/not/exist.json").is_err());
+    }
+
+    #[test]
+    fn bench_utils_invalid_line() {
+        let path = std::env::temp_dir().join("serde_json_borrow_bench_utils_invalid_line.json");
+        std::fs::write(&path, b"{\"a\": 1}\n\"\xff\"\n").unwrap();
+        let lines: Vec<_> = lines_for_file(&path).unwrap().collect();
+        assert_eq!(lines[0].as_ref().unwrap(), r#"{"a": 1}"#);
+        assert_eq!(
+            lines[1].as_ref().unwrap_err().kind(),
+            io::ErrorKind::InvalidData
+        );
+        assert!(load_borrowed(&path).is_err());
+        assert!(load_serde_json(&path).is_err());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,145 @@
+use serde::ser::{Serialize, Serializer};
+
+use crate::Value;
+
+/// How [`Value::Bytes`] is serialized, see [`Value::with_bytes_encoding`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BytesEncoding {
+    /// Serializes bytes via `Serializer::serialize_bytes`. Binary formats like MessagePack or
+    /// CBOR write them natively, `serde_json` writes an array of numbers.
+    #[default]
+    Native,
+    /// Serializes bytes as standard base64 string with padding (RFC 4648).
+    Base64,
+}
+
+/// Serializes a [`Value`] with the given [`BytesEncoding`], see [`Value::with_bytes_encoding`].
+#[derive(Debug, Clone, Copy)]
+pub struct WithBytesEncoding<'a, 'ctx> {
+    value: &'a Value<'ctx>,
+    encoding: BytesEncoding,
+}
+
+impl<'ctx> Value<'ctx> {
+    /// Returns a wrapper, which serializes the value with the given encoding for
+    /// [`Value::Bytes`]. The `Serialize` impl of `Value` uses [`BytesEncoding::Native`].
+    ///
+    /// # Example
+    /// ```
+    /// use serde_json_borrow::{BytesEncoding, Value};
+    ///
+    /// let value = Value::Array(vec![Value::Bytes(b"hi!".as_slice().into())]);
+    /// let base64 = value.with_bytes_encoding(BytesEncoding::Base64);
+    /// assert_eq!(serde_json::to_string(&base64).unwrap(), r#"["aGkh"]"#);
+    /// assert_eq!(serde_json::to_string(&value).unwrap(), "[[104,105,33]]");
+    /// ```
+    pub fn with_bytes_encoding(&self, encoding: BytesEncoding) -> WithBytesEncoding<'_, 'ctx> {
+        WithBytesEncoding {
+            value: self,
+            encoding,
+        }
+    }
+}
+
+impl Serialize for WithBytesEncoding<'_, '_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where S: Serializer {
+        let wrap = |value| self.value_with_encoding(value);
+        match self.value {
+            Value::Bytes(bytes) => match self.encoding {
+                BytesEncoding::Native => serializer.serialize_bytes(bytes),
+                BytesEncoding::Base64 => serializer.serialize_str(&encode_base64(bytes)),
+            },
+            Value::Array(arr) => serializer.collect_seq(arr.iter().map(wrap)),
+            Value::Object(obj) => {
+                serializer.collect_map(obj.iter().map(|(key, value)| (key, wrap(value))))
+            }
+            value => value.serialize(serializer),
+        }
+    }
+}
+
+impl<'a, 'ctx> WithBytesEncoding<'a, 'ctx> {
+    fn value_with_encoding(&self, value: &'a Value<'ctx>) -> Self {
+        WithBytesEncoding {
+            value,
+            encoding: self.encoding,
+        }
+    }
+}
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Encodes `bytes` as standard base64 with padding.
+fn encode_base64(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let group = chunk.iter().enumerate().fold(0u32, |group, (idx, byte)| {
+            group | (*byte as u32) << (16 - 8 * idx)
+        });
+        for idx in 0..4 {
+            if idx <= chunk.len() {
+                let sextet = (group >> (18 - 6 * idx)) & 0x3f;
+                out.push(BASE64_ALPHABET[sextet as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use serde::de::value::{BorrowedBytesDeserializer, BytesDeserializer, Error};
+    use serde::Deserialize;
+
+    use super::*;
+
+    #[test]
+    fn base64_test() {
+        for (bytes, expected) in [
+            (&b""[..], ""),
+            (b"f", "Zg=="),
+            (b"fo", "Zm8="),
+            (b"foo", "Zm9v"),
+            (b"foob", "Zm9vYg=="),
+            (b"\xff\xfe\xfd", "//79"),
+        ] {
+            assert_eq!(encode_base64(bytes), expected);
+        }
+    }
+
+    #[test]
+    fn bytes_value() {
+        let data = [0u8, 1, 255];
+        let borrowed = Value::deserialize(BorrowedBytesDeserializer::<Error>::new(&data)).unwrap();
+        assert!(matches!(&borrowed, Value::Bytes(Cow::Borrowed(_))));
+        let owned = Value::deserialize(BytesDeserializer::<Error>::new(&data)).unwrap();
+        assert!(matches!(&owned, Value::Bytes(Cow::Owned(_))));
+        assert_eq!(borrowed, owned);
+        assert_eq!(owned.as_bytes(), Some(&data[..]));
+
+        let value =
+            Value::Object(vec![("b", owned), ("e", Value::Bytes(Cow::Borrowed(&[])))].into());
+        let expected = serde_json::json!({"b": [0, 1, 255], "e": []});
+        assert_eq!(value, expected);
+        assert_eq!(serde_json::Value::from(&value), expected);
+        assert_eq!(serde_json::to_value(&value).unwrap(), expected);
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&value.to_string()).unwrap(),
+            expected
+        );
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&format!("{value:#}")).unwrap(),
+            expected
+        );
+        assert_eq!(
+            serde_json::to_value(value.with_bytes_encoding(BytesEncoding::Base64)).unwrap(),
+            serde_json::json!({"b": "AAH/", "e": ""})
+        );
+    }
+}
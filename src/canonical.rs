@@ -0,0 +1,498 @@
+use core::hash::Hasher;
+use std::fmt::Write as _;
+use std::io;
+
+use crate::object_vec::key_str;
+use crate::value::{push_pointer_token, N};
+use crate::{NonFiniteNumber, Value};
+
+impl Value<'_> {
+    /// Feeds the canonical JSON serialization of the value into `state`, without materializing
+    /// it as a `String`.
+    ///
+    /// The canonical serialization follows the JSON Canonicalization Scheme (RFC 8785): no
+    /// whitespace, object keys sorted by their UTF-16 code units, floats formatted as shortest
+    /// round-trip numbers like in ECMAScript and minimal string escaping. Integers are written
+    /// exactly, even if they are not representable as `f64`.
+    ///
+    /// Values with the same canonical serialization produce the same hash, independent of the
+    /// key order of objects. The bytes are passed to the hasher in chunks of a fixed size, so the
+    /// hash only depends on the bytes, also for hashers which are sensitive to chunk boundaries.
+    /// The result may differ from hashing the serialized string with a single `write` though.
+    ///
+    /// Non-finite float numbers have no canonical serialization. They are hashed as `NaN`,
+    /// `Infinity` and `-Infinity` like in ECMAScript, so every value can be hashed and numbers,
+    /// which are equal, produce the same hash.
+    ///
+    /// # Example
+    /// ```
+    /// use std::collections::hash_map::DefaultHasher;
+    /// use std::hash::Hasher;
+    ///
+    /// use serde_json_borrow::Value;
+    ///
+    /// fn content_id(value: &Value) -> u64 {
+    ///     let mut hasher = DefaultHasher::new();
+    ///     value.hash_canonical(&mut hasher);
+    ///     hasher.finish()
+    /// }
+    ///
+    /// let a: Value = serde_json::from_str(r#"{"a": 1, "b": [true, null]}"#).unwrap();
+    /// let b: Value = serde_json::from_str(r#"{ "b": [true, null], "a": 1.0 }"#).unwrap();
+    /// assert_eq!(content_id(&a), content_id(&b));
+    /// ```
+    pub fn hash_canonical<H: Hasher>(&self, state: &mut H) {
+        let mut writer = HashWriter {
+            hasher: state,
+            buf: [0; HASH_CHUNK_LEN],
+            len: 0,
+        };
+        write_canonical(self, &mut writer, true).expect("hashing doesn't fail");
+        writer.finish();
+    }
+
+    /// Serializes the value per the JSON Canonicalization Scheme (RFC 8785), e.g. to sign it.
+    ///
+    /// Object keys are sorted by their UTF-16 code units, floats are formatted as shortest
+    /// round-trip numbers like in ECMAScript and strings are escaped minimally. Integers are
+    /// written exactly; RFC 8785 only covers integers up to 2^53 in magnitude, larger ones may
+    /// be formatted differently by other implementations.
+    ///
+    /// Returns an error if the value contains a non-finite float number.
+    ///
+    /// # Example
+    /// ```
+    /// use serde_json_borrow::Value;
+    ///
+    /// let value: Value =
+    ///     serde_json::from_str(r#"{"b": [1E3, 1.50, "\u0041"], "a": {"y": null, "x": true}}"#)
+    ///         .unwrap();
+    /// assert_eq!(
+    ///     value.to_canonical_string().unwrap(),
+    ///     r#"{"a":{"x":true,"y":null},"b":[1000,1.5,"A"]}"#
+    /// );
+    /// ```
+    pub fn to_canonical_string(&self) -> Result<String, NonFiniteNumber> {
+        let mut out = Vec::new();
+        if write_canonical(self, &mut out, false).is_err() {
+            let mut path = String::new();
+            return Err(find_non_finite(self, &mut path).expect("only non-finite numbers fail"));
+        }
+        Ok(String::from_utf8(out).expect("canonical JSON is valid UTF-8"))
+    }
+
+    /// Writes the canonical serialization of [`Value::to_canonical_string`] into `writer`.
+    ///
+    /// Returns an error with [`io::ErrorKind::InvalidData`] if the value contains a non-finite
+    /// float number.
+    pub fn write_canonical<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
+        write_canonical(self, &mut writer, false)
+    }
+}
+
+/// Returns the first non-finite number in `value`, `path` is the JSON Pointer of `value`.
+fn find_non_finite(value: &Value, path: &mut String) -> Option<NonFiniteNumber> {
+    let len = path.len();
+    let mut check = |token: &str, value| {
+        push_pointer_token(path, token);
+        let found = find_non_finite(value, path);
+        path.truncate(len);
+        found
+    };
+    match value {
+        Value::Number(n) => match n.n {
+            N::Float(v) if !v.is_finite() => Some(NonFiniteNumber {
+                path: path.clone(),
+                value: v,
+            }),
+            _ => None,
+        },
+        Value::Array(arr) => arr
+            .iter()
+            .enumerate()
+            .find_map(|(idx, value)| check(&idx.to_string(), value)),
+        Value::Object(obj) => obj.iter().find_map(|(key, value)| check(key, value)),
+        _ => None,
+    }
+}
+
+/// Writes the canonical JSON serialization of `value` into `writer`.
+///
+/// Non-finite float numbers return an error, or are written like in ECMAScript if
+/// `non_finite_tokens` is set.
+fn write_canonical<W: io::Write>(
+    value: &Value,
+    writer: &mut W,
+    non_finite_tokens: bool,
+) -> io::Result<()> {
+    match value {
+        Value::Null => writer.write_all(b"null"),
+        Value::Bool(true) => writer.write_all(b"true"),
+        Value::Bool(false) => writer.write_all(b"false"),
+        Value::Number(n) => match n.n {
+            N::PosInt(v) => write!(writer, "{v}"),
+            N::NegInt(v) => write!(writer, "{v}"),
+            N::Float(v) => write_es_float(v, writer, non_finite_tokens),
+        },
+        Value::Str(text) => write_canonical_str(text, writer),
+        #[cfg(feature = "bytes")]
+        Value::Bytes(bytes) => {
+            writer.write_all(b"[")?;
+            for (idx, byte) in bytes.iter().enumerate() {
+                if idx != 0 {
+                    writer.write_all(b",")?;
+                }
+                write!(writer, "{byte}")?;
+            }
+            writer.write_all(b"]")
+        }
+        Value::Array(arr) => {
+            writer.write_all(b"[")?;
+            for (idx, value) in arr.iter().enumerate() {
+                if idx != 0 {
+                    writer.write_all(b",")?;
+                }
+                write_canonical(value, writer, non_finite_tokens)?;
+            }
+            writer.write_all(b"]")
+        }
+        Value::Object(obj) => {
+            let mut entries: Vec<_> = obj.0.iter().collect();
+            entries.sort_by(|a, b| {
+                key_str(&a.0)
+                    .encode_utf16()
+                    .cmp(key_str(&b.0).encode_utf16())
+            });
+            writer.write_all(b"{")?;
+            for (idx, (key, value)) in entries.into_iter().enumerate() {
+                if idx != 0 {
+                    writer.write_all(b",")?;
+                }
+                write_canonical_str(key_str(key), writer)?;
+                writer.write_all(b":")?;
+                write_canonical(value, writer, non_finite_tokens)?;
+            }
+            writer.write_all(b"}")
+        }
+    }
+}
+
+/// Writes the string quoted, only `"`, `\` and control characters are escaped.
+fn write_canonical_str<W: io::Write>(text: &str, writer: &mut W) -> io::Result<()> {
+    writer.write_all(b"\"")?;
+    let bytes = text.as_bytes();
+    let mut start = 0;
+    for (idx, byte) in bytes.iter().enumerate() {
+        let escape: &[u8] = match byte {
+            b'"' => b"\\\"",
+            b'\\' => b"\\\\",
+            b'\x08' => b"\\b",
+            b'\x0c' => b"\\f",
+            b'\n' => b"\\n",
+            b'\r' => b"\\r",
+            b'\t' => b"\\t",
+            0..=0x1f => b"",
+            _ => continue,
+        };
+        writer.write_all(&bytes[start..idx])?;
+        if escape.is_empty() {
+            write!(writer, "\\u{byte:04x}")?;
+        } else {
+            writer.write_all(escape)?;
+        }
+        start = idx + 1;
+    }
+    writer.write_all(&bytes[start..])?;
+    writer.write_all(b"\"")
+}
+
+/// Writes a float like ECMAScript's `Number.prototype.toString`. Non-finite numbers return an
+/// error, unless `non_finite_tokens` is set.
+fn write_es_float<W: io::Write>(
+    value: f64,
+    writer: &mut W,
+    non_finite_tokens: bool,
+) -> io::Result<()> {
+    if non_finite_tokens && !value.is_finite() {
+        let token: &[u8] = if value.is_nan() {
+            b"NaN"
+        } else if value > 0.0 {
+            b"Infinity"
+        } else {
+            b"-Infinity"
+        };
+        return writer.write_all(token);
+    }
+    if !value.is_finite() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("number {value} can't be represented in JSON"),
+        ));
+    }
+    if value == 0.0 {
+        // Also covers -0
+        return writer.write_all(b"0");
+    }
+    if value < 0.0 {
+        writer.write_all(b"-")?;
+    }
+    // `{:e}` writes the shortest representation, which round-trips, e.g. `1.2345e-7`.
+    let mut buf = StackBuf::default();
+    write!(buf, "{:e}", value.abs()).expect("buffer is large enough");
+    let formatted = buf.as_str();
+    let (mantissa, exponent) = formatted
+        .split_once('e')
+        .expect("exponent is always written");
+    let exponent: i32 = exponent.parse().expect("exponent is an integer");
+    let (first, rest) = mantissa.split_at(1);
+    let rest = rest.strip_prefix('.').unwrap_or_default();
+    let digits = [first, rest];
+    let num_digits = (first.len() + rest.len()) as i32;
+    // The value is 0.<digits> * 10^n
+    let n = exponent + 1;
+
+    if num_digits <= n && n <= 21 {
+        write_digits(&digits, writer)?;
+        for _ in 0..n - num_digits {
+            writer.write_all(b"0")?;
+        }
+        Ok(())
+    } else if 0 < n && n <= 21 {
+        // `first` is a single digit
+        let (integer, fraction) = rest.split_at(n as usize - 1);
+        writer.write_all(first.as_bytes())?;
+        writer.write_all(integer.as_bytes())?;
+        writer.write_all(b".")?;
+        writer.write_all(fraction.as_bytes())
+    } else if -6 < n && n <= 0 {
+        writer.write_all(b"0.")?;
+        for _ in 0..-n {
+            writer.write_all(b"0")?;
+        }
+        write_digits(&digits, writer)
+    } else {
+        writer.write_all(first.as_bytes())?;
+        if !rest.is_empty() {
+            writer.write_all(b".")?;
+            writer.write_all(rest.as_bytes())?;
+        }
+        let sign = if n > 0 { '+' } else { '-' };
+        write!(writer, "e{}{}", sign, (n - 1).abs())
+    }
+}
+
+fn write_digits<W: io::Write>(digits: &[&str; 2], writer: &mut W) -> io::Result<()> {
+    writer.write_all(digits[0].as_bytes())?;
+    writer.write_all(digits[1].as_bytes())
+}
+
+/// A small buffer for formatting floats without allocating.
+#[derive(Default)]
+struct StackBuf {
+    buf: [u8; 32],
+    len: usize,
+}
+
+impl StackBuf {
+    fn as_str(&self) -> &str {
+        std::str::from_utf8(&self.buf[..self.len]).expect("only str is written")
+    }
+}
+
+impl std::fmt::Write for StackBuf {
+    fn write_str(&mut self, text: &str) -> std::fmt::Result {
+        let end = self.len + text.len();
+        self.buf
+            .get_mut(self.len..end)
+            .ok_or(std::fmt::Error)?
+            .copy_from_slice(text.as_bytes());
+        self.len = end;
+        Ok(())
+    }
+}
+
+/// The size of the chunks passed to the hasher by [`HashWriter`].
+const HASH_CHUNK_LEN: usize = 64;
+
+/// Passes written bytes to a hasher in chunks of [`HASH_CHUNK_LEN`] bytes, so the hash doesn't
+/// depend on how the bytes were split into writes.
+struct HashWriter<'a, H> {
+    hasher: &'a mut H,
+    buf: [u8; HASH_CHUNK_LEN],
+    len: usize,
+}
+
+impl<H: Hasher> HashWriter<'_, H> {
+    /// Passes the remaining bytes to the hasher.
+    fn finish(self) {
+        if self.len != 0 {
+            self.hasher.write(&self.buf[..self.len]);
+        }
+    }
+}
+
+impl<H: Hasher> io::Write for HashWriter<'_, H> {
+    fn write(&mut self, mut buf: &[u8]) -> io::Result<usize> {
+        let written = buf.len();
+        while !buf.is_empty() {
+            let take = buf.len().min(HASH_CHUNK_LEN - self.len);
+            self.buf[self.len..self.len + take].copy_from_slice(&buf[..take]);
+            self.len += take;
+            buf = &buf[take..];
+            if self.len == HASH_CHUNK_LEN {
+                self.hasher.write(&self.buf);
+                self.len = 0;
+            }
+        }
+        Ok(written)
+    }
+
+    #[inline]
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::hash_map::DefaultHasher;
+
+    use super::*;
+
+    fn canonical(value: &Value) -> String {
+        let mut out = Vec::new();
+        write_canonical(value, &mut out, false).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    fn es_float(value: f64) -> String {
+        let mut out = Vec::new();
+        write_es_float(value, &mut out, false).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    fn hash(value: &Value) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        value.hash_canonical(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn es_float_formatting() {
+        // Examples from RFC 8785 Appendix B
+        assert_eq!(es_float(0.0), "0");
+        assert_eq!(es_float(-0.0), "0");
+        assert_eq!(es_float(5e-324), "5e-324");
+        assert_eq!(es_float(-5e-324), "-5e-324");
+        assert_eq!(es_float(1.7976931348623157e308), "1.7976931348623157e+308");
+        assert_eq!(es_float(9007199254740992.0), "9007199254740992");
+        assert_eq!(es_float(-9007199254740992.0), "-9007199254740992");
+        assert_eq!(es_float(295147905179352830000.0), "295147905179352830000");
+        assert_eq!(es_float(9.999999999999997e22), "9.999999999999997e+22");
+        assert_eq!(es_float(1e23), "1e+23");
+        assert_eq!(es_float(1e21), "1e+21");
+        assert_eq!(es_float(999999999999999700000.0), "999999999999999700000");
+        assert_eq!(es_float(0.000001), "0.000001");
+        assert_eq!(es_float(0.0000001), "1e-7");
+        assert_eq!(es_float(1.5), "1.5");
+        assert_eq!(es_float(-123.456), "-123.456");
+        assert_eq!(es_float(100.0), "100");
+        assert_eq!(es_float(0.25), "0.25");
+    }
+
+    #[test]
+    fn canonical_serialization() {
+        let value: Value = serde_json::from_str(
+            r#"{"b": [1, -2, 1.5e3, "x\n\u001f\"\\/é"], "a": {"d": null, "c": true}, "": false}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            canonical(&value),
+            r#"{"":false,"a":{"c":true,"d":null},"b":[1,-2,1500,"x\n\u001f\"\\/é"]}"#
+        );
+    }
+
+    #[cfg(feature = "cowkeys")]
+    #[test]
+    fn canonical_key_order_utf16() {
+        // U+1F600 is encoded as surrogate pair 0xD83D 0xDE00 in UTF-16, so it sorts before
+        // U+FB33, but after it in UTF-8
+        let value: Value = serde_json::from_str(r#"{"\ufb33": 2, "\ud83d\ude00": 1}"#).unwrap();
+        assert_eq!(canonical(&value), "{\"\u{1f600}\":1,\"\u{fb33}\":2}");
+    }
+
+    #[test]
+    fn to_canonical_string_test() {
+        let value: Value =
+            serde_json::from_str(r#"{"n": [-0.0, 1e21, 1e-7, 18446744073709551615]}"#).unwrap();
+        assert_eq!(
+            value.to_canonical_string().unwrap(),
+            r#"{"n":[0,1e+21,1e-7,18446744073709551615]}"#
+        );
+        let mut out = Vec::new();
+        value.write_canonical(&mut out).unwrap();
+        assert_eq!(out, value.to_canonical_string().unwrap().as_bytes());
+
+        let value = Value::from(vec![Value::Null, Value::from(vec![f64::NAN])]);
+        let err = value.to_canonical_string().unwrap_err();
+        assert_eq!(err.path, "/1/0");
+        assert!(err.value.is_nan());
+        let err = Value::from(f64::INFINITY)
+            .write_canonical(Vec::new())
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn hash_canonical_test() {
+        let a: Value = serde_json::from_str(r#"{"a": [1, {"x": 1, "y": 2}], "b": "t"}"#).unwrap();
+        let b: Value = serde_json::from_str(r#"{"b": "t", "a": [1.0, {"y": 2, "x": 1}]}"#).unwrap();
+        let c: Value = serde_json::from_str(r#"{"b": "t", "a": [{"y": 2, "x": 1}, 1]}"#).unwrap();
+        assert_eq!(hash(&a), hash(&b));
+        assert_ne!(hash(&a), hash(&c));
+
+        let nan = Value::from(vec![f64::NAN, f64::INFINITY]);
+        assert_eq!(
+            hash(&nan),
+            hash(&Value::from(vec![-f64::NAN, f64::INFINITY]))
+        );
+        assert_ne!(
+            hash(&nan),
+            hash(&Value::from(vec![f64::NAN, f64::NEG_INFINITY]))
+        );
+    }
+
+    /// A hasher, whose result depends on the boundaries of the written chunks.
+    #[derive(Default)]
+    struct ChunkHasher(DefaultHasher);
+
+    impl Hasher for ChunkHasher {
+        fn finish(&self) -> u64 {
+            self.0.finish()
+        }
+
+        fn write(&mut self, bytes: &[u8]) {
+            self.0.write_usize(bytes.len());
+            self.0.write(bytes);
+        }
+    }
+
+    #[test]
+    fn hash_canonical_chunk_boundaries() {
+        let chunk_hash = |value: &Value| {
+            let mut hasher = ChunkHasher::default();
+            value.hash_canonical(&mut hasher);
+            hasher.finish()
+        };
+        // `1e18` is written digit by digit, the integer at once.
+        let float = Value::from(1e18);
+        let int = Value::from(1_000_000_000_000_000_000_u64);
+        assert_eq!(canonical(&float), canonical(&int));
+        assert_eq!(chunk_hash(&float), chunk_hash(&int));
+
+        let long = "x".repeat(HASH_CHUNK_LEN * 2 + 3);
+        let a = Value::from(vec![Value::from(1e18), Value::from(long.as_str())]);
+        let b = Value::from(vec![Value::from(1e18 as u64), Value::from(long.as_str())]);
+        assert_eq!(chunk_hash(&a), chunk_hash(&b));
+    }
+}
@@ -0,0 +1,141 @@
+use std::fmt;
+
+use crate::value::type_name;
+use crate::Value;
+
+/// Error returned by the `TryFrom<&Value>` conversions if the value has a different type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TypeMismatch {
+    /// The expected type, e.g. `"string"` or `"i64"`.
+    pub expected: &'static str,
+    /// The JSON type of the value, e.g. `"number"`.
+    pub actual: &'static str,
+}
+
+impl TypeMismatch {
+    fn new(expected: &'static str, actual: &Value) -> Self {
+        Self {
+            expected,
+            actual: type_name(actual),
+        }
+    }
+}
+
+impl fmt::Display for TypeMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "expected {}, found {}", self.expected, self.actual)
+    }
+}
+
+impl std::error::Error for TypeMismatch {}
+
+macro_rules! impl_try_from {
+    ($ty:ty, $expected:literal, $convert:ident) => {
+        impl TryFrom<&Value<'_>> for $ty {
+            type Error = TypeMismatch;
+
+            fn try_from(value: &Value<'_>) -> Result<Self, Self::Error> {
+                value
+                    .$convert()
+                    .ok_or_else(|| TypeMismatch::new($expected, value))
+            }
+        }
+    };
+}
+
+impl_try_from!(i64, "i64", as_i64);
+impl_try_from!(u64, "u64", as_u64);
+impl_try_from!(f64, "number", as_f64);
+impl_try_from!(bool, "boolean", as_bool);
+
+impl<'a> TryFrom<&'a Value<'_>> for &'a str {
+    type Error = TypeMismatch;
+
+    fn try_from(value: &'a Value<'_>) -> Result<Self, Self::Error> {
+        value
+            .as_str()
+            .ok_or_else(|| TypeMismatch::new("string", value))
+    }
+}
+
+impl TryFrom<&Value<'_>> for String {
+    type Error = TypeMismatch;
+
+    fn try_from(value: &Value<'_>) -> Result<Self, Self::Error> {
+        <&str>::try_from(value).map(str::to_string)
+    }
+}
+
+/// Converts an array by converting every element. Fails on the first element which can't be
+/// converted.
+impl<'a, 'ctx, T> TryFrom<&'a Value<'ctx>> for Vec<T>
+where T: TryFrom<&'a Value<'ctx>, Error = TypeMismatch>
+{
+    type Error = TypeMismatch;
+
+    fn try_from(value: &'a Value<'ctx>) -> Result<Self, Self::Error> {
+        value
+            .as_array()
+            .ok_or_else(|| TypeMismatch::new("array", value))?
+            .iter()
+            .map(T::try_from)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn try_from_scalars() {
+        let value: Value = serde_json::from_str(
+            r#"{"i": -1, "u": 18446744073709551615, "f": 0.5, "b": true, "s": "x"}"#,
+        )
+        .unwrap();
+        assert_eq!(i64::try_from(value.get("i")), Ok(-1));
+        assert_eq!(u64::try_from(value.get("u")), Ok(u64::MAX));
+        assert_eq!(f64::try_from(value.get("f")), Ok(0.5));
+        assert_eq!(f64::try_from(value.get("i")), Ok(-1.0));
+        assert_eq!(bool::try_from(value.get("b")), Ok(true));
+        assert_eq!(<&str>::try_from(value.get("s")), Ok("x"));
+        assert_eq!(String::try_from(value.get("s")), Ok("x".to_string()));
+
+        let err = i64::try_from(value.get("u")).unwrap_err();
+        assert_eq!(err.to_string(), "expected i64, found number");
+        assert_eq!(
+            u64::try_from(value.get("i")),
+            Err(TypeMismatch {
+                expected: "u64",
+                actual: "number"
+            })
+        );
+        assert_eq!(
+            String::try_from(value.get("missing"))
+                .unwrap_err()
+                .to_string(),
+            "expected string, found null"
+        );
+        assert_eq!(
+            bool::try_from(&value).unwrap_err().to_string(),
+            "expected boolean, found object"
+        );
+    }
+
+    #[test]
+    fn try_from_vec() {
+        let value: Value = serde_json::from_str(r#"[["a", "b"], ["c"]]"#).unwrap();
+        let strings: Vec<Vec<&str>> = Vec::try_from(&value).unwrap();
+        assert_eq!(strings, vec![vec!["a", "b"], vec!["c"]]);
+
+        assert_eq!(
+            Vec::<String>::try_from(&value).unwrap_err().to_string(),
+            "expected string, found array"
+        );
+        let value: Value = serde_json::from_str(r#"{"a": 1}"#).unwrap();
+        assert_eq!(
+            Vec::<u64>::try_from(&value).unwrap_err().to_string(),
+            "expected array, found object"
+        );
+    }
+}
@@ -58,6 +58,27 @@ impl<'de> Deserialize<'de> for Value<'de> {
                 Ok(Value::Str(Cow::Borrowed(v)))
             }
 
+            #[cfg(feature = "bytes")]
+            #[inline]
+            fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Self::Value, E>
+            where E: serde::de::Error {
+                Ok(Value::Bytes(v.into()))
+            }
+
+            #[cfg(feature = "bytes")]
+            #[inline]
+            fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+            where E: serde::de::Error {
+                Ok(Value::Bytes(Cow::Owned(v.to_owned())))
+            }
+
+            #[cfg(feature = "bytes")]
+            #[inline]
+            fn visit_borrowed_bytes<E>(self, v: &'de [u8]) -> Result<Self::Value, E>
+            where E: serde::de::Error {
+                Ok(Value::Bytes(Cow::Borrowed(v)))
+            }
+
             #[inline]
             fn visit_none<E>(self) -> Result<Value<'de>, E> {
                 Ok(Value::Null)
@@ -130,7 +151,7 @@ impl<'de> Deserialize<'de> for Value<'de> {
                     values.push((key, value));
                 }
 
-                Ok(Value::Object(ObjectAsVec(values)))
+                Ok(Value::Object(ObjectAsVec::from_entries(values)))
             }
         }
 
@@ -138,6 +159,29 @@ impl<'de> Deserialize<'de> for Value<'de> {
     }
 }
 
+/// Deserializes an instance of `T` from a string of JSON text, like `serde_json::from_str`.
+///
+/// Together with [`crate::to_string`], [`crate::to_vec`], [`crate::to_writer`] and
+/// [`crate::json!`], this allows to migrate from `serde_json` mostly by swapping the imports.
+/// Unlike in `serde_json`, the serialization functions of [`Value`] can't fail and return the
+/// output directly.
+///
+/// # Example
+/// ```
+/// use serde_json_borrow::{from_str, Value};
+///
+/// let value: Value = from_str(r#"{"name": "John"}"#).unwrap();
+/// assert_eq!(value.get("name"), "John");
+/// ```
+pub fn from_str<'a, T: Deserialize<'a>>(json: &'a str) -> serde_json::Result<T> {
+    serde_json::from_str(json)
+}
+
+/// Deserializes an instance of `T` from bytes of JSON text, like `serde_json::from_slice`.
+pub fn from_slice<'a, T: Deserialize<'a>>(json: &'a [u8]) -> serde_json::Result<T> {
+    serde_json::from_slice(json)
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -145,6 +189,16 @@ mod tests {
 
     use crate::Value;
 
+    #[test]
+    fn from_str_and_slice() {
+        let json = r#"{"a": ["b", 1]}"#;
+        let value: Value = crate::from_str(json).unwrap();
+        assert_eq!(value, crate::json!({"a": ["b", 1]}));
+        let from_slice: Value = crate::from_slice(json.as_bytes()).unwrap();
+        assert_eq!(from_slice, value);
+        assert!(crate::from_str::<Value>("{").is_err());
+    }
+
     #[test]
     fn deserialize_json_test() {
         let json_obj = r#"
@@ -168,6 +222,22 @@ mod tests {
         assert_eq!(val.get("u64"), &Value::Number(123u64.into()));
     }
 
+    #[test]
+    fn deserialize_json_keys() {
+        let val: Value = serde_json::from_str(r#"{"unescaped": 1}"#).unwrap();
+        assert_eq!(val.get("unescaped"), &Value::Number(1u64.into()));
+
+        let escaped = serde_json::from_str::<Value>(r#"{"esc\"aped": 2}"#);
+        #[cfg(feature = "cowkeys")]
+        assert_eq!(
+            escaped.unwrap().get("esc\"aped"),
+            &Value::Number(2u64.into())
+        );
+        // Without `cowkeys` keys are borrowed from the input, which fails if they contain escapes
+        #[cfg(not(feature = "cowkeys"))]
+        assert!(escaped.is_err());
+    }
+
     #[test]
     fn deserialize_json_allow_escaped_strings_in_values() {
         let json_obj = r#"
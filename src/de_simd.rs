@@ -0,0 +1,52 @@
+use std::io;
+
+use crate::Value;
+
+impl<'ctx> Value<'ctx> {
+    /// Parses `data` with the SIMD parser of `simd-json` into a `Value`, which borrows from
+    /// `data`.
+    ///
+    /// The parser unescapes strings in place, so strings with escapes are borrowed too, instead
+    /// of being allocated. Afterwards `data` doesn't contain the JSON text anymore and may not
+    /// even be valid utf-8. Apart from that, the result is the same as for [`crate::from_slice`].
+    ///
+    /// Requires the `simd` feature flag.
+    ///
+    /// # Example
+    /// ```
+    /// use std::borrow::Cow;
+    ///
+    /// use serde_json_borrow::Value;
+    ///
+    /// let mut data = br#"{"name": "Jo\"hn"}"#.to_vec();
+    /// let value = Value::from_slice_in_situ(&mut data).unwrap();
+    /// assert!(matches!(value.get("name"), Value::Str(Cow::Borrowed("Jo\"hn"))));
+    /// ```
+    pub fn from_slice_in_situ(data: &'ctx mut [u8]) -> io::Result<Self> {
+        simd_json::serde::from_slice(data)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use super::*;
+
+    #[test]
+    fn from_slice_in_situ_test() {
+        let json = r#"{"a": ["x\ty", 1, -2, 1.5, 18446744073709551615, null, true], "b": {}}"#;
+        let mut data = json.as_bytes().to_vec();
+        let value = Value::from_slice_in_situ(&mut data).unwrap();
+        assert_eq!(value, serde_json::from_str::<Value>(json).unwrap());
+        assert!(matches!(
+            value.get("a").get(0),
+            Value::Str(Cow::Borrowed("x\ty"))
+        ));
+
+        assert!(Value::from_slice_in_situ(&mut b"[1,".to_vec()).is_err());
+        assert!(Value::from_slice_in_situ(&mut b"[1] 2".to_vec()).is_err());
+        assert!(Value::from_slice_in_situ(&mut b"[\"\xff\"]".to_vec()).is_err());
+    }
+}
@@ -26,6 +26,8 @@ impl<'de> Deserializer<'de> for &'de Value<'_> {
                 N::Float(f) => visitor.visit_f64(f),
             },
             Value::Str(s) => visitor.visit_borrowed_str(s),
+            #[cfg(feature = "bytes")]
+            Value::Bytes(bytes) => visitor.visit_borrowed_bytes(bytes),
             Value::Array(arr) => {
                 let seq = SeqDeserializer::new(arr);
                 visitor.visit_seq(seq)
@@ -0,0 +1,267 @@
+use core::fmt;
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::io;
+
+use serde::de::{DeserializeSeed, MapAccess, SeqAccess, Visitor};
+
+use crate::value::push_pointer_token;
+
+/// Analyzes a corpus of JSON documents for escaped strings and keys.
+///
+/// Strings containing escape sequences can't be borrowed from the input and are allocated as
+/// owned `String`s. Keys containing escape sequences can't be parsed at all without the
+/// `cowkeys` feature flag. The report shows which fields are affected, e.g. to decide on feature
+/// flags or to make producers avoid escapes in hot fields.
+///
+/// Fields are identified by their JSON Pointer, with all array indices replaced by `*`, so the
+/// elements of an array are aggregated.
+///
+/// # Example
+/// ```
+/// use serde_json_borrow::EscapeReport;
+///
+/// let mut report = EscapeReport::new();
+/// report.add_document(r#"{"msg": "a \"quote\"", "tags": ["x", "y"]}"#).unwrap();
+/// report.add_document(r#"{"msg": "plain", "tags": ["z\n"], "k\u00e9y": 1}"#).unwrap();
+///
+/// assert_eq!(report.documents, 2);
+/// assert_eq!(report.fields["/msg"].strings, 2);
+/// assert_eq!(report.fields["/msg"].escaped_strings, 1);
+/// let escaped: Vec<_> = report.escaped_fields().map(|(path, _)| path).collect();
+/// assert_eq!(escaped, vec!["/msg", "/tags/*"]);
+/// assert_eq!(report.escaped_keys().collect::<Vec<_>>(), vec!["/kéy"]);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EscapeReport {
+    /// The number of analyzed documents.
+    pub documents: usize,
+    /// The statistics per field, for fields with string values or escaped keys.
+    pub fields: BTreeMap<String, FieldEscapeStats>,
+}
+
+/// The escape statistics of a single field, see [`EscapeReport`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FieldEscapeStats {
+    /// The number of string values.
+    pub strings: usize,
+    /// The number of string values with escapes, which require an owned `String`.
+    pub escaped_strings: usize,
+    /// The number of times the key of the field contained escapes. Documents with such keys
+    /// fail to parse without the `cowkeys` feature flag.
+    pub escaped_keys: usize,
+}
+
+impl EscapeReport {
+    /// Creates an empty report.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Analyzes a JSON document and adds its statistics to the report.
+    ///
+    /// The document is not parsed into a [`crate::Value`]. On invalid JSON an error is returned
+    /// and the report may contain partial statistics of the document.
+    pub fn add_document(&mut self, json: &str) -> io::Result<()> {
+        let mut deserializer = serde_json::Deserializer::from_str(json);
+        AnalyzeSeed {
+            report: self,
+            path: &mut String::new(),
+        }
+        .deserialize(&mut deserializer)?;
+        deserializer.end()?;
+        self.documents += 1;
+        Ok(())
+    }
+
+    /// Returns the fields, which had string values with escapes.
+    pub fn escaped_fields(&self) -> impl Iterator<Item = (&str, &FieldEscapeStats)> {
+        self.fields
+            .iter()
+            .filter(|(_, stats)| stats.escaped_strings > 0)
+            .map(|(path, stats)| (path.as_str(), stats))
+    }
+
+    /// Returns the fields, whose keys contained escapes and which would break parsing without
+    /// the `cowkeys` feature flag.
+    pub fn escaped_keys(&self) -> impl Iterator<Item = &str> {
+        self.fields
+            .iter()
+            .filter(|(_, stats)| stats.escaped_keys > 0)
+            .map(|(path, _)| path.as_str())
+    }
+
+    fn stats(&mut self, path: &str) -> &mut FieldEscapeStats {
+        if !self.fields.contains_key(path) {
+            self.fields
+                .insert(path.to_string(), FieldEscapeStats::default());
+        }
+        self.fields.get_mut(path).expect("inserted above")
+    }
+}
+
+struct AnalyzeSeed<'a> {
+    report: &'a mut EscapeReport,
+    /// The JSON Pointer of the current value, with array indices replaced by `*`.
+    path: &'a mut String,
+}
+
+impl AnalyzeSeed<'_> {
+    fn string(self, escaped: bool) {
+        let stats = self.report.stats(self.path);
+        stats.strings += 1;
+        stats.escaped_strings += escaped as usize;
+    }
+}
+
+impl<'de> DeserializeSeed<'de> for AnalyzeSeed<'_> {
+    type Value = ();
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where D: serde::Deserializer<'de> {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de> Visitor<'de> for AnalyzeSeed<'_> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("any valid JSON value")
+    }
+
+    fn visit_bool<E>(self, _value: bool) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_i64<E>(self, _value: i64) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_u64<E>(self, _value: u64) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_f64<E>(self, _value: f64) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_unit<E>(self) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_borrowed_str<E>(self, _value: &'de str) -> Result<(), E> {
+        self.string(false);
+        Ok(())
+    }
+
+    fn visit_str<E>(self, _value: &str) -> Result<(), E> {
+        self.string(true);
+        Ok(())
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<(), A::Error>
+    where A: SeqAccess<'de> {
+        let len = self.path.len();
+        self.path.push_str("/*");
+        while seq
+            .next_element_seed(AnalyzeSeed {
+                report: self.report,
+                path: self.path,
+            })?
+            .is_some()
+        {}
+        self.path.truncate(len);
+        Ok(())
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<(), A::Error>
+    where A: MapAccess<'de> {
+        let len = self.path.len();
+        while let Some(key) = map.next_key_seed(KeyProbe)? {
+            push_pointer_token(self.path, &key);
+            if let Cow::Owned(_) = key {
+                self.report.stats(self.path).escaped_keys += 1;
+            }
+            map.next_value_seed(AnalyzeSeed {
+                report: self.report,
+                path: self.path,
+            })?;
+            self.path.truncate(len);
+        }
+        Ok(())
+    }
+}
+
+/// Deserializes a key, which is only borrowed if it contains no escapes.
+pub(crate) struct KeyProbe;
+
+impl<'de> DeserializeSeed<'de> for KeyProbe {
+    type Value = Cow<'de, str>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where D: serde::Deserializer<'de> {
+        deserializer.deserialize_str(self)
+    }
+}
+
+impl<'de> Visitor<'de> for KeyProbe {
+    type Value = Cow<'de, str>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a string")
+    }
+
+    fn visit_borrowed_str<E>(self, v: &'de str) -> Result<Self::Value, E> {
+        Ok(Cow::Borrowed(v))
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E> {
+        Ok(Cow::Owned(v.to_owned()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escape_report_nested() {
+        let mut report = EscapeReport::new();
+        report
+            .add_document(r#"[{"a": {"b": ["A", "\u0042", 1]}, "a\/b": "c"}, "\t", null]"#)
+            .unwrap();
+        assert_eq!(report.documents, 1);
+        let stats = |strings, escaped_strings, escaped_keys| FieldEscapeStats {
+            strings,
+            escaped_strings,
+            escaped_keys,
+        };
+        assert_eq!(
+            report.fields,
+            BTreeMap::from([
+                ("/*".to_string(), stats(1, 1, 0)),
+                ("/*/a/b/*".to_string(), stats(2, 1, 0)),
+                ("/*/a~1b".to_string(), stats(1, 0, 1)),
+            ])
+        );
+        assert_eq!(report.escaped_keys().collect::<Vec<_>>(), vec!["/*/a~1b"]);
+    }
+
+    #[test]
+    fn escape_report_invalid() {
+        let mut report = EscapeReport::new();
+        assert!(report.add_document(r#"{"a": "b""#).is_err());
+        assert!(report.add_document(r#"{} {}"#).is_err());
+        assert_eq!(report.documents, 0);
+        report.add_document(r#""""#).unwrap();
+        assert_eq!(
+            report.fields[""],
+            FieldEscapeStats {
+                strings: 1,
+                escaped_strings: 0,
+                escaped_keys: 0
+            }
+        );
+    }
+}
@@ -0,0 +1,296 @@
+use std::borrow::Cow;
+use std::{fmt, slice};
+
+use crate::object_vec::{key_from_cow, key_str, KeyStrType};
+use crate::{Number, ObjectAsVec, Value};
+
+/// A token of a JSON document in a streaming representation, see [`Value::to_events`] and
+/// [`Value::from_events`].
+///
+/// Arrays and objects are framed by start and end events. Within an object, each member is a
+/// [`JsonEvent::Key`] followed by the events of its value.
+#[derive(Clone, PartialEq)]
+pub enum JsonEvent<'a> {
+    /// The start of an array.
+    StartArray,
+    /// The end of an array.
+    EndArray,
+    /// The start of an object.
+    StartObject,
+    /// The end of an object.
+    EndObject,
+    /// The key of an object member.
+    Key(Cow<'a, str>),
+    /// A `null` value.
+    Null,
+    /// A boolean value.
+    Bool(bool),
+    /// A number value.
+    Number(Number),
+    /// A string value.
+    Str(Cow<'a, str>),
+    /// Binary data, see `Value::Bytes`.
+    #[cfg(feature = "bytes")]
+    Bytes(Cow<'a, [u8]>),
+}
+
+impl fmt::Debug for JsonEvent<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JsonEvent::StartArray => f.write_str("StartArray"),
+            JsonEvent::EndArray => f.write_str("EndArray"),
+            JsonEvent::StartObject => f.write_str("StartObject"),
+            JsonEvent::EndObject => f.write_str("EndObject"),
+            JsonEvent::Key(key) => f.debug_tuple("Key").field(key).finish(),
+            JsonEvent::Null => f.write_str("Null"),
+            JsonEvent::Bool(val) => f.debug_tuple("Bool").field(val).finish(),
+            // Formats like `Value::Number`, since `Number` doesn't implement `Debug`.
+            JsonEvent::Number(val) => fmt::Debug::fmt(&Value::Number(*val), f),
+            JsonEvent::Str(val) => f.debug_tuple("Str").field(val).finish(),
+            #[cfg(feature = "bytes")]
+            JsonEvent::Bytes(val) => f.debug_tuple("Bytes").field(val).finish(),
+        }
+    }
+}
+
+impl<'ctx> Value<'ctx> {
+    /// Returns the events of the value in document order. Strings and keys are borrowed from
+    /// `self`.
+    ///
+    /// Nesting is tracked on the heap, so deeply nested values can't overflow the stack.
+    ///
+    /// # Example
+    /// ```
+    /// use serde_json_borrow::{JsonEvent, Value};
+    ///
+    /// let value: Value = serde_json::from_str(r#"{"a": [true]}"#).unwrap();
+    /// let events: Vec<_> = value.to_events().collect();
+    /// assert_eq!(
+    ///     events,
+    ///     [
+    ///         JsonEvent::StartObject,
+    ///         JsonEvent::Key("a".into()),
+    ///         JsonEvent::StartArray,
+    ///         JsonEvent::Bool(true),
+    ///         JsonEvent::EndArray,
+    ///         JsonEvent::EndObject,
+    ///     ]
+    /// );
+    /// assert_eq!(Value::from_events(events).unwrap(), value);
+    /// ```
+    pub fn to_events(&self) -> Events<'_, 'ctx> {
+        Events {
+            stack: Vec::new(),
+            next: Some(self),
+        }
+    }
+
+    /// Builds a value from a sequence of events, e.g. produced by [`Value::to_events`] or a
+    /// streaming parser.
+    ///
+    /// Returns an error if the events don't form exactly one complete value, or if a key is
+    /// owned without the `cowkeys` feature flag.
+    pub fn from_events<I>(events: I) -> Result<Value<'ctx>, InvalidEvents>
+    where I: IntoIterator<Item = JsonEvent<'ctx>> {
+        /// An array or object, whose elements are being collected.
+        enum Builder<'ctx> {
+            Array(Vec<Value<'ctx>>),
+            Object(
+                Vec<(KeyStrType<'ctx>, Value<'ctx>)>,
+                Option<KeyStrType<'ctx>>,
+            ),
+        }
+
+        let mut stack: Vec<Builder> = Vec::new();
+        let mut root = None;
+        let mut position = 0;
+        for event in events {
+            let invalid = |reason| InvalidEvents { position, reason };
+            if root.is_some() {
+                return Err(invalid("trailing events after the value"));
+            }
+            let value = match event {
+                JsonEvent::StartArray => {
+                    stack.push(Builder::Array(Vec::new()));
+                    None
+                }
+                JsonEvent::StartObject => {
+                    stack.push(Builder::Object(Vec::new(), None));
+                    None
+                }
+                JsonEvent::EndArray => match stack.pop() {
+                    Some(Builder::Array(arr)) => Some(Value::Array(arr)),
+                    _ => return Err(invalid("unexpected end of array")),
+                },
+                JsonEvent::EndObject => match stack.pop() {
+                    Some(Builder::Object(entries, None)) => {
+                        Some(Value::Object(ObjectAsVec::from_entries(entries)))
+                    }
+                    _ => return Err(invalid("unexpected end of object")),
+                },
+                JsonEvent::Key(key) => match stack.last_mut() {
+                    Some(Builder::Object(_, pending @ None)) => {
+                        let key = key_from_cow(key)
+                            .ok_or(invalid("owned keys require the `cowkeys` feature flag"))?;
+                        *pending = Some(key);
+                        None
+                    }
+                    _ => return Err(invalid("unexpected key")),
+                },
+                JsonEvent::Null => Some(Value::Null),
+                JsonEvent::Bool(val) => Some(Value::Bool(val)),
+                JsonEvent::Number(val) => Some(Value::Number(val)),
+                JsonEvent::Str(val) => Some(Value::Str(val)),
+                #[cfg(feature = "bytes")]
+                JsonEvent::Bytes(val) => Some(Value::Bytes(val)),
+            };
+            if let Some(value) = value {
+                match stack.last_mut() {
+                    None => root = Some(value),
+                    Some(Builder::Array(arr)) => arr.push(value),
+                    Some(Builder::Object(entries, pending)) => match pending.take() {
+                        Some(key) => entries.push((key, value)),
+                        None => return Err(invalid("expected key")),
+                    },
+                }
+            }
+            position += 1;
+        }
+        root.ok_or(InvalidEvents {
+            position,
+            reason: "unexpected end of events",
+        })
+    }
+}
+
+/// Iterator over the events of a [`Value`], see [`Value::to_events`].
+#[derive(Debug, Clone)]
+pub struct Events<'a, 'ctx> {
+    stack: Vec<Frame<'a, 'ctx>>,
+    /// The value, whose events are returned next.
+    next: Option<&'a Value<'ctx>>,
+}
+
+/// An array or object, whose elements are being iterated.
+#[derive(Debug, Clone)]
+enum Frame<'a, 'ctx> {
+    Array(slice::Iter<'a, Value<'ctx>>),
+    Object(slice::Iter<'a, (KeyStrType<'ctx>, Value<'ctx>)>),
+}
+
+impl<'a> Iterator for Events<'a, '_> {
+    type Item = JsonEvent<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(value) = self.next.take() {
+                return Some(match value {
+                    Value::Null => JsonEvent::Null,
+                    Value::Bool(val) => JsonEvent::Bool(*val),
+                    Value::Number(val) => JsonEvent::Number(*val),
+                    Value::Str(val) => JsonEvent::Str(Cow::Borrowed(val)),
+                    #[cfg(feature = "bytes")]
+                    Value::Bytes(val) => JsonEvent::Bytes(Cow::Borrowed(val)),
+                    Value::Array(arr) => {
+                        self.stack.push(Frame::Array(arr.iter()));
+                        JsonEvent::StartArray
+                    }
+                    Value::Object(obj) => {
+                        self.stack.push(Frame::Object(obj.0.iter()));
+                        JsonEvent::StartObject
+                    }
+                });
+            }
+            match self.stack.last_mut()? {
+                Frame::Array(iter) => match iter.next() {
+                    Some(value) => self.next = Some(value),
+                    None => {
+                        self.stack.pop();
+                        return Some(JsonEvent::EndArray);
+                    }
+                },
+                Frame::Object(iter) => match iter.next() {
+                    Some((key, value)) => {
+                        self.next = Some(value);
+                        return Some(JsonEvent::Key(Cow::Borrowed(key_str(key))));
+                    }
+                    None => {
+                        self.stack.pop();
+                        return Some(JsonEvent::EndObject);
+                    }
+                },
+            }
+        }
+    }
+}
+
+/// Error returned by [`Value::from_events`] for an invalid sequence of events.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidEvents {
+    /// The index of the invalid event, or the number of events if they ended early.
+    pub position: usize,
+    /// Why the events are invalid.
+    pub reason: &'static str,
+}
+
+impl fmt::Display for InvalidEvents {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid event at position {}: {}",
+            self.position, self.reason
+        )
+    }
+}
+
+impl std::error::Error for InvalidEvents {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn events_roundtrip() {
+        let json = r#"[{"a": null, "b": [[], {}], "c": "s\n"}, -1, 2.5, false, "x"]"#;
+        let value: Value = serde_json::from_str(json).unwrap();
+        let events: Vec<_> = value.to_events().collect();
+        assert_eq!(events.len(), 19);
+        assert_eq!(Value::from_events(events).unwrap(), value);
+
+        let scalar = Value::from("x");
+        assert_eq!(
+            scalar.to_events().collect::<Vec<_>>(),
+            [JsonEvent::Str("x".into())]
+        );
+    }
+
+    #[test]
+    fn events_invalid() {
+        use JsonEvent::*;
+        let error = |events: Vec<JsonEvent<'static>>| Value::from_events(events).unwrap_err();
+        assert_eq!(error(vec![]).reason, "unexpected end of events");
+        assert_eq!(error(vec![StartArray, Null]).position, 2);
+        assert_eq!(
+            error(vec![Null, Null]).reason,
+            "trailing events after the value"
+        );
+        assert_eq!(
+            error(vec![StartArray, EndObject]).reason,
+            "unexpected end of object"
+        );
+        assert_eq!(
+            error(vec![StartObject, EndArray]).reason,
+            "unexpected end of array"
+        );
+        assert_eq!(error(vec![StartObject, Null]).reason, "expected key");
+        assert_eq!(
+            error(vec![StartArray, Key("a".into())]).reason,
+            "unexpected key"
+        );
+        let dangling_key = error(vec![StartObject, Key("a".into()), EndObject]);
+        assert_eq!(dangling_key.reason, "unexpected end of object");
+        assert_eq!(dangling_key.position, 2);
+        let double_key = error(vec![StartObject, Key("a".into()), Key("b".into())]);
+        assert_eq!(double_key.reason, "unexpected key");
+    }
+}
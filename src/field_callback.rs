@@ -0,0 +1,124 @@
+use core::fmt;
+use std::io;
+
+use serde::de::{DeserializeSeed, MapAccess, Visitor};
+
+use crate::object_vec::{key_str, KeyStrType, ObjectAsVec};
+use crate::value::push_pointer_token;
+use crate::Value;
+
+/// Parses a JSON object and invokes `callback` for each top-level field as soon as it is parsed.
+///
+/// The callback receives the JSON Pointer of the field (e.g. `/name`) and its parsed value. If
+/// the callback returns `false` the field is discarded and won't be part of the returned object.
+/// This allows to filter and build the DOM in a single pass, which is useful for huge objects
+/// where only some fields are retained.
+///
+/// Returns an error if the JSON is not an object.
+///
+/// # Example
+/// ```
+/// use serde_json_borrow::{from_str_with_field_callback, Value};
+///
+/// let json = r#"{"id": 1, "payload": {"large": "blob"}, "type": "event"}"#;
+/// let mut seen = Vec::new();
+/// let value = from_str_with_field_callback(json, |pointer, _value| {
+///     seen.push(pointer.to_string());
+///     pointer != "/payload"
+/// })
+/// .unwrap();
+///
+/// assert_eq!(seen, vec!["/id", "/payload", "/type"]);
+/// assert_eq!(value.get("type"), &Value::Str("event".into()));
+/// assert_eq!(value.get("payload"), &Value::Null);
+/// ```
+pub fn from_str_with_field_callback<'ctx, F>(
+    json: &'ctx str,
+    callback: F,
+) -> io::Result<Value<'ctx>>
+where
+    F: FnMut(&str, &Value<'ctx>) -> bool,
+{
+    let mut deserializer = serde_json::Deserializer::from_str(json);
+    let value = FieldCallbackSeed { callback }.deserialize(&mut deserializer)?;
+    deserializer.end()?;
+    Ok(value)
+}
+
+struct FieldCallbackSeed<F> {
+    callback: F,
+}
+
+impl<'de, F> DeserializeSeed<'de> for FieldCallbackSeed<F>
+where F: FnMut(&str, &Value<'de>) -> bool
+{
+    type Value = Value<'de>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where D: serde::Deserializer<'de> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de, F> Visitor<'de> for FieldCallbackSeed<F>
+where F: FnMut(&str, &Value<'de>) -> bool
+{
+    type Value = Value<'de>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a JSON object")
+    }
+
+    fn visit_map<V>(mut self, mut visitor: V) -> Result<Value<'de>, V::Error>
+    where V: MapAccess<'de> {
+        let mut values = Vec::with_capacity(visitor.size_hint().unwrap_or(0));
+        // Reused for every field to avoid an allocation per field.
+        let mut pointer = String::new();
+
+        while let Some(key) = visitor.next_key::<KeyStrType>()? {
+            let value: Value<'de> = visitor.next_value()?;
+            pointer.clear();
+            push_pointer_token(&mut pointer, key_str(&key));
+            if (self.callback)(&pointer, &value) {
+                values.push((key, value));
+            }
+        }
+
+        Ok(Value::Object(ObjectAsVec::from_entries(values)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn field_callback_keeps_all() {
+        let json = r#"{"a": 1, "b": [1, 2], "c": {"d": null}}"#;
+        let value = from_str_with_field_callback(json, |_, _| true).unwrap();
+        let expected: Value = serde_json::from_str(json).unwrap();
+        assert_eq!(value, expected);
+    }
+
+    #[test]
+    fn field_callback_discards_fields() {
+        let json = r#"{"keep": "yes", "drop": {"nested": [1, 2, 3]}, "a/b~c": true}"#;
+        let mut pointers = Vec::new();
+        let value = from_str_with_field_callback(json, |pointer, value| {
+            pointers.push(pointer.to_string());
+            !value.is_object()
+        })
+        .unwrap();
+        assert_eq!(pointers, vec!["/keep", "/drop", "/a~1b~0c"]);
+        assert_eq!(value.as_object().unwrap().len(), 2);
+        assert_eq!(value.get("keep"), &Value::Str("yes".into()));
+        assert_eq!(value.pointer("/a~1b~0c"), Some(&Value::Bool(true)));
+    }
+
+    #[test]
+    fn field_callback_errors() {
+        assert!(from_str_with_field_callback("[1, 2]", |_, _| true).is_err());
+        assert!(from_str_with_field_callback(r#"{"a": 1} x"#, |_, _| true).is_err());
+        assert!(from_str_with_field_callback(r#"{"a": 1"#, |_, _| true).is_err());
+    }
+}
@@ -0,0 +1,253 @@
+use crate::object_vec::key_str;
+use crate::Value;
+
+impl<'ctx> Value<'ctx> {
+    /// Returns a copy of the value, which contains only the paths matched by `include` and none
+    /// of the paths matched by `exclude`.
+    ///
+    /// See [`Value::retain_paths`] for the pattern syntax.
+    ///
+    /// # Example
+    /// ```
+    /// use serde_json_borrow::Value;
+    ///
+    /// let value: Value = serde_json::from_str(
+    ///     r#"{"users": [{"name": "a", "email": "a@x.com"}], "secret": 1, "meta": {"v": 2}}"#,
+    /// )
+    /// .unwrap();
+    /// let shared = value.filter_paths(&["users", "meta"], &["users.*.email"]);
+    ///
+    /// assert_eq!(
+    ///     shared,
+    ///     serde_json::from_str::<Value>(r#"{"users": [{"name": "a"}], "meta": {"v": 2}}"#).unwrap()
+    /// );
+    /// ```
+    pub fn filter_paths(&self, include: &[&str], exclude: &[&str]) -> Value<'ctx> {
+        let mut value = self.clone();
+        value.retain_paths(include, exclude);
+        value
+    }
+
+    /// Prunes the value in place, so that it contains only the paths matched by `include` and
+    /// none of the paths matched by `exclude`.
+    ///
+    /// Patterns are paths of object keys or array indices separated by `.`, e.g. `a.b.0`. A `*`
+    /// in a segment matches any number of characters, so `a.*.b` matches `b` in any child of `a`
+    /// and `a.user_*` matches all keys of `a` starting with `user_`. Keys containing a `.` can't
+    /// be matched.
+    ///
+    /// A matched path includes or excludes the whole subtree below it. An empty `include` list
+    /// includes everything. Objects and arrays, which contain no included path anymore, are
+    /// removed. Excludes take precedence over includes.
+    pub fn retain_paths(&mut self, include: &[&str], exclude: &[&str]) {
+        let include: Vec<Vec<&str>> = include.iter().map(|p| p.split('.').collect()).collect();
+        let exclude: Vec<Vec<&str>> = exclude.iter().map(|p| p.split('.').collect()).collect();
+        let patterns = Patterns {
+            include: &include,
+            exclude: &exclude,
+        };
+        let active_include: Option<Vec<usize>> = if include.is_empty() {
+            None
+        } else {
+            Some((0..include.len()).collect())
+        };
+        let active_exclude: Vec<usize> = (0..exclude.len()).collect();
+        match self {
+            Value::Object(_) | Value::Array(_) => {
+                retain_children(
+                    self,
+                    0,
+                    active_include.as_deref(),
+                    &active_exclude,
+                    &patterns,
+                );
+            }
+            // A scalar root has no paths, so it can only be matched by an empty include list.
+            _ if active_include.is_some() => *self = Value::Null,
+            _ => {}
+        }
+    }
+}
+
+struct Patterns<'a> {
+    include: &'a [Vec<&'a str>],
+    exclude: &'a [Vec<&'a str>],
+}
+
+/// Applies the patterns to the children of `value`, which is at `depth` in the tree.
+///
+/// `include` are the indices of the include patterns, whose first `depth` segments match the
+/// path of `value`. `None` means the whole subtree is included. `exclude` are the indices of the
+/// matching exclude patterns.
+fn retain_children(
+    value: &mut Value,
+    depth: usize,
+    include: Option<&[usize]>,
+    exclude: &[usize],
+    patterns: &Patterns,
+) {
+    match value {
+        Value::Object(obj) => obj.0.retain_mut(|(key, child)| {
+            let key = key_str(key);
+            retain_child(child, depth, include, exclude, patterns, |segment| {
+                segment_matches(segment, key)
+            })
+        }),
+        Value::Array(arr) => {
+            let mut idx = 0;
+            arr.retain_mut(|child| {
+                let keep = retain_child(child, depth, include, exclude, patterns, |segment| {
+                    segment == "*" || segment.parse::<usize>() == Ok(idx)
+                });
+                idx += 1;
+                keep
+            })
+        }
+        _ => {}
+    }
+}
+
+/// Returns false if `child` should be removed.
+fn retain_child(
+    child: &mut Value,
+    depth: usize,
+    include: Option<&[usize]>,
+    exclude: &[usize],
+    patterns: &Patterns,
+    matches: impl Fn(&str) -> bool,
+) -> bool {
+    let advance = |active: &[usize], all: &[Vec<&str>]| -> (Vec<usize>, bool) {
+        let next: Vec<usize> = active
+            .iter()
+            .copied()
+            .filter(|idx| all[*idx].len() > depth && matches(all[*idx][depth]))
+            .collect();
+        let full_match = next.iter().any(|idx| all[*idx].len() == depth + 1);
+        (next, full_match)
+    };
+
+    let (exclude, excluded) = advance(exclude, patterns.exclude);
+    if excluded {
+        return false;
+    }
+    let include = match include {
+        None => None,
+        Some(include) => {
+            let (include, included) = advance(include, patterns.include);
+            if included {
+                None
+            } else if include.is_empty() {
+                return false;
+            } else {
+                Some(include)
+            }
+        }
+    };
+
+    if include.is_none() && exclude.is_empty() {
+        return true;
+    }
+    match child {
+        Value::Object(_) | Value::Array(_) => {
+            retain_children(child, depth + 1, include.as_deref(), &exclude, patterns);
+            // Only keep containers, which still contain included paths.
+            include.is_none() || !is_empty_container(child)
+        }
+        // The include patterns go deeper than this value.
+        _ => include.is_none(),
+    }
+}
+
+fn is_empty_container(value: &Value) -> bool {
+    match value {
+        Value::Object(obj) => obj.is_empty(),
+        Value::Array(arr) => arr.is_empty(),
+        _ => false,
+    }
+}
+
+/// Matches a pattern segment against a key. `*` matches any number of characters.
+fn segment_matches(pattern: &str, key: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = key.strip_prefix(first) else {
+        return false;
+    };
+    let mut parts = parts.peekable();
+    if parts.peek().is_none() {
+        // No wildcard in the pattern
+        return rest.is_empty();
+    }
+    while let Some(part) = parts.next() {
+        if parts.peek().is_none() {
+            // The last part has to match the end of the key
+            return rest.ends_with(part);
+        }
+        match rest.find(part) {
+            Some(pos) => rest = &rest[pos + part.len()..],
+            None => return false,
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(json: &str) -> Value<'_> {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn segment_matches_test() {
+        assert!(segment_matches("abc", "abc"));
+        assert!(!segment_matches("abc", "abcd"));
+        assert!(segment_matches("*", "abc"));
+        assert!(segment_matches("*", ""));
+        assert!(segment_matches("a*", "abc"));
+        assert!(segment_matches("*c", "abc"));
+        assert!(segment_matches("a*c", "abc"));
+        assert!(segment_matches("a*b*c", "abbbc"));
+        assert!(!segment_matches("a*b*c", "acb"));
+        assert!(!segment_matches("ab*bc", "abc"));
+    }
+
+    #[test]
+    fn filter_paths_include() {
+        let value =
+            parse(r#"{"a": {"b": 1, "c": {"b": 2, "d": 3}}, "e": [{"b": 4}, {"f": 5}], "g": 6}"#);
+
+        assert_eq!(value.filter_paths(&[], &[]), value);
+        assert_eq!(value.filter_paths(&["g"], &[]), parse(r#"{"g": 6}"#));
+        assert_eq!(
+            value.filter_paths(&["a.*.b"], &[]),
+            parse(r#"{"a": {"c": {"b": 2}}}"#)
+        );
+        assert_eq!(
+            value.filter_paths(&["e.*.b", "a.b"], &[]),
+            parse(r#"{"a": {"b": 1}, "e": [{"b": 4}]}"#)
+        );
+        assert_eq!(
+            value.filter_paths(&["e.1"], &[]),
+            parse(r#"{"e": [{"f": 5}]}"#)
+        );
+        assert_eq!(value.filter_paths(&["g.x"], &[]), parse("{}"));
+        assert_eq!(parse("1").filter_paths(&["a"], &[]), Value::Null);
+    }
+
+    #[test]
+    fn filter_paths_exclude() {
+        let mut value =
+            parse(r#"{"user_id": 1, "user_name": "x", "items": [{"price": 1, "id": 2}]}"#);
+        value.retain_paths(&[], &["user_*", "items.*.price"]);
+        assert_eq!(value, parse(r#"{"items": [{"id": 2}]}"#));
+
+        let value = parse(r#"{"a": {"b": 1, "c": 2}, "d": 3}"#);
+        assert_eq!(
+            value.filter_paths(&["a"], &["a.b"]),
+            parse(r#"{"a": {"c": 2}}"#)
+        );
+        assert_eq!(value.filter_paths(&["a.b"], &["a"]), parse("{}"));
+    }
+}
@@ -0,0 +1,193 @@
+use std::fmt::Write;
+
+use crate::object_vec::key_str;
+use crate::Value;
+
+/// How [`Value::flatten_with`] writes array indices into paths.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum IndexStyle {
+    /// Indices are separated like object keys, e.g. `a.0.b`.
+    #[default]
+    Separator,
+    /// Indices are written in brackets, e.g. `a[0].b`.
+    Brackets,
+}
+
+impl<'ctx> Value<'ctx> {
+    /// An iterator over all leaves of the value and their paths, e.g. `("a.b.0", &Value)`.
+    ///
+    /// Object keys and array indices are separated by `.`. See [`Value::flatten_with`] for
+    /// details.
+    ///
+    /// # Example
+    /// ```
+    /// use serde_json_borrow::Value;
+    ///
+    /// let value: Value =
+    ///     serde_json::from_str(r#"{"http": {"method": "GET", "ports": [80, 443]}}"#).unwrap();
+    /// let leaves: Vec<(String, String)> =
+    ///     value.flatten().map(|(path, leaf)| (path, leaf.to_string())).collect();
+    /// assert_eq!(
+    ///     leaves,
+    ///     vec![
+    ///         ("http.method".to_string(), "\"GET\"".to_string()),
+    ///         ("http.ports.0".to_string(), "80".to_string()),
+    ///         ("http.ports.1".to_string(), "443".to_string()),
+    ///     ]
+    /// );
+    /// ```
+    pub fn flatten<'a>(&'a self) -> impl Iterator<Item = (String, &'a Value<'ctx>)> + 'a {
+        self.flatten_with(".", IndexStyle::Separator)
+    }
+
+    /// An iterator over all leaves of the value and their paths in depth-first order.
+    ///
+    /// Leaves are `null`, booleans, numbers, strings and empty arrays or objects. Object keys
+    /// are joined with `key_separator` and not escaped. Array indices are written according to
+    /// `indices`. The path of a root scalar is empty.
+    ///
+    /// # Example
+    /// ```
+    /// use serde_json_borrow::{IndexStyle, Value};
+    ///
+    /// let value: Value = serde_json::from_str(r#"{"a": [{"b": 1}, []]}"#).unwrap();
+    /// let paths: Vec<String> =
+    ///     value.flatten_with("/", IndexStyle::Brackets).map(|(path, _)| path).collect();
+    /// assert_eq!(paths, vec!["a[0]/b", "a[1]"]);
+    /// ```
+    pub fn flatten_with<'a>(
+        &'a self,
+        key_separator: &'a str,
+        indices: IndexStyle,
+    ) -> impl Iterator<Item = (String, &'a Value<'ctx>)> + 'a {
+        let mut path = String::new();
+        // The entries are the length of the parent path, whether the parent is the root, the
+        // segment and the value.
+        let mut stack = vec![(0, true, Segment::Root, self)];
+        std::iter::from_fn(move || loop {
+            let (parent_len, parent_is_root, segment, value) = stack.pop()?;
+            path.truncate(parent_len);
+            match segment {
+                Segment::Root => {}
+                Segment::Key(key) => {
+                    if !parent_is_root {
+                        path.push_str(key_separator);
+                    }
+                    path.push_str(key);
+                }
+                Segment::Index(idx) => match indices {
+                    IndexStyle::Separator => {
+                        if !parent_is_root {
+                            path.push_str(key_separator);
+                        }
+                        write!(path, "{idx}").expect("writing to a String can't fail");
+                    }
+                    IndexStyle::Brackets => {
+                        write!(path, "[{idx}]").expect("writing to a String can't fail")
+                    }
+                },
+            }
+            match value {
+                Value::Array(arr) if !arr.is_empty() => {
+                    let len = path.len();
+                    let is_root = matches!(segment, Segment::Root);
+                    stack.extend(
+                        arr.iter()
+                            .enumerate()
+                            .rev()
+                            .map(|(idx, value)| (len, is_root, Segment::Index(idx), value)),
+                    );
+                }
+                Value::Object(obj) if !obj.is_empty() => {
+                    let len = path.len();
+                    let is_root = matches!(segment, Segment::Root);
+                    stack.extend(
+                        obj.0
+                            .iter()
+                            .rev()
+                            .map(|(key, value)| (len, is_root, Segment::Key(key_str(key)), value)),
+                    );
+                }
+                leaf => return Some((path.clone(), leaf)),
+            }
+        })
+    }
+}
+
+/// The last segment of the path of a node.
+enum Segment<'a> {
+    Root,
+    Key(&'a str),
+    Index(usize),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn flatten(json: &str, key_separator: &str, indices: IndexStyle) -> Vec<(String, String)> {
+        let value: Value = serde_json::from_str(json).unwrap();
+        value
+            .flatten_with(key_separator, indices)
+            .map(|(path, leaf)| (path, leaf.to_string()))
+            .collect()
+    }
+
+    fn pairs(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(path, leaf)| (path.to_string(), leaf.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn flatten_test() {
+        let json = r#"{"a": {"b": [1, {"c": null}], "d": {}}, "e": [[true], []], "f": "x"}"#;
+        assert_eq!(
+            flatten(json, ".", IndexStyle::Separator),
+            pairs(&[
+                ("a.b.0", "1"),
+                ("a.b.1.c", "null"),
+                ("a.d", "{}"),
+                ("e.0.0", "true"),
+                ("e.1", "[]"),
+                ("f", "\"x\""),
+            ])
+        );
+        assert_eq!(
+            flatten(json, "_", IndexStyle::Brackets),
+            pairs(&[
+                ("a_b[0]", "1"),
+                ("a_b[1]_c", "null"),
+                ("a_d", "{}"),
+                ("e[0][0]", "true"),
+                ("e[1]", "[]"),
+                ("f", "\"x\""),
+            ])
+        );
+    }
+
+    #[test]
+    fn flatten_root() {
+        assert_eq!(
+            flatten("1", ".", IndexStyle::Separator),
+            pairs(&[("", "1")])
+        );
+        assert_eq!(
+            flatten("{}", ".", IndexStyle::Separator),
+            pairs(&[("", "{}")])
+        );
+        assert_eq!(
+            flatten(r#"[1, [2]]"#, ".", IndexStyle::Separator),
+            pairs(&[("0", "1"), ("1.0", "2")])
+        );
+        assert_eq!(
+            flatten(r#"[1, [2]]"#, ".", IndexStyle::Brackets),
+            pairs(&[("[0]", "1"), ("[1][0]", "2")])
+        );
+        assert_eq!(
+            flatten(r#"{"": {"": 1}}"#, ".", IndexStyle::Separator),
+            pairs(&[(".", "1")])
+        );
+    }
+}
@@ -0,0 +1,215 @@
+use std::io::{self, Read};
+
+use crate::{OwnedValue, Value};
+
+/// The length prefix of a frame, see [`FrameReader`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FramePrefix {
+    /// A 4 byte big endian `u32`.
+    U32BigEndian,
+    /// A 4 byte little endian `u32`.
+    U32LittleEndian,
+    /// An unsigned LEB128 varint, like in protobuf.
+    Varint,
+}
+
+/// The default for [`FrameReader::with_max_frame_len`], 64 MiB.
+const DEFAULT_MAX_FRAME_LEN: usize = 64 * 1024 * 1024;
+
+/// Reads length-prefixed JSON documents from a reader, e.g. from an RPC protocol.
+///
+/// [`FrameReader::read_value`] reads each frame into the same buffer and returns a [`Value`]
+/// borrowing from it, so there is no allocation per frame for the data.
+/// [`FrameReader::read_owned`] reads each frame directly into the buffer of a new
+/// [`OwnedValue`], without an intermediate copy.
+///
+/// # Example
+/// ```
+/// use serde_json_borrow::{FramePrefix, FrameReader};
+///
+/// let mut data = Vec::new();
+/// for doc in [r#"{"id": 1}"#, r#"{"id": 2}"#] {
+///     data.extend_from_slice(&(doc.len() as u32).to_be_bytes());
+///     data.extend_from_slice(doc.as_bytes());
+/// }
+///
+/// let mut reader = FrameReader::new(data.as_slice(), FramePrefix::U32BigEndian);
+/// let mut ids = Vec::new();
+/// while let Some(value) = reader.read_value().unwrap() {
+///     ids.push(value.get("id").as_u64());
+/// }
+/// assert_eq!(ids, [Some(1), Some(2)]);
+/// ```
+#[derive(Debug)]
+pub struct FrameReader<R> {
+    reader: R,
+    prefix: FramePrefix,
+    max_frame_len: usize,
+    buf: Vec<u8>,
+}
+
+impl<R: Read> FrameReader<R> {
+    /// Creates a reader for frames with the given length prefix.
+    ///
+    /// The frames are read in small pieces, so `reader` should be buffered, e.g. by a
+    /// `BufReader`.
+    pub fn new(reader: R, prefix: FramePrefix) -> Self {
+        Self {
+            reader,
+            prefix,
+            max_frame_len: DEFAULT_MAX_FRAME_LEN,
+            buf: Vec::new(),
+        }
+    }
+
+    /// Sets the maximum length of a frame in bytes. Longer frames return an error, before
+    /// their data is read. The default is 64 MiB.
+    pub fn with_max_frame_len(mut self, max_frame_len: usize) -> Self {
+        self.max_frame_len = max_frame_len;
+        self
+    }
+
+    /// Reads the next frame into the reused buffer and parses it.
+    ///
+    /// Returns `None` at the end of the input. If the input ends within a frame, an
+    /// `UnexpectedEof` error is returned.
+    pub fn read_value(&mut self) -> io::Result<Option<Value<'_>>> {
+        let Some(len) = self.read_len()? else {
+            return Ok(None);
+        };
+        self.buf.clear();
+        self.buf.resize(len, 0);
+        self.reader.read_exact(&mut self.buf)?;
+        let json = std::str::from_utf8(&self.buf)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        Ok(Some(serde_json::from_str(json)?))
+    }
+
+    /// Reads the next frame into a new [`OwnedValue`].
+    ///
+    /// Returns `None` at the end of the input, see [`FrameReader::read_value`].
+    pub fn read_owned(&mut self) -> io::Result<Option<OwnedValue>> {
+        let Some(len) = self.read_len()? else {
+            return Ok(None);
+        };
+        let mut data = vec![0; len];
+        self.reader.read_exact(&mut data)?;
+        OwnedValue::from_vec(data).map(Some)
+    }
+
+    /// Returns the inner reader.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    /// Reads the length prefix. Returns `None` if the input ends before the prefix.
+    fn read_len(&mut self) -> io::Result<Option<usize>> {
+        let len = match self.prefix {
+            FramePrefix::U32BigEndian | FramePrefix::U32LittleEndian => {
+                let mut bytes = [0; 4];
+                if !self.read_first_byte(&mut bytes[0])? {
+                    return Ok(None);
+                }
+                self.reader.read_exact(&mut bytes[1..])?;
+                if self.prefix == FramePrefix::U32BigEndian {
+                    u32::from_be_bytes(bytes) as u64
+                } else {
+                    u32::from_le_bytes(bytes) as u64
+                }
+            }
+            FramePrefix::Varint => {
+                let mut byte = 0;
+                if !self.read_first_byte(&mut byte)? {
+                    return Ok(None);
+                }
+                let mut len = 0u64;
+                let mut shift = 0;
+                loop {
+                    if shift > 63 || (shift == 63 && byte > 1) {
+                        return Err(io::Error::new(
+                            io::ErrorKind::InvalidData,
+                            "varint length prefix overflows u64",
+                        ));
+                    }
+                    len |= u64::from(byte & 0x7f) << shift;
+                    if byte & 0x80 == 0 {
+                        break;
+                    }
+                    shift += 7;
+                    self.reader.read_exact(std::slice::from_mut(&mut byte))?;
+                }
+                len
+            }
+        };
+        match usize::try_from(len) {
+            Ok(len) if len <= self.max_frame_len => Ok(Some(len)),
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "frame of {len} bytes exceeds the maximum of {} bytes",
+                    self.max_frame_len
+                ),
+            )),
+        }
+    }
+
+    /// Reads the first byte of a prefix. Returns false at the end of the input.
+    fn read_first_byte(&mut self, byte: &mut u8) -> io::Result<bool> {
+        loop {
+            match self.reader.read(std::slice::from_mut(byte)) {
+                Ok(0) => return Ok(false),
+                Ok(_) => return Ok(true),
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                Err(err) => return Err(err),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn varint(mut len: usize, out: &mut Vec<u8>) {
+        while len >= 0x80 {
+            out.push(len as u8 | 0x80);
+            len >>= 7;
+        }
+        out.push(len as u8);
+    }
+
+    #[test]
+    fn frame_reader_varint() {
+        let long = format!("[{}]", vec!["1"; 100].join(","));
+        let mut data = Vec::new();
+        for doc in ["\"a\"", long.as_str(), "{}"] {
+            varint(doc.len(), &mut data);
+            data.extend_from_slice(doc.as_bytes());
+        }
+        let mut reader = FrameReader::new(data.as_slice(), FramePrefix::Varint);
+        assert_eq!(reader.read_value().unwrap().unwrap(), "a");
+        assert_eq!(reader.read_owned().unwrap().unwrap().get(99), &1_u64);
+        assert!(reader.read_value().unwrap().unwrap().is_object());
+        assert!(reader.read_value().unwrap().is_none());
+        assert!(reader.read_owned().unwrap().is_none());
+    }
+
+    #[test]
+    fn frame_reader_errors() {
+        let read = |data: &[u8], prefix| {
+            FrameReader::new(data, prefix)
+                .with_max_frame_len(16)
+                .read_value()
+                .map(|value| value.is_some())
+        };
+        assert!(read(&[2, 0, 0, 0, b'[', b']'], FramePrefix::U32LittleEndian).unwrap());
+        let truncated = read(&[0, 0, 0, 2, b'['], FramePrefix::U32BigEndian).unwrap_err();
+        assert_eq!(truncated.kind(), io::ErrorKind::UnexpectedEof);
+        assert!(read(&[0, 0], FramePrefix::U32BigEndian).is_err());
+        assert!(read(&[0, 0, 0, 17], FramePrefix::U32BigEndian).is_err());
+        assert!(read(&[0, 0, 0, 2, b'[', b'1'], FramePrefix::U32BigEndian).is_err());
+        assert!(read(&[0x80], FramePrefix::Varint).is_err());
+        assert!(read(&[0xff; 11], FramePrefix::Varint).is_err());
+        assert!(read(&[2, 0xff, 0xfe], FramePrefix::Varint).is_err());
+    }
+}
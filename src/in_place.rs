@@ -0,0 +1,501 @@
+use std::borrow::Cow;
+use std::io;
+
+use crate::value::parse_number;
+use crate::{ObjectAsVec, Value};
+
+/// Same as serde_json.
+const RECURSION_LIMIT: usize = 128;
+
+impl<'ctx> Value<'ctx> {
+    /// Parses JSON from a mutable buffer and unescapes strings in place, like simd-json.
+    ///
+    /// Escaped strings and keys are unescaped within `json`, so all strings and keys of the
+    /// returned `Value` borrow from the buffer and no string is allocated. Afterwards the buffer
+    /// contains the unescaped strings and can't be parsed again.
+    ///
+    /// Since keys are always borrowed, this also supports escaped keys without the `cowkeys`
+    /// feature flag.
+    ///
+    /// # Example
+    /// ```
+    /// use std::borrow::Cow;
+    ///
+    /// use serde_json_borrow::Value;
+    ///
+    /// let mut json = br#"{"text": "line\nbreak \u00e9"}"#.to_vec();
+    /// let value = Value::from_slice_in_place(&mut json).unwrap();
+    ///
+    /// assert!(matches!(value.get("text"), Value::Str(Cow::Borrowed("line\nbreak é"))));
+    /// ```
+    pub fn from_slice_in_place(json: &'ctx mut [u8]) -> io::Result<Value<'ctx>> {
+        Self::from_slice_in_place_non_finite(json, NonFiniteMode::Reject)
+    }
+
+    /// Same as [`Value::from_slice_in_place`], but handles the non-standard tokens `NaN`,
+    /// `Infinity` and `-Infinity` according to `mode`, e.g. for JSON written by Python's `json`
+    /// module.
+    ///
+    /// Non-finite numbers are written as `null` by the serializers, except by
+    /// [`crate::to_string_non_finite`].
+    ///
+    /// # Example
+    /// ```
+    /// use serde_json_borrow::{NonFiniteMode, Value};
+    ///
+    /// let mut json = br#"[NaN, -Infinity, 1.5]"#.to_vec();
+    /// let value = Value::from_slice_in_place_non_finite(&mut json, NonFiniteMode::Number).unwrap();
+    /// assert!(value.get(0).as_f64().unwrap().is_nan());
+    /// assert_eq!(value.get(1).as_f64(), Some(f64::NEG_INFINITY));
+    ///
+    /// let mut json = br#"[NaN, -Infinity, 1.5]"#.to_vec();
+    /// let value = Value::from_slice_in_place_non_finite(&mut json, NonFiniteMode::Null).unwrap();
+    /// assert_eq!(value, serde_json::json!([null, null, 1.5]));
+    /// ```
+    pub fn from_slice_in_place_non_finite(
+        json: &'ctx mut [u8],
+        mode: NonFiniteMode,
+    ) -> io::Result<Value<'ctx>> {
+        if let Err(err) = std::str::from_utf8(json) {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, err));
+        }
+        let mut parser = InPlaceParser {
+            rest: json,
+            offset: 0,
+            non_finite: mode,
+        };
+        let value = parser.parse_value(0)?;
+        parser.skip_whitespace();
+        if !parser.rest.is_empty() {
+            return Err(parser.error("trailing characters"));
+        }
+        Ok(value)
+    }
+}
+
+/// How the non-standard tokens `NaN`, `Infinity` and `-Infinity` are parsed, see
+/// [`Value::from_slice_in_place_non_finite`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum NonFiniteMode {
+    /// The tokens are invalid JSON and return an error.
+    #[default]
+    Reject,
+    /// The tokens are parsed into non-finite float numbers. They can be compared, sorted and
+    /// hashed, `NaN` is equal to itself and greater than all other numbers, see [`crate::Number`].
+    Number,
+    /// The tokens are parsed into `null`.
+    Null,
+}
+
+struct InPlaceParser<'ctx> {
+    /// The unparsed part of the input.
+    rest: &'ctx mut [u8],
+    /// Position of `rest` in the input, for error messages.
+    offset: usize,
+    non_finite: NonFiniteMode,
+}
+
+impl<'ctx> InPlaceParser<'ctx> {
+    fn error(&self, msg: &str) -> io::Error {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{msg} at position {}", self.offset),
+        )
+    }
+
+    #[inline]
+    fn peek(&self) -> Option<u8> {
+        self.rest.first().copied()
+    }
+
+    /// Splits off and returns the next `len` bytes.
+    #[inline]
+    fn consume(&mut self, len: usize) -> &'ctx mut [u8] {
+        let (head, tail) = std::mem::take(&mut self.rest).split_at_mut(len);
+        self.rest = tail;
+        self.offset += len;
+        head
+    }
+
+    #[inline]
+    fn skip_whitespace(&mut self) {
+        let len = self
+            .rest
+            .iter()
+            .position(|b| !matches!(b, b' ' | b'\t' | b'\n' | b'\r'))
+            .unwrap_or(self.rest.len());
+        self.consume(len);
+    }
+
+    fn expect(&mut self, expected: u8) -> io::Result<()> {
+        self.skip_whitespace();
+        if self.peek() != Some(expected) {
+            return Err(self.error(&format!("expected `{}`", expected as char)));
+        }
+        self.consume(1);
+        Ok(())
+    }
+
+    fn parse_value(&mut self, depth: usize) -> io::Result<Value<'ctx>> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(b'n') => self.parse_literal(b"null", Value::Null),
+            Some(b't') => self.parse_literal(b"true", Value::Bool(true)),
+            Some(b'f') => self.parse_literal(b"false", Value::Bool(false)),
+            Some(b'"') => Ok(Value::Str(Cow::Borrowed(self.parse_str()?))),
+            Some(b'N') => self.parse_non_finite(b"NaN", f64::NAN),
+            Some(b'I') => self.parse_non_finite(b"Infinity", f64::INFINITY),
+            Some(b'-') if self.rest.get(1) == Some(&b'I') => {
+                self.parse_non_finite(b"-Infinity", f64::NEG_INFINITY)
+            }
+            Some(b'-' | b'0'..=b'9') => self.parse_number(),
+            Some(b'[') => self.parse_array(depth + 1),
+            Some(b'{') => self.parse_object(depth + 1),
+            Some(_) => Err(self.error("expected value")),
+            None => Err(self.error("EOF while parsing a value")),
+        }
+    }
+
+    fn parse_literal(&mut self, literal: &[u8], value: Value<'ctx>) -> io::Result<Value<'ctx>> {
+        if !self.rest.starts_with(literal) {
+            return Err(self.error("expected value"));
+        }
+        self.consume(literal.len());
+        Ok(value)
+    }
+
+    fn parse_non_finite(&mut self, literal: &[u8], value: f64) -> io::Result<Value<'ctx>> {
+        match self.non_finite {
+            NonFiniteMode::Reject => Err(self.error("expected value")),
+            NonFiniteMode::Number => self.parse_literal(literal, Value::Number(value.into())),
+            NonFiniteMode::Null => self.parse_literal(literal, Value::Null),
+        }
+    }
+
+    fn parse_number(&mut self) -> io::Result<Value<'ctx>> {
+        let len = self
+            .rest
+            .iter()
+            .position(|b| !matches!(b, b'-' | b'+' | b'.' | b'e' | b'E' | b'0'..=b'9'))
+            .unwrap_or(self.rest.len());
+        let token = std::str::from_utf8(&self.rest[..len]).expect("ASCII is valid UTF-8");
+        let number = parse_number(token).ok_or_else(|| self.error("invalid number"))?;
+        self.consume(len);
+        Ok(Value::Number(number))
+    }
+
+    fn parse_array(&mut self, depth: usize) -> io::Result<Value<'ctx>> {
+        if depth > RECURSION_LIMIT {
+            return Err(self.error("recursion limit exceeded"));
+        }
+        self.consume(1);
+        let mut arr = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some(b']') {
+            self.consume(1);
+            return Ok(Value::Array(arr));
+        }
+        loop {
+            arr.push(self.parse_value(depth)?);
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => {
+                    self.consume(1);
+                }
+                Some(b']') => {
+                    self.consume(1);
+                    return Ok(Value::Array(arr));
+                }
+                _ => return Err(self.error("expected `,` or `]`")),
+            }
+        }
+    }
+
+    #[allow(clippy::useless_conversion)]
+    fn parse_object(&mut self, depth: usize) -> io::Result<Value<'ctx>> {
+        if depth > RECURSION_LIMIT {
+            return Err(self.error("recursion limit exceeded"));
+        }
+        self.consume(1);
+        let mut entries = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some(b'}') {
+            self.consume(1);
+            return Ok(Value::Object(ObjectAsVec::from_entries(entries)));
+        }
+        loop {
+            self.skip_whitespace();
+            if self.peek() != Some(b'"') {
+                return Err(self.error("key must be a string"));
+            }
+            let key = self.parse_str()?;
+            self.expect(b':')?;
+            let value = self.parse_value(depth)?;
+            entries.push((key.into(), value));
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => {
+                    self.consume(1);
+                }
+                Some(b'}') => {
+                    self.consume(1);
+                    return Ok(Value::Object(ObjectAsVec::from_entries(entries)));
+                }
+                _ => return Err(self.error("expected `,` or `}`")),
+            }
+        }
+    }
+
+    /// Parses the string at the start of `rest` and unescapes it in place.
+    fn parse_str(&mut self) -> io::Result<&'ctx str> {
+        // Skip the opening quote
+        let mut read = 1;
+        // Fast path for strings without escapes
+        loop {
+            match self.rest.get(read) {
+                Some(b'"') => {
+                    let token: &'ctx [u8] = self.consume(read + 1);
+                    return Ok(to_str(&token[1..read]));
+                }
+                Some(b'\\') => break,
+                Some(0..=0x1f) => return Err(self.error("control character in string")),
+                Some(_) => read += 1,
+                None => return Err(self.error("EOF while parsing a string")),
+            }
+        }
+
+        let buf = &mut *self.rest;
+        let mut write = read;
+        loop {
+            match buf.get(read).copied() {
+                Some(b'"') => break,
+                Some(b'\\') => {
+                    let escape = buf.get(read + 1).copied();
+                    read += 2;
+                    let unescaped = match escape {
+                        Some(b'"') => b'"',
+                        Some(b'\\') => b'\\',
+                        Some(b'/') => b'/',
+                        Some(b'b') => b'\x08',
+                        Some(b'f') => b'\x0c',
+                        Some(b'n') => b'\n',
+                        Some(b'r') => b'\r',
+                        Some(b't') => b'\t',
+                        Some(b'u') => {
+                            let c = decode_unicode_escape(buf, &mut read).ok_or_else(|| {
+                                io::Error::new(
+                                    io::ErrorKind::InvalidData,
+                                    format!(
+                                        "invalid unicode escape at position {}",
+                                        self.offset + read
+                                    ),
+                                )
+                            })?;
+                            // The encoded char is shorter than the escape, so this only
+                            // overwrites already read bytes.
+                            let mut encoded = [0u8; 4];
+                            let encoded = c.encode_utf8(&mut encoded).as_bytes();
+                            buf[write..write + encoded.len()].copy_from_slice(encoded);
+                            write += encoded.len();
+                            continue;
+                        }
+                        _ => {
+                            return Err(io::Error::new(
+                                io::ErrorKind::InvalidData,
+                                format!("invalid escape at position {}", self.offset + read),
+                            ))
+                        }
+                    };
+                    buf[write] = unescaped;
+                    write += 1;
+                }
+                Some(0..=0x1f) => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!(
+                            "control character in string at position {}",
+                            self.offset + read
+                        ),
+                    ))
+                }
+                Some(b) => {
+                    buf[write] = b;
+                    write += 1;
+                    read += 1;
+                }
+                None => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!(
+                            "EOF while parsing a string at position {}",
+                            self.offset + read
+                        ),
+                    ))
+                }
+            }
+        }
+        let token: &'ctx [u8] = self.consume(read + 1);
+        Ok(to_str(&token[1..write]))
+    }
+}
+
+/// Decodes the hex digits of a `\u` escape starting at `pos` and a following low surrogate
+/// escape if required. Advances `pos` behind the consumed bytes.
+fn decode_unicode_escape(buf: &[u8], pos: &mut usize) -> Option<char> {
+    let high = parse_hex4(buf, *pos)?;
+    *pos += 4;
+    match high {
+        0xD800..=0xDBFF => {
+            if buf.get(*pos..*pos + 2)? != b"\\u" {
+                return None;
+            }
+            let low = parse_hex4(buf, *pos + 2)?;
+            if !(0xDC00..=0xDFFF).contains(&low) {
+                return None;
+            }
+            *pos += 6;
+            let c = 0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00);
+            char::from_u32(c)
+        }
+        // Lone low surrogate
+        0xDC00..=0xDFFF => None,
+        _ => char::from_u32(high),
+    }
+}
+
+fn parse_hex4(buf: &[u8], pos: usize) -> Option<u32> {
+    let hex = std::str::from_utf8(buf.get(pos..pos + 4)?).ok()?;
+    if !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    u32::from_str_radix(hex, 16).ok()
+}
+
+fn to_str(bytes: &[u8]) -> &str {
+    // SAFETY: The input is validated as UTF-8 before parsing. Strings are delimited by ASCII
+    // quotes and escapes consist of ASCII characters, so the bytes between them are complete
+    // UTF-8 sequences, which are either kept or moved as a whole. Escapes are replaced by the
+    // UTF-8 encoding of the escaped char.
+    unsafe { std::str::from_utf8_unchecked(bytes) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(json: &str) -> io::Result<Value<'static>> {
+        let buf: &'static mut [u8] = Box::leak(json.as_bytes().to_vec().into_boxed_slice());
+        Value::from_slice_in_place(buf)
+    }
+
+    #[test]
+    fn in_place_same_as_serde_json() {
+        let json = r#"
+            {
+                "null": null, "bool": [true, false],
+                "numbers": [0, -0, 1, -1, 1.5, -1.5e-3, 1E2, 18446744073709551615, -9223372036854775808,
+                    18446744073709551616, -9223372036854775809, 0.1e1],
+                "strings": ["", "plain", "é😀", "\"\\\/\b\f\n\r\t", "\u00e9\ud83d\ude00\u0041"],
+                "nested": {"a": {"b": [[], {}]}}
+            }
+        "#;
+        let value = parse(json).unwrap();
+        let expected: serde_json::Value = serde_json::from_str(json).unwrap();
+        assert_eq!(value, expected);
+        let expected: Value = serde_json::from_str(json).unwrap();
+        assert_eq!(value, expected);
+    }
+
+    #[test]
+    fn in_place_borrows_escaped_strings() {
+        let value = parse(r#"{"k\ney": "va\"lue", "arr": ["\u0041b"]}"#).unwrap();
+        let obj = value.as_object().unwrap();
+        assert_eq!(obj.get("k\ney"), Some(&Value::from("va\"lue")));
+        assert!(matches!(
+            value.get("k\ney"),
+            Value::Str(Cow::Borrowed("va\"lue"))
+        ));
+        assert!(matches!(
+            value.get("arr").get(0),
+            Value::Str(Cow::Borrowed("Ab"))
+        ));
+    }
+
+    #[test]
+    fn in_place_errors() {
+        for json in [
+            "",
+            "nul",
+            "[1,]",
+            "[1 2]",
+            "{\"a\" 1}",
+            "{\"a\": 1,}",
+            "{1: 1}",
+            "\"abc",
+            "\"a\\x\"",
+            "\"\\ud800\"",
+            "\"\\udc00\"",
+            "\"\\u12g4\"",
+            "\"a\nb\"",
+            "01",
+            "1.",
+            "-",
+            "1e",
+            "1e400",
+            "[] x",
+            "{\"a\": 1} {}",
+        ] {
+            assert!(parse(json).is_err(), "{json:?} should fail");
+        }
+        let deep = "[".repeat(200) + &"]".repeat(200);
+        assert!(parse(&deep).is_err());
+        assert!(Value::from_slice_in_place(&mut [b'"', 0xff, b'"']).is_err());
+    }
+
+    #[test]
+    fn in_place_non_finite() {
+        let json = r#"{"inf": [Infinity, -Infinity, -1], "nan": NaN}"#;
+        assert!(parse(json).is_err());
+
+        let mut buf = json.as_bytes().to_vec();
+        let value = Value::from_slice_in_place_non_finite(&mut buf, NonFiniteMode::Number).unwrap();
+        assert!(value.get("nan").as_f64().unwrap().is_nan());
+        assert_eq!(value.get(("inf", 0)).as_f64(), Some(f64::INFINITY));
+        assert_eq!(value.get(("inf", 1)).as_f64(), Some(f64::NEG_INFINITY));
+        assert_eq!(
+            crate::to_string_non_finite(&value),
+            r#"{"inf":[Infinity,-Infinity,-1],"nan":NaN}"#
+        );
+
+        let mut buf = json.as_bytes().to_vec();
+        let value = Value::from_slice_in_place_non_finite(&mut buf, NonFiniteMode::Null).unwrap();
+        assert_eq!(
+            value,
+            serde_json::json!({"nan": null, "inf": [null, null, -1]})
+        );
+
+        // Values with non-finite numbers can be compared, sorted and hashed.
+        let json = "[NaN, Infinity, 1, -Infinity, NaN, 2.5]";
+        let mut buf = json.as_bytes().to_vec();
+        let value = Value::from_slice_in_place_non_finite(&mut buf, NonFiniteMode::Number).unwrap();
+        let mut values = value.as_array().unwrap().to_vec();
+        assert_eq!(values[0], values[4]);
+        assert_eq!(values[0].cmp(&values[1]), std::cmp::Ordering::Greater);
+        values.sort();
+        assert_eq!(
+            crate::to_string_non_finite(&Value::Array(values)),
+            "[-Infinity,1,2.5,Infinity,NaN,NaN]"
+        );
+        let unique: std::collections::HashSet<_> = value.as_array().unwrap().iter().collect();
+        assert_eq!(unique.len(), 5);
+        let mut buf = json.as_bytes().to_vec();
+        let other = Value::from_slice_in_place_non_finite(&mut buf, NonFiniteMode::Number).unwrap();
+        assert_eq!(value, other);
+
+        for json in ["nan", "Inf", "-Infinit", "+Infinity", "NaNa"] {
+            let mut buf = json.as_bytes().to_vec();
+            let result = Value::from_slice_in_place_non_finite(&mut buf, NonFiniteMode::Number);
+            assert!(result.is_err(), "{json:?} should fail");
+        }
+    }
+}
@@ -1,16 +1,23 @@
+use std::ops;
+
 use super::Value;
+use crate::value::type_name;
+use crate::ObjectAsVec;
 
 /// A type that can be used to index into a `serde_json_borrow::Value`.
 ///
-/// [`get`] of `Value` accept any type that implements `Index`. This
-/// trait is implemented for strings which are used as the index into a JSON
-/// map, and for `usize` which is used as the index into a JSON array.
+/// [`get`] and [`get_mut`] of `Value` accept any type that implements `Index`.
+/// This trait is implemented for strings which are used as the index into a
+/// JSON map, and for `usize` which is used as the index into a JSON array.
+/// Tuples of up to 8 indices resolve a whole path in one call, e.g.
+/// `value.get(("a", 0, "b"))` is the same as `value.get("a").get(0).get("b")`.
 ///
 /// [`get`]: ../enum.Value.html#method.get
+/// [`get_mut`]: ../enum.Value.html#method.get_mut
 ///
 /// This trait is sealed and cannot be implemented for types outside of
 /// `serde_json_borrow`.
-
+///
 /// # Examples
 ///
 /// ```
@@ -32,29 +39,419 @@ use super::Value;
 ///
 /// assert_eq!(data.get("a"), &Value::Null);
 /// assert_eq!(data.get("a").get("b"), &Value::Null);
+///
+/// assert_eq!(data.get(("x", "y", 1)), &Value::Str(std::borrow::Cow::Borrowed("zz")));
+/// assert_eq!(data.get(("x", 0)), &Value::Null);
 /// ```
-pub trait Index<'v> {
+pub trait Index<'ctx> {
     /// Return None if the key is not already in the array or object.
     #[doc(hidden)]
-    fn index_into(self, v: &'v Value<'v>) -> Option<&Value<'v>>;
+    fn index_into<'v>(self, v: &'v Value<'ctx>) -> Option<&'v Value<'ctx>>;
+
+    /// Return None if the key is not already in the array or object.
+    #[doc(hidden)]
+    fn index_into_mut<'v>(self, v: &'v mut Value<'ctx>) -> Option<&'v mut Value<'ctx>>;
+
+    /// Panic if array index out of bounds. If key is not already in the object,
+    /// insert it with a value of null. Panic if `v` is a type that cannot be
+    /// indexed into, except if `v` is null then it can be treated as an empty
+    /// object.
+    #[doc(hidden)]
+    fn index_or_insert<'v>(self, v: &'v mut Value<'ctx>) -> &'v mut Value<'ctx>;
+
+    /// Like `index_into`, but tells why the value was not found.
+    #[doc(hidden)]
+    fn lookup_into<'v>(self, v: &'v Value<'ctx>) -> Lookup<'v, 'ctx>;
 }
 
-impl<'v> Index<'v> for usize {
+/// The result of [`Value::lookup`], which tells why a value was not found.
+///
+/// Unlike [`Value::get`], this distinguishes a missing value from a `null` value that is present.
+///
+/// # Examples
+///
+/// ```
+/// # use serde_json_borrow::{Lookup, Value};
+/// #
+/// let data: Value = serde_json::from_str(r#"{"a": null, "b": [1]}"#).unwrap();
+///
+/// assert_eq!(data.lookup("a"), Lookup::Found(&Value::Null));
+/// assert_eq!(data.lookup("x"), Lookup::MissingKey);
+/// assert_eq!(data.lookup(("b", 1)), Lookup::IndexOutOfBounds);
+/// assert_eq!(data.lookup(("a", "c")), Lookup::WrongType);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Lookup<'v, 'ctx> {
+    /// The value was found, it may be `null`.
+    Found(&'v Value<'ctx>),
+    /// The key does not exist in the object.
+    MissingKey,
+    /// The index is not within the bounds of the array.
+    IndexOutOfBounds,
+    /// A key was used on a value that is not an object, or an index on a value that is not an
+    /// array.
+    WrongType,
+}
+
+impl<'v, 'ctx> Lookup<'v, 'ctx> {
+    /// Returns the value, if it was found.
     #[inline]
-    fn index_into(self, v: &'v Value<'v>) -> Option<&Value<'v>> {
+    pub fn found(self) -> Option<&'v Value<'ctx>> {
+        match self {
+            Lookup::Found(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Returns true if the value was found.
+    #[inline]
+    pub fn is_found(&self) -> bool {
+        matches!(self, Lookup::Found(_))
+    }
+}
+
+impl<'ctx> Index<'ctx> for usize {
+    #[inline]
+    fn index_into<'v>(self, v: &'v Value<'ctx>) -> Option<&'v Value<'ctx>> {
         match v {
             Value::Array(vec) => vec.get(self),
             _ => None,
         }
     }
+
+    #[inline]
+    fn index_into_mut<'v>(self, v: &'v mut Value<'ctx>) -> Option<&'v mut Value<'ctx>> {
+        match v {
+            Value::Array(vec) => vec.get_mut(self),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    fn index_or_insert<'v>(self, v: &'v mut Value<'ctx>) -> &'v mut Value<'ctx> {
+        match v {
+            Value::Array(vec) => {
+                let len = vec.len();
+                vec.get_mut(self).unwrap_or_else(|| {
+                    panic!(
+                        "cannot access index {} of JSON array of length {}",
+                        self, len
+                    )
+                })
+            }
+            _ => panic!("cannot access index {} of JSON {}", self, type_name(v)),
+        }
+    }
+
+    #[inline]
+    fn lookup_into<'v>(self, v: &'v Value<'ctx>) -> Lookup<'v, 'ctx> {
+        match v {
+            Value::Array(vec) => vec
+                .get(self)
+                .map_or(Lookup::IndexOutOfBounds, Lookup::Found),
+            _ => Lookup::WrongType,
+        }
+    }
 }
 
-impl<'v, 'a: 'v> Index<'v> for &'a str {
+impl<'ctx> Index<'ctx> for &str {
     #[inline]
-    fn index_into(self, v: &'v Value<'v>) -> Option<&Value<'v>> {
+    fn index_into<'v>(self, v: &'v Value<'ctx>) -> Option<&'v Value<'ctx>> {
         match v {
-            Value::Object(map) => map.iter().find(|(k, _v)| k == &self).map(|(_k, v)| v),
+            Value::Object(map) => map.get(self),
             _ => None,
         }
     }
+
+    #[inline]
+    fn index_into_mut<'v>(self, v: &'v mut Value<'ctx>) -> Option<&'v mut Value<'ctx>> {
+        match v {
+            Value::Object(map) => map.get_mut(self),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    fn index_or_insert<'v>(self, v: &'v mut Value<'ctx>) -> &'v mut Value<'ctx> {
+        if let Value::Null = v {
+            *v = Value::Object(ObjectAsVec::default());
+        }
+        match v {
+            Value::Object(map) => map.get_mut_or_insert_null(self),
+            _ => panic!("cannot access key {:?} in JSON {}", self, type_name(v)),
+        }
+    }
+
+    #[inline]
+    fn lookup_into<'v>(self, v: &'v Value<'ctx>) -> Lookup<'v, 'ctx> {
+        match v {
+            Value::Object(map) => map.get(self).map_or(Lookup::MissingKey, Lookup::Found),
+            _ => Lookup::WrongType,
+        }
+    }
+}
+
+// Tuples of indices resolve a path, e.g. `("a", 0)` is the same as indexing by "a" and then 0.
+macro_rules! impl_index_tuple {
+    ($($name:ident)+) => {
+        impl<'ctx, $($name: Index<'ctx>),+> Index<'ctx> for ($($name,)+) {
+            #[inline]
+            #[allow(non_snake_case)]
+            fn index_into<'v>(self, v: &'v Value<'ctx>) -> Option<&'v Value<'ctx>> {
+                let ($($name,)+) = self;
+                $(let v = $name.index_into(v)?;)+
+                Some(v)
+            }
+
+            #[inline]
+            #[allow(non_snake_case)]
+            fn index_into_mut<'v>(self, v: &'v mut Value<'ctx>) -> Option<&'v mut Value<'ctx>> {
+                let ($($name,)+) = self;
+                $(let v = $name.index_into_mut(v)?;)+
+                Some(v)
+            }
+
+            #[inline]
+            #[allow(non_snake_case)]
+            fn index_or_insert<'v>(self, v: &'v mut Value<'ctx>) -> &'v mut Value<'ctx> {
+                let ($($name,)+) = self;
+                $(let v = $name.index_or_insert(v);)+
+                v
+            }
+
+            #[inline]
+            #[allow(non_snake_case)]
+            fn lookup_into<'v>(self, v: &'v Value<'ctx>) -> Lookup<'v, 'ctx> {
+                let ($($name,)+) = self;
+                $(let v = match $name.lookup_into(v) {
+                    Lookup::Found(v) => v,
+                    not_found => return not_found,
+                };)+
+                Lookup::Found(v)
+            }
+        }
+    };
+}
+
+impl_index_tuple!(A);
+impl_index_tuple!(A B);
+impl_index_tuple!(A B C);
+impl_index_tuple!(A B C D);
+impl_index_tuple!(A B C D E);
+impl_index_tuple!(A B C D E F);
+impl_index_tuple!(A B C D E F G);
+impl_index_tuple!(A B C D E F G H);
+
+/// Index into a `serde_json_borrow::Value` using the syntax `value[0]` or `value["k"]`.
+///
+/// Returns `Value::Null` if the type of `self` does not match the type of the
+/// index, for example if the index is a string and `self` is an array or a
+/// number. Also returns `Value::Null` if the given key does not exist in the map
+/// or the given index is not within the bounds of the array.
+///
+/// For retrieving deeply nested values, you should have a look at the
+/// `Value::pointer` method.
+///
+/// # Examples
+///
+/// ```
+/// # use serde_json_borrow::Value;
+/// #
+/// let data: Value = serde_json::from_str(r#"{"x": {"y": ["z", "zz"]}}"#).unwrap();
+///
+/// assert_eq!(data["x"]["y"], Value::from(vec!["z", "zz"]));
+/// assert_eq!(data["x"]["y"][0], Value::from("z"));
+///
+/// assert_eq!(data["a"], Value::Null); // returns null for undefined values
+/// assert_eq!(data["a"]["b"], Value::Null); // does not panic
+/// ```
+impl<'ctx, I: Index<'ctx>> ops::Index<I> for Value<'ctx> {
+    type Output = Value<'ctx>;
+
+    #[inline]
+    fn index(&self, index: I) -> &Value<'ctx> {
+        self.get(index)
+    }
+}
+
+/// Write into a `serde_json_borrow::Value` using the syntax `value[0] = ...` or
+/// `value["k"] = ...`.
+///
+/// If the index is a number, the value must be an array of length bigger than
+/// the index. Indexing into a value that is not an array or an array that is
+/// too small will panic.
+///
+/// If the index is a string, the value must be an object or null which is
+/// treated like an empty object. If the key is not already present in the
+/// object, it will be inserted with a value of null. Indexing into a value that
+/// is neither an object nor null will panic.
+///
+/// Inserted keys are copied into an owned key, so this requires the `cowkeys`
+/// feature flag. Without it, only arrays can be written by index, see
+/// [`ObjectAsVec::insert`] to insert borrowed keys.
+///
+/// # Examples
+///
+/// ```
+/// # use serde_json_borrow::Value;
+/// #
+/// let mut data: Value = serde_json::from_str(r#"{"x": 0}"#).unwrap();
+///
+/// // replace an existing key
+/// data["x"] = Value::from(1u64);
+///
+/// // insert a key
+/// data["y"] = Value::from(vec![false, false, false]);
+///
+/// // replace an array value
+/// data["y"][0] = Value::Bool(true);
+///
+/// // inserted a deeply nested key
+/// data["a"]["b"]["c"]["d"] = Value::Bool(true);
+///
+/// # #[cfg(not(feature = "btree"))]
+/// assert_eq!(
+///     serde_json::to_string(&data).unwrap(),
+///     r#"{"x":1,"y":[true,false,false],"a":{"b":{"c":{"d":true}}}}"#
+/// );
+/// # #[cfg(feature = "btree")]
+/// # assert_eq!(
+/// #     serde_json::to_string(&data).unwrap(),
+/// #     r#"{"a":{"b":{"c":{"d":true}}},"x":1,"y":[true,false,false]}"#
+/// # );
+/// ```
+#[cfg(feature = "cowkeys")]
+impl<'ctx, I: Index<'ctx>> ops::IndexMut<I> for Value<'ctx> {
+    #[inline]
+    fn index_mut(&mut self, index: I) -> &mut Value<'ctx> {
+        index.index_or_insert(self)
+    }
+}
+
+/// Write into an array of a `serde_json_borrow::Value` using the syntax `value[0] = ...`.
+///
+/// Indexing into a value that is not an array or an array that is too small will panic.
+///
+/// Writing by key requires the `cowkeys` feature flag, as inserted keys are owned:
+///
+/// ```compile_fail
+/// # use serde_json_borrow::Value;
+/// let mut data: Value = serde_json::from_str(r#"{"x": 0}"#).unwrap();
+/// data["x"] = Value::from(1u64);
+/// ```
+#[cfg(not(feature = "cowkeys"))]
+impl<'ctx> ops::IndexMut<usize> for Value<'ctx> {
+    #[inline]
+    fn index_mut(&mut self, index: usize) -> &mut Value<'ctx> {
+        index.index_or_insert(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Lookup, Value};
+
+    #[test]
+    fn index_operator() {
+        let data: Value = serde_json::from_str(r#"{"a": [1, {"b": true}], "c": null}"#).unwrap();
+
+        assert_eq!(data["a"][0], Value::from(1u64));
+        assert_eq!(data["a"][1]["b"], Value::Bool(true));
+        assert_eq!(data["c"], Value::Null);
+        assert_eq!(data["missing"]["deeper"][3], Value::Null);
+        assert_eq!(data[0], Value::Null);
+        assert_eq!(data["a"][5], Value::Null);
+    }
+
+    #[cfg(not(feature = "cowkeys"))]
+    #[test]
+    fn index_mut_operator_without_cowkeys() {
+        let mut data: Value = serde_json::from_str(r#"[1, {"a": true}]"#).unwrap();
+        data[0] = Value::from(2u64);
+        assert_eq!(serde_json::to_string(&data).unwrap(), r#"[2,{"a":true}]"#);
+    }
+
+    #[cfg(feature = "cowkeys")]
+    #[test]
+    fn index_mut_operator() {
+        let mut data: Value =
+            serde_json::from_str(r#"{"a": [1, {"b": true}], "c": null}"#).unwrap();
+
+        data["a"][0] = Value::from(2u64);
+        data["a"][1]["b"] = Value::Bool(false);
+        assert_eq!(
+            serde_json::to_string(&data).unwrap(),
+            r#"{"a":[2,{"b":false}],"c":null}"#
+        );
+    }
+
+    #[cfg(feature = "cowkeys")]
+    #[test]
+    fn index_mut_operator_inserts() {
+        let mut data: Value = serde_json::from_str(r#"{"a": 1, "c": null}"#).unwrap();
+
+        let key = String::from("new");
+        data[key.as_str()] = Value::from("value");
+        data["c"]["d"]["e"] = Value::from(3u64);
+        assert_eq!(
+            serde_json::to_string(&data).unwrap(),
+            r#"{"a":1,"c":{"d":{"e":3}},"new":"value"}"#
+        );
+    }
+
+    #[test]
+    fn index_tuple() {
+        let mut data: Value =
+            serde_json::from_str(r#"{"a": [1, {"b": [true, null]}], "c": null}"#).unwrap();
+
+        assert_eq!(data.get(("a",)), &data["a"]);
+        assert_eq!(data.get(("a", 1, "b", 0)), &Value::Bool(true));
+        assert_eq!(data[("a", 0)], Value::from(1u64));
+        assert_eq!(data.get(("a", 1, "x", 0)), &Value::Null);
+        assert_eq!(data.get_mut(("a", 5)), None);
+
+        *data.get_mut(("a", 1, "b", 1)).unwrap() = Value::from("x");
+        #[cfg(feature = "cowkeys")]
+        {
+            data[("a", 0)] = Value::Bool(false);
+        }
+        #[cfg(not(feature = "cowkeys"))]
+        {
+            *data.get_mut(("a", 0)).unwrap() = Value::Bool(false);
+        }
+        assert_eq!(
+            serde_json::to_string(&data).unwrap(),
+            r#"{"a":[false,{"b":[true,"x"]}],"c":null}"#
+        );
+    }
+
+    #[test]
+    fn lookup() {
+        let data: Value = serde_json::from_str(r#"{"a": [1, null], "c": null}"#).unwrap();
+
+        assert_eq!(data.lookup("c"), Lookup::Found(&Value::Null));
+        assert_eq!(data.lookup(("a", 1)).found(), Some(&Value::Null));
+        assert_eq!(data.lookup("missing"), Lookup::MissingKey);
+        assert_eq!(data.lookup(("a", 2)), Lookup::IndexOutOfBounds);
+        assert_eq!(data.lookup(0), Lookup::WrongType);
+        assert_eq!(data.lookup(("a", "b")), Lookup::WrongType);
+        assert_eq!(data.lookup(("missing", 0, "b")), Lookup::MissingKey);
+        assert!(data.lookup(("a", 0)).is_found());
+        assert!(!Value::Null.lookup("a").is_found());
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot access index 2 of JSON array of length 1")]
+    fn index_mut_out_of_bounds() {
+        let mut data: Value = serde_json::from_str("[1]").unwrap();
+        data[2] = Value::Null;
+    }
+
+    #[cfg(feature = "cowkeys")]
+    #[test]
+    #[should_panic(expected = "cannot access key \"a\" in JSON number")]
+    fn index_mut_type_mismatch() {
+        let mut data: Value = serde_json::from_str("1").unwrap();
+        data["a"] = Value::Null;
+    }
 }
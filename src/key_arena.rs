@@ -0,0 +1,139 @@
+use std::cell::RefCell;
+use std::fmt::{self, Write};
+
+/// The minimum capacity of a chunk, so short keys share an allocation.
+const CHUNK_CAPACITY: usize = 1024;
+
+/// Pooled storage for generated object keys.
+///
+/// Keys of a [`crate::Value`] are borrowed, so keys generated at runtime, e.g. with
+/// `format!("field_{i}")`, need to be stored somewhere. Instead of allocating a `String` per key,
+/// `KeyArena` formats the keys into shared chunks and hands out `&str` referencing them, which can
+/// be used as [`crate::KeyStrType`]. The keys live as long as the arena.
+///
+/// Memory is only released when the arena is dropped.
+///
+/// # Example
+/// ```
+/// use serde_json_borrow::{KeyArena, ObjectAsVec, Value};
+///
+/// let arena = KeyArena::new();
+/// let mut obj = ObjectAsVec::default();
+/// for i in 0..3 {
+///     obj.insert(arena.alloc_fmt(format_args!("field_{i}")), Value::from(i as u64));
+/// }
+/// assert_eq!(obj.get("field_2"), Some(&Value::from(2u64)));
+/// assert_eq!(obj.len(), 3);
+/// ```
+#[derive(Debug, Default)]
+pub struct KeyArena {
+    /// The chunks never grow beyond their capacity, so their contents are never moved.
+    chunks: RefCell<Vec<String>>,
+    /// Reused buffer for formatting keys of unknown length.
+    scratch: RefCell<String>,
+}
+
+impl KeyArena {
+    /// Creates an empty arena. No memory is allocated until the first key is stored.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Copies `key` into the arena.
+    pub fn alloc(&self, key: &str) -> &str {
+        let mut chunks = self.chunks.borrow_mut();
+        let fits = chunks
+            .last()
+            .is_some_and(|chunk| chunk.capacity() - chunk.len() >= key.len());
+        if !fits {
+            chunks.push(String::with_capacity(key.len().max(CHUNK_CAPACITY)));
+        }
+        let chunk = chunks.last_mut().expect("pushed above");
+        let start = chunk.len();
+        chunk.push_str(key);
+        let stored: *const str = &chunk[start..];
+        // SAFETY: The key was written within the capacity of the chunk, so the chunk didn't
+        // reallocate, and chunks are only appended to and dropped with the arena. Moving the
+        // `String` when `chunks` grows doesn't move its heap buffer.
+        unsafe { &*stored }
+    }
+
+    /// Formats `args` into the arena, e.g. `arena.alloc_fmt(format_args!("field_{i}"))`.
+    ///
+    /// The `Display` impls of the arguments may use the arena themselves.
+    pub fn alloc_fmt(&self, args: fmt::Arguments<'_>) -> &str {
+        if let Some(key) = args.as_str() {
+            return self.alloc(key);
+        }
+        // Take the buffer out of the arena while formatting, so no borrow is held if a `Display`
+        // impl calls into the arena.
+        let mut scratch = self.scratch.take();
+        scratch.clear();
+        scratch
+            .write_fmt(args)
+            .expect("formatting into a String doesn't fail");
+        let key = self.alloc(&scratch);
+        *self.scratch.borrow_mut() = scratch;
+        key
+    }
+
+    /// Returns the number of bytes allocated by the arena.
+    pub fn allocated_bytes(&self) -> usize {
+        self.chunks.borrow().iter().map(String::capacity).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ObjectAsVec, Value};
+
+    #[test]
+    fn key_arena_alloc() {
+        let arena = KeyArena::new();
+        assert_eq!(arena.allocated_bytes(), 0);
+        let keys: Vec<&str> = (0..500)
+            .map(|i| arena.alloc_fmt(format_args!("field_{i}")))
+            .collect();
+        let long = "x".repeat(CHUNK_CAPACITY * 2);
+        let long_key = arena.alloc(&long);
+        let literal = arena.alloc_fmt(format_args!("literal"));
+
+        for (i, key) in keys.iter().enumerate() {
+            assert_eq!(*key, format!("field_{i}"));
+        }
+        assert_eq!(long_key, long);
+        assert_eq!(literal, "literal");
+        assert_eq!(arena.alloc(""), "");
+        // The keys share a few chunks.
+        assert!(arena.allocated_bytes() < CHUNK_CAPACITY * 10);
+    }
+
+    #[test]
+    fn key_arena_reentrant_display() {
+        struct Prefixed<'a>(&'a KeyArena, u32);
+        impl fmt::Display for Prefixed<'_> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                let inner = self.0.alloc_fmt(format_args!("inner_{}", self.1));
+                write!(f, "outer_{inner}")
+            }
+        }
+
+        let arena = KeyArena::new();
+        let key = arena.alloc_fmt(format_args!("{}", Prefixed(&arena, 1)));
+        assert_eq!(key, "outer_inner_1");
+        assert_eq!(arena.alloc_fmt(format_args!("k{}", 2)), "k2");
+    }
+
+    #[test]
+    fn key_arena_object() {
+        let arena = KeyArena::new();
+        let obj: ObjectAsVec = (0..3)
+            .map(|i| (arena.alloc_fmt(format_args!("k{i}")), Value::from(i as u64)))
+            .collect();
+        assert_eq!(
+            Value::Object(obj),
+            serde_json::json!({"k0": 0, "k1": 1, "k2": 2})
+        );
+    }
+}
@@ -0,0 +1,284 @@
+use std::borrow::Cow;
+use std::cell::OnceCell;
+use std::{fmt, io};
+
+use serde::de::{Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
+
+use crate::escape_report::KeyProbe;
+use crate::{RawValue, Value};
+
+/// A JSON value, whose arrays and objects are only parsed on first access.
+///
+/// The document is validated when the `LazyValue` is created, but only the accessed arrays and
+/// objects are parsed, one level at a time. Their elements are kept as raw slices of the input
+/// until they are accessed themselves. Parsed levels are cached, so each level is parsed at most
+/// once. This is faster than building a [`Value`], if only a small part of a document is
+/// accessed.
+///
+/// Keys with escapes are supported without the `cowkeys` feature flag, except by
+/// [`LazyValue::to_value`].
+///
+/// # Example
+/// ```
+/// use serde_json_borrow::LazyValue;
+///
+/// let json = r#"{"user": {"name": "John", "tags": [1, 2]}, "payload": [{"big": "data"}]}"#;
+/// let doc = LazyValue::from_str(json).unwrap();
+///
+/// let name = doc.get("user").and_then(|user| user.get("name"));
+/// assert_eq!(name.and_then(|name| name.scalar()).and_then(|name| name.as_str()), Some("John"));
+/// assert_eq!(doc.get("user").unwrap().raw(), r#"{"name": "John", "tags": [1, 2]}"#);
+/// assert!(!doc.get("payload").unwrap().is_parsed());
+/// ```
+pub struct LazyValue<'ctx> {
+    raw: &'ctx str,
+    node: OnceCell<Node<'ctx>>,
+}
+
+/// A parsed level of a [`LazyValue`].
+enum Node<'ctx> {
+    Scalar(Value<'ctx>),
+    Array(Vec<LazyValue<'ctx>>),
+    Object(Vec<(Cow<'ctx, str>, LazyValue<'ctx>)>),
+}
+
+impl<'ctx> LazyValue<'ctx> {
+    /// Validates `json` and wraps it, without parsing it into a [`Value`].
+    ///
+    /// The validation is as strict as parsing, e.g. numbers out of range and invalid escapes are
+    /// rejected, so accessing the value can't fail later.
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(json: &'ctx str) -> io::Result<Self> {
+        let mut deserializer = serde_json::Deserializer::from_str(json);
+        Validate::deserialize(&mut deserializer)?;
+        deserializer.end()?;
+        Ok(Self::from_raw(RawValue::from_str(json)?))
+    }
+
+    fn from_raw(raw: RawValue<'ctx>) -> Self {
+        LazyValue {
+            raw: raw.get(),
+            node: OnceCell::new(),
+        }
+    }
+
+    /// Returns the source text of the value.
+    pub fn raw(&self) -> &'ctx str {
+        self.raw
+    }
+
+    /// Returns true if the value has been parsed, i.e. it was accessed.
+    pub fn is_parsed(&self) -> bool {
+        self.node.get().is_some()
+    }
+
+    /// Returns the value, if it is `null`, a boolean, a number or a string. Returns None for
+    /// arrays and objects.
+    pub fn scalar(&self) -> Option<&Value<'ctx>> {
+        match self.node() {
+            Node::Scalar(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns the value of the first member with `key`, if the value is an object.
+    pub fn get(&self, key: &str) -> Option<&LazyValue<'ctx>> {
+        self.iter_object()?
+            .find(|(member, _)| *member == key)
+            .map(|(_, value)| value)
+    }
+
+    /// Returns the element at `idx`, if the value is an array.
+    pub fn get_index(&self, idx: usize) -> Option<&LazyValue<'ctx>> {
+        match self.node() {
+            Node::Array(arr) => arr.get(idx),
+            _ => None,
+        }
+    }
+
+    /// Iterates over the elements, if the value is an array.
+    pub fn iter_array(&self) -> Option<impl Iterator<Item = &LazyValue<'ctx>>> {
+        match self.node() {
+            Node::Array(arr) => Some(arr.iter()),
+            _ => None,
+        }
+    }
+
+    /// Iterates over the members, if the value is an object.
+    pub fn iter_object(&self) -> Option<impl Iterator<Item = (&str, &LazyValue<'ctx>)>> {
+        match self.node() {
+            Node::Object(members) => Some(members.iter().map(|(key, value)| (key.as_ref(), value))),
+            _ => None,
+        }
+    }
+
+    /// Parses the whole value into a [`Value`], borrowing from the input.
+    ///
+    /// Returns an error for keys with escapes without the `cowkeys` feature flag.
+    pub fn to_value(&self) -> io::Result<Value<'ctx>> {
+        Ok(serde_json::from_str(self.raw)?)
+    }
+
+    fn node(&self) -> &Node<'ctx> {
+        self.node.get_or_init(|| {
+            serde_json::Deserializer::from_str(self.raw)
+                .deserialize_any(NodeVisitor)
+                .expect("the value was validated on creation")
+        })
+    }
+}
+
+impl fmt::Debug for LazyValue<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("LazyValue").field(&self.raw).finish()
+    }
+}
+
+/// Deserializes a value like [`Value`] does, to get the same errors, but without building it.
+struct Validate;
+
+impl<'de> Deserialize<'de> for Validate {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where D: Deserializer<'de> {
+        deserializer.deserialize_any(Validate)
+    }
+}
+
+impl<'de> Visitor<'de> for Validate {
+    type Value = Validate;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("any valid JSON value")
+    }
+
+    fn visit_bool<E>(self, _value: bool) -> Result<Self::Value, E> {
+        Ok(Validate)
+    }
+
+    fn visit_i64<E>(self, _value: i64) -> Result<Self::Value, E> {
+        Ok(Validate)
+    }
+
+    fn visit_u64<E>(self, _value: u64) -> Result<Self::Value, E> {
+        Ok(Validate)
+    }
+
+    fn visit_f64<E>(self, _value: f64) -> Result<Self::Value, E> {
+        Ok(Validate)
+    }
+
+    fn visit_unit<E>(self) -> Result<Self::Value, E> {
+        Ok(Validate)
+    }
+
+    fn visit_str<E>(self, _value: &str) -> Result<Self::Value, E> {
+        Ok(Validate)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where A: SeqAccess<'de> {
+        while seq.next_element::<Validate>()?.is_some() {}
+        Ok(Validate)
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where A: MapAccess<'de> {
+        while map.next_entry::<Validate, Validate>()?.is_some() {}
+        Ok(Validate)
+    }
+}
+
+/// Parses one level of a value, nested values are kept as [`RawValue`].
+struct NodeVisitor;
+
+impl<'de> Visitor<'de> for NodeVisitor {
+    type Value = Node<'de>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("any valid JSON value")
+    }
+
+    fn visit_bool<E>(self, value: bool) -> Result<Self::Value, E> {
+        Ok(Node::Scalar(Value::Bool(value)))
+    }
+
+    fn visit_i64<E>(self, value: i64) -> Result<Self::Value, E> {
+        Ok(Node::Scalar(Value::from(value)))
+    }
+
+    fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E> {
+        Ok(Node::Scalar(Value::from(value)))
+    }
+
+    fn visit_f64<E>(self, value: f64) -> Result<Self::Value, E> {
+        Ok(Node::Scalar(Value::from(value)))
+    }
+
+    fn visit_unit<E>(self) -> Result<Self::Value, E> {
+        Ok(Node::Scalar(Value::Null))
+    }
+
+    fn visit_borrowed_str<E>(self, value: &'de str) -> Result<Self::Value, E> {
+        Ok(Node::Scalar(Value::Str(Cow::Borrowed(value))))
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<Self::Value, E> {
+        Ok(Node::Scalar(Value::Str(Cow::Owned(value.to_owned()))))
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where A: SeqAccess<'de> {
+        let mut arr = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(raw) = seq.next_element::<RawValue>()? {
+            arr.push(LazyValue::from_raw(raw));
+        }
+        Ok(Node::Array(arr))
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where A: MapAccess<'de> {
+        let mut members = Vec::with_capacity(map.size_hint().unwrap_or(0));
+        while let Some(key) = map.next_key_seed(KeyProbe)? {
+            let raw: RawValue = map.next_value()?;
+            members.push((key, LazyValue::from_raw(raw)));
+        }
+        Ok(Node::Object(members))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lazy_value_test() {
+        let json = r#" {"a": [1, {"b": "x\ny"}], "k\"ey": null, "c": {"d": 1.5}} "#;
+        let doc = LazyValue::from_str(json).unwrap();
+        assert!(!doc.is_parsed());
+        assert!(doc.scalar().is_none());
+        assert!(doc.is_parsed());
+
+        let a = doc.get("a").unwrap();
+        assert_eq!(a.raw(), r#"[1, {"b": "x\ny"}]"#);
+        assert_eq!(a.get_index(0).unwrap().scalar(), Some(&Value::from(1u64)));
+        let b = a.get_index(1).and_then(|obj| obj.get("b")).unwrap();
+        assert_eq!(b.scalar(), Some(&Value::from("x\ny")));
+        assert!(a.get_index(2).is_none() && a.get("b").is_none());
+
+        let c = doc.get("c").unwrap();
+        assert!(!c.is_parsed());
+        assert_eq!(doc.get("k\"ey").unwrap().scalar(), Some(&Value::Null));
+        let keys: Vec<_> = doc.iter_object().unwrap().map(|(key, _)| key).collect();
+        assert_eq!(keys, ["a", "k\"ey", "c"]);
+        assert_eq!(a.iter_array().unwrap().count(), 2);
+        assert!(doc.iter_array().is_none());
+        assert_eq!(c.to_value().unwrap(), serde_json::json!({"d": 1.5}));
+
+        assert!(LazyValue::from_str(r#"{"a": [1,}"#).is_err());
+        assert!(LazyValue::from_str("1 2").is_err());
+        // Syntactically valid, but rejected by the parser.
+        assert!(LazyValue::from_str("[1e400]").is_err());
+        assert!(LazyValue::from_str(r#"["\ud800"]"#).is_err());
+        assert!(LazyValue::from_str(r#"{"\ud800": 1}"#).is_err());
+    }
+}
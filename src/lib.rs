@@ -31,6 +31,16 @@
 //! as [`OwnedValue`] will take ownership of the `String` and reference slices of
 //! it, rather than making copies.
 //!
+//! ## BTree
+//! The feature flag `btree` keeps the entries of objects sorted by key. This makes lookups by
+//! key `O(log n)`, at the cost of slower deserialization. See [`ObjectAsVec`].
+//!
+//! **The feature flag is not additive**: if any crate in the dependency graph enables it, the
+//! key order changes for every crate using `serde_json_borrow`. Only enable it in binaries.
+//!
+//! The feature flag `deterministic` guarantees sorted key order for iteration, `Debug` output
+//! and serialization, e.g. for golden-file tests. It currently enables `btree`.
+//!
 //! # Limitations
 //! The feature flag `cowkeys` uses `Cow<str>` instead of `&str` as keys in objects. This enables
 //! support for escaped data in keys. Without the `cowkeys` feature flag `&str` is used, which does
@@ -71,14 +81,93 @@
 //! On a hadoop file system log data set benchmark, I get _714Mb/s_ JSON deserialization throughput
 //! on my machine.
 
+#[cfg(feature = "bench_utils")]
+pub mod bench_utils;
+#[cfg(feature = "bytes")]
+mod bytes;
+mod canonical;
+mod convert;
 mod de;
+#[cfg(feature = "simd")]
+mod de_simd;
 mod deserializer;
+mod escape_report;
+mod events;
+mod field_callback;
+mod filter;
+mod flatten;
+mod frame;
+mod in_place;
 mod index;
+mod key_arena;
+mod lazy;
+mod macros;
+mod matcher;
+mod merge;
+mod ndjson;
+#[cfg(feature = "cowkeys")]
+mod normalize;
+mod number_hints;
 mod object_vec;
 mod owned;
+mod partial_eq;
+mod patch;
+mod profile;
+mod provenance;
+mod raw;
+mod sample;
 mod ser;
+mod signature;
+#[cfg(feature = "simd")]
+mod simd_convert;
+mod subtree_cache;
+mod tagged;
+mod tracked;
+mod unordered;
 mod value;
+mod value_ref;
+#[cfg(feature = "simd")]
+mod value_trait;
+mod walk;
+mod writer;
 
+#[cfg(feature = "bytes")]
+pub use bytes::{BytesEncoding, WithBytesEncoding};
+pub use convert::TypeMismatch;
+pub use de::{from_slice, from_str};
+pub use escape_report::{EscapeReport, FieldEscapeStats};
+pub use events::{Events, InvalidEvents, JsonEvent};
+pub use field_callback::from_str_with_field_callback;
+pub use flatten::IndexStyle;
+pub use frame::{FramePrefix, FrameReader};
+pub use in_place::NonFiniteMode;
+pub use index::Lookup;
+pub use key_arena::KeyArena;
+pub use lazy::LazyValue;
+pub use matcher::{InvalidPattern, Matcher};
+pub use merge::ArrayMergeStrategy;
+pub use ndjson::{NdjsonErrorPolicy, NdjsonReader};
+#[cfg(feature = "cowkeys")]
+pub use normalize::Case;
+pub use number_hints::{from_str_with_number_hints, NumberHint, NumberHints};
 pub use object_vec::{KeyStrType, ObjectAsVec, ObjectAsVec as Map};
-pub use owned::OwnedValue;
-pub use value::Value;
+pub use owned::{OwnedValue, OwnedValueIn, StableDeref};
+#[cfg(feature = "cowkeys")]
+pub use patch::diff;
+pub use patch::{PatchError, PatchErrorKind, PatchOp};
+pub use profile::ParseProfile;
+pub use provenance::Layered;
+pub use raw::RawValue;
+pub use sample::TRUNCATED_MARKER_KEY;
+pub use ser::{
+    to_string, to_string_bounded, to_string_non_finite, to_string_pretty, to_vec, to_writer,
+    SerializeOptions, SizeLimitExceeded,
+};
+pub use subtree_cache::{CachedValue, SubtreeCache};
+pub use tagged::{NodeId, TaggedValue};
+pub use tracked::{AccessLog, TrackedValue};
+pub use unordered::Unordered;
+pub use value::{parse_number, InvalidNumber, NonFiniteNumber, Number, Value};
+pub use value_ref::ValueRef;
+pub use walk::{ValueVisitor, WalkControl};
+pub use writer::{ArrayWriter, DocWriter, ObjectWriter};
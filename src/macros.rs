@@ -0,0 +1,346 @@
+/// Construct a `serde_json_borrow::Value` from a JSON literal.
+///
+/// Works like `serde_json::json!`, but string literals are borrowed instead of allocated. Values
+/// which are not JSON literals are converted via `Value::from`.
+///
+/// ```
+/// # use serde_json_borrow::{json_borrow, Value};
+/// #
+/// let value: Value = json_borrow!({
+///     "code": 200,
+///     "success": true,
+///     "payload": {
+///         "features": [
+///             "serde",
+///             "json"
+///         ],
+///         "homepage": null
+///     }
+/// });
+/// assert_eq!(value.get("payload").get("features").get(0), &Value::Str("serde".into()));
+/// ```
+///
+/// Variables or expressions can be interpolated into the JSON literal. Keys need to be `&str`.
+///
+/// ```
+/// # use serde_json_borrow::{json_borrow, Value};
+/// #
+/// let code = 200;
+/// let features = vec!["serde", "json"];
+///
+/// let value: Value = json_borrow!({
+///     "code": code,
+///     "success": code == 200,
+///     "payload": {
+///         features[0]: features[1]
+///     }
+/// });
+/// assert_eq!(value.get("payload").get("serde"), &Value::Str("json".into()));
+/// ```
+///
+/// Trailing commas are allowed inside both arrays and objects.
+#[macro_export]
+macro_rules! json_borrow {
+    // Hide distracting implementation details from the generated rustdoc.
+    ($($json:tt)+) => {
+        $crate::json_borrow_internal!($($json)+)
+    };
+}
+
+/// Alias of [`json_borrow!`], named like `serde_json::json!` to ease the migration from
+/// `serde_json`.
+///
+/// ```
+/// use serde_json_borrow::{json, Value};
+///
+/// let value: Value = json!({"name": "John", "tags": [1, 2]});
+/// assert_eq!(value.get("tags").get(1), &2_u64);
+/// ```
+#[macro_export]
+macro_rules! json {
+    ($($json:tt)+) => {
+        $crate::json_borrow_internal!($($json)+)
+    };
+}
+
+// The implementation is a port of the `json_internal!` macro in `serde_json`.
+#[macro_export]
+#[doc(hidden)]
+macro_rules! json_borrow_internal {
+    //////////////////////////////////////////////////////////////////////////
+    // TT muncher for parsing the inside of an array [...]. Produces a vec![...]
+    // of the elements.
+    //
+    // Must be invoked as: json_borrow_internal!(@array [] $($tt)*)
+    //////////////////////////////////////////////////////////////////////////
+
+    // Done with trailing comma.
+    (@array [$($elems:expr,)*]) => {
+        ::std::vec![$($elems,)*]
+    };
+
+    // Done without trailing comma.
+    (@array [$($elems:expr),*]) => {
+        ::std::vec![$($elems),*]
+    };
+
+    // Next element is `null`.
+    (@array [$($elems:expr,)*] null $($rest:tt)*) => {
+        $crate::json_borrow_internal!(@array [$($elems,)* $crate::json_borrow_internal!(null)] $($rest)*)
+    };
+
+    // Next element is `true`.
+    (@array [$($elems:expr,)*] true $($rest:tt)*) => {
+        $crate::json_borrow_internal!(@array [$($elems,)* $crate::json_borrow_internal!(true)] $($rest)*)
+    };
+
+    // Next element is `false`.
+    (@array [$($elems:expr,)*] false $($rest:tt)*) => {
+        $crate::json_borrow_internal!(@array [$($elems,)* $crate::json_borrow_internal!(false)] $($rest)*)
+    };
+
+    // Next element is an array.
+    (@array [$($elems:expr,)*] [$($array:tt)*] $($rest:tt)*) => {
+        $crate::json_borrow_internal!(@array [$($elems,)* $crate::json_borrow_internal!([$($array)*])] $($rest)*)
+    };
+
+    // Next element is a map.
+    (@array [$($elems:expr,)*] {$($map:tt)*} $($rest:tt)*) => {
+        $crate::json_borrow_internal!(@array [$($elems,)* $crate::json_borrow_internal!({$($map)*})] $($rest)*)
+    };
+
+    // Next element is an expression followed by comma.
+    (@array [$($elems:expr,)*] $next:expr, $($rest:tt)*) => {
+        $crate::json_borrow_internal!(@array [$($elems,)* $crate::json_borrow_internal!($next),] $($rest)*)
+    };
+
+    // Last element is an expression with no trailing comma.
+    (@array [$($elems:expr,)*] $last:expr) => {
+        $crate::json_borrow_internal!(@array [$($elems,)* $crate::json_borrow_internal!($last)])
+    };
+
+    // Comma after the most recent element.
+    (@array [$($elems:expr),*] , $($rest:tt)*) => {
+        $crate::json_borrow_internal!(@array [$($elems,)*] $($rest)*)
+    };
+
+    // Unexpected token after most recent element.
+    (@array [$($elems:expr),*] $unexpected:tt $($rest:tt)*) => {
+        $crate::json_borrow_unexpected!($unexpected)
+    };
+
+    //////////////////////////////////////////////////////////////////////////
+    // TT muncher for parsing the inside of an object {...}. Each entry is
+    // inserted into the given map variable.
+    //
+    // Must be invoked as: json_borrow_internal!(@object $map () ($($tt)*) ($($tt)*))
+    //
+    // We require two copies of the input tokens so that we can match on one
+    // copy and trigger errors on the other copy.
+    //////////////////////////////////////////////////////////////////////////
+
+    // Done.
+    (@object $object:ident () () ()) => {};
+
+    // Insert the current entry followed by trailing comma.
+    (@object $object:ident [$($key:tt)+] ($value:expr) , $($rest:tt)*) => {
+        let _ = $object.insert($($key)+, $value);
+        $crate::json_borrow_internal!(@object $object () ($($rest)*) ($($rest)*));
+    };
+
+    // Current entry followed by unexpected token.
+    (@object $object:ident [$($key:tt)+] ($value:expr) $unexpected:tt $($rest:tt)*) => {
+        $crate::json_borrow_unexpected!($unexpected);
+    };
+
+    // Insert the last entry without trailing comma.
+    (@object $object:ident [$($key:tt)+] ($value:expr)) => {
+        let _ = $object.insert($($key)+, $value);
+    };
+
+    // Next value is `null`.
+    (@object $object:ident ($($key:tt)+) (: null $($rest:tt)*) $copy:tt) => {
+        $crate::json_borrow_internal!(@object $object [$($key)+] ($crate::json_borrow_internal!(null)) $($rest)*);
+    };
+
+    // Next value is `true`.
+    (@object $object:ident ($($key:tt)+) (: true $($rest:tt)*) $copy:tt) => {
+        $crate::json_borrow_internal!(@object $object [$($key)+] ($crate::json_borrow_internal!(true)) $($rest)*);
+    };
+
+    // Next value is `false`.
+    (@object $object:ident ($($key:tt)+) (: false $($rest:tt)*) $copy:tt) => {
+        $crate::json_borrow_internal!(@object $object [$($key)+] ($crate::json_borrow_internal!(false)) $($rest)*);
+    };
+
+    // Next value is an array.
+    (@object $object:ident ($($key:tt)+) (: [$($array:tt)*] $($rest:tt)*) $copy:tt) => {
+        $crate::json_borrow_internal!(@object $object [$($key)+] ($crate::json_borrow_internal!([$($array)*])) $($rest)*);
+    };
+
+    // Next value is a map.
+    (@object $object:ident ($($key:tt)+) (: {$($map:tt)*} $($rest:tt)*) $copy:tt) => {
+        $crate::json_borrow_internal!(@object $object [$($key)+] ($crate::json_borrow_internal!({$($map)*})) $($rest)*);
+    };
+
+    // Next value is an expression followed by comma.
+    (@object $object:ident ($($key:tt)+) (: $value:expr , $($rest:tt)*) $copy:tt) => {
+        $crate::json_borrow_internal!(@object $object [$($key)+] ($crate::json_borrow_internal!($value)) , $($rest)*);
+    };
+
+    // Last value is an expression with no trailing comma.
+    (@object $object:ident ($($key:tt)+) (: $value:expr) $copy:tt) => {
+        $crate::json_borrow_internal!(@object $object [$($key)+] ($crate::json_borrow_internal!($value)));
+    };
+
+    // Missing value for last entry. Trigger a reasonable error message.
+    (@object $object:ident ($($key:tt)+) (:) $copy:tt) => {
+        // "unexpected end of macro invocation"
+        $crate::json_borrow_internal!();
+    };
+
+    // Missing colon and value for last entry. Trigger a reasonable error
+    // message.
+    (@object $object:ident ($($key:tt)+) () $copy:tt) => {
+        // "unexpected end of macro invocation"
+        $crate::json_borrow_internal!();
+    };
+
+    // Misplaced colon. Trigger a reasonable error message.
+    (@object $object:ident () (: $($rest:tt)*) ($colon:tt $($copy:tt)*)) => {
+        // Takes no arguments so "no rules expected the token `:`".
+        $crate::json_borrow_unexpected!($colon);
+    };
+
+    // Found a comma inside a key. Trigger a reasonable error message.
+    (@object $object:ident ($($key:tt)*) (, $($rest:tt)*) ($comma:tt $($copy:tt)*)) => {
+        // Takes no arguments so "no rules expected the token `,`".
+        $crate::json_borrow_unexpected!($comma);
+    };
+
+    // Key is fully parenthesized. This avoids clippy double_parens false
+    // positives because the parenthesization may be necessary here.
+    (@object $object:ident () (($key:expr) : $($rest:tt)*) $copy:tt) => {
+        $crate::json_borrow_internal!(@object $object ($key) (: $($rest)*) (: $($rest)*));
+    };
+
+    // Refuse to absorb colon token into key expression.
+    (@object $object:ident ($($key:tt)*) (: $($unexpected:tt)+) $copy:tt) => {
+        $crate::json_borrow_expect_expr_comma!($($unexpected)+);
+    };
+
+    // Munch a token into the current key.
+    (@object $object:ident ($($key:tt)*) ($tt:tt $($rest:tt)*) $copy:tt) => {
+        $crate::json_borrow_internal!(@object $object ($($key)* $tt) ($($rest)*) ($($rest)*));
+    };
+
+    //////////////////////////////////////////////////////////////////////////
+    // The main implementation.
+    //
+    // Must be invoked as: json_borrow_internal!($($json)+)
+    //////////////////////////////////////////////////////////////////////////
+
+    (null) => {
+        $crate::Value::Null
+    };
+
+    (true) => {
+        $crate::Value::Bool(true)
+    };
+
+    (false) => {
+        $crate::Value::Bool(false)
+    };
+
+    ([]) => {
+        $crate::Value::Array(::std::vec![])
+    };
+
+    ([ $($tt:tt)+ ]) => {
+        $crate::Value::Array($crate::json_borrow_internal!(@array [] $($tt)+))
+    };
+
+    ({}) => {
+        $crate::Value::Object($crate::ObjectAsVec::default())
+    };
+
+    ({ $($tt:tt)+ }) => {
+        $crate::Value::Object({
+            let mut object = $crate::ObjectAsVec::default();
+            $crate::json_borrow_internal!(@object object () ($($tt)+) ($($tt)+));
+            object
+        })
+    };
+
+    // Any type convertible into a `Value`: numbers, strings, variables etc.
+    // Must be below every other rule.
+    ($other:expr) => {
+        $crate::Value::from($other)
+    };
+}
+
+// Used by json_borrow_internal to trigger an error at the invocation site.
+#[macro_export]
+#[doc(hidden)]
+macro_rules! json_borrow_unexpected {
+    () => {};
+}
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! json_borrow_expect_expr_comma {
+    ($e:expr , $($tt:tt)*) => {};
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use crate::Value;
+
+    #[test]
+    fn json_borrow_scalars() {
+        assert_eq!(json_borrow!(null), Value::Null);
+        assert_eq!(json_borrow!(true), Value::Bool(true));
+        assert_eq!(json_borrow!(false), Value::Bool(false));
+        assert_eq!(json_borrow!(1), Value::Number(1i64.into()));
+        assert_eq!(json_borrow!(1.5), Value::Number(1.5.into()));
+        assert_eq!(json_borrow!("text"), Value::Str(Cow::Borrowed("text")));
+    }
+
+    #[test]
+    fn json_borrow_nested() {
+        let name = String::from("John");
+        let value = json_borrow!({
+            "name": name,
+            "age": 30u64,
+            "tags": ["a", null, [], {}],
+            "address": {
+                "city": "Paris",
+                "zip": null,
+            },
+        });
+        #[cfg(not(feature = "btree"))]
+        assert_eq!(
+            serde_json::to_string(&value).unwrap(),
+            r#"{"name":"John","age":30,"tags":["a",null,[],{}],"address":{"city":"Paris","zip":null}}"#
+        );
+        #[cfg(feature = "btree")]
+        assert_eq!(
+            serde_json::to_string(&value).unwrap(),
+            r#"{"address":{"city":"Paris","zip":null},"age":30,"name":"John","tags":["a",null,[],{}]}"#
+        );
+        assert!(matches!(
+            value.get("address").get("city"),
+            Value::Str(Cow::Borrowed(_))
+        ));
+    }
+
+    #[test]
+    fn json_borrow_expression_keys() {
+        let key = "dynamic";
+        let value = json_borrow!({ key: 1, ("other"): [true, false] });
+        assert_eq!(value.get("dynamic"), &Value::Number(1i64.into()));
+        assert_eq!(value.get("other").get(1), &Value::Bool(false));
+    }
+}
@@ -0,0 +1,286 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+use crate::value::{push_pointer_token, type_name, N};
+use crate::Value;
+
+/// A pattern compiled from a pattern document, which can be matched against values quickly, e.g.
+/// for routing messages.
+///
+/// The pattern document is a subset of what JSON Schema can express:
+/// - A scalar matches an equal value. Numbers are compared by their numeric value, so `1` matches
+///   `1.0`.
+/// - An object matches objects, which have matching values for all keys of the pattern. Other keys
+///   of the value are ignored.
+/// - An array matches arrays of the same length with matching elements.
+/// - `{"$type": "<type>"}` matches any value of the type, which is one of `null`, `boolean`,
+///   `number`, `string`, `array`, `object` or `any`.
+/// - `{"$exists": true}` matches any value, `{"$exists": false}` only matches if an object member
+///   is missing.
+///
+/// Keys starting with `$` are reserved for these operators, objects containing them can't have
+/// other keys.
+///
+/// # Example
+/// ```
+/// use serde_json_borrow::{Matcher, Value};
+///
+/// let pattern: Value = serde_json::from_str(
+///     r#"{"kind": "order", "amount": {"$type": "number"}, "test": {"$exists": false}}"#,
+/// )
+/// .unwrap();
+/// let matcher = Matcher::new(&pattern).unwrap();
+///
+/// let message: Value = serde_json::from_str(r#"{"kind": "order", "amount": 12.5}"#).unwrap();
+/// assert!(matcher.matches(&message));
+/// let message: Value =
+///     serde_json::from_str(r#"{"kind": "order", "amount": 1, "test": true}"#).unwrap();
+/// assert!(!matcher.matches(&message));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Matcher {
+    root: Node,
+}
+
+#[derive(Debug, Clone)]
+enum Node {
+    Null,
+    Bool(bool),
+    Number(N),
+    Str(String),
+    #[cfg(feature = "bytes")]
+    Bytes(Vec<u8>),
+    Array(Vec<Node>),
+    Object(Vec<(String, Node)>),
+    Type(&'static str),
+    Any,
+    Missing,
+}
+
+const TYPES: [&str; 6] = ["null", "boolean", "number", "string", "array", "object"];
+
+impl Matcher {
+    /// Compiles the pattern document.
+    pub fn new(pattern: &Value) -> Result<Self, InvalidPattern> {
+        let root = compile(pattern, &mut String::new())?;
+        if matches!(root, Node::Missing) {
+            return Err(InvalidPattern {
+                path: String::new(),
+                reason: "`$exists: false` is only allowed for object members",
+            });
+        }
+        Ok(Self { root })
+    }
+
+    /// Returns true if the value matches the pattern.
+    pub fn matches(&self, value: &Value) -> bool {
+        matches_node(&self.root, Some(value))
+    }
+}
+
+fn compile(pattern: &Value, path: &mut String) -> Result<Node, InvalidPattern> {
+    let invalid = |path: &String, reason| InvalidPattern {
+        path: path.clone(),
+        reason,
+    };
+    let node = match pattern {
+        Value::Null => Node::Null,
+        Value::Bool(val) => Node::Bool(*val),
+        Value::Number(num) => Node::Number(num.n),
+        Value::Str(text) => Node::Str(text.to_string()),
+        #[cfg(feature = "bytes")]
+        Value::Bytes(bytes) => Node::Bytes(bytes.to_vec()),
+        Value::Array(arr) => {
+            let len = path.len();
+            let mut nodes = Vec::with_capacity(arr.len());
+            for (idx, pattern) in arr.iter().enumerate() {
+                push_pointer_token(path, &idx.to_string());
+                let node = compile(pattern, path)?;
+                if matches!(node, Node::Missing) {
+                    return Err(invalid(
+                        path,
+                        "`$exists: false` is only allowed for object members",
+                    ));
+                }
+                nodes.push(node);
+                path.truncate(len);
+            }
+            Node::Array(nodes)
+        }
+        Value::Object(obj) if obj.keys().any(|key| key.starts_with('$')) => {
+            if obj.len() != 1 {
+                return Err(invalid(path, "operators can't be combined with other keys"));
+            }
+            let (key, arg) = obj.iter().next().expect("object has one entry");
+            match (key, arg) {
+                ("$exists", Value::Bool(true)) => Node::Any,
+                ("$exists", Value::Bool(false)) => Node::Missing,
+                ("$exists", _) => return Err(invalid(path, "`$exists` requires a boolean")),
+                ("$type", Value::Str(name)) if name == "any" => Node::Any,
+                ("$type", Value::Str(name)) => Node::Type(
+                    TYPES
+                        .into_iter()
+                        .find(|ty| ty == name)
+                        .ok_or_else(|| invalid(path, "unknown type for `$type`"))?,
+                ),
+                ("$type", _) => return Err(invalid(path, "`$type` requires a string")),
+                _ => return Err(invalid(path, "unknown operator")),
+            }
+        }
+        Value::Object(obj) => {
+            let len = path.len();
+            let mut members = Vec::with_capacity(obj.len());
+            for (key, pattern) in obj.iter() {
+                push_pointer_token(path, key);
+                members.push((key.to_string(), compile(pattern, path)?));
+                path.truncate(len);
+            }
+            Node::Object(members)
+        }
+    };
+    Ok(node)
+}
+
+fn matches_node(node: &Node, value: Option<&Value>) -> bool {
+    let Some(value) = value else {
+        return matches!(node, Node::Missing);
+    };
+    match (node, value) {
+        (Node::Any, _) => true,
+        (Node::Missing, _) => false,
+        (Node::Type(ty), value) => *ty == type_name(value),
+        (Node::Null, Value::Null) => true,
+        (Node::Bool(expected), Value::Bool(val)) => expected == val,
+        (Node::Number(expected), Value::Number(num)) => {
+            expected.cmp_numeric(&num.n) == Ordering::Equal
+        }
+        (Node::Str(expected), Value::Str(text)) => expected == text,
+        #[cfg(feature = "bytes")]
+        (Node::Bytes(expected), Value::Bytes(bytes)) => **expected == **bytes,
+        (Node::Array(nodes), Value::Array(arr)) => {
+            nodes.len() == arr.len()
+                && nodes
+                    .iter()
+                    .zip(arr)
+                    .all(|(node, value)| matches_node(node, Some(value)))
+        }
+        (Node::Object(members), Value::Object(obj)) => members
+            .iter()
+            .all(|(key, node)| matches_node(node, obj.get(key))),
+        _ => false,
+    }
+}
+
+/// Error returned by [`Matcher::new`] for invalid pattern documents.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidPattern {
+    /// The JSON Pointer of the invalid part of the pattern.
+    pub path: String,
+    /// Why the pattern is invalid.
+    pub reason: &'static str,
+}
+
+impl fmt::Display for InvalidPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid pattern at {:?}: {}", self.path, self.reason)
+    }
+}
+
+impl std::error::Error for InvalidPattern {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matcher(pattern: &str) -> Matcher {
+        let pattern: Value = serde_json::from_str(pattern).unwrap();
+        Matcher::new(&pattern).unwrap()
+    }
+
+    fn matches(matcher: &Matcher, json: &str) -> bool {
+        let value: Value = serde_json::from_str(json).unwrap();
+        matcher.matches(&value)
+    }
+
+    fn error(pattern: &str) -> String {
+        let pattern: Value = serde_json::from_str(pattern).unwrap();
+        Matcher::new(&pattern).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn matcher_exact_values() {
+        let m = matcher(r#"{"a": 1, "b": "x", "c": [true, null], "d": {"e": -2.5}}"#);
+        assert!(matches(
+            &m,
+            r#"{"a": 1.0, "b": "x", "c": [true, null], "d": {"e": -2.5, "f": 0}, "g": 1}"#
+        ));
+        assert!(!matches(
+            &m,
+            r#"{"a": 2, "b": "x", "c": [true, null], "d": {"e": -2.5}}"#
+        ));
+        assert!(!matches(
+            &m,
+            r#"{"a": 1, "b": "x", "c": [true], "d": {"e": -2.5}}"#
+        ));
+        assert!(!matches(&m, r#"{"a": 1, "b": "x", "c": [true, null]}"#));
+        assert!(!matches(&m, r#"[1]"#));
+
+        assert!(matches(&matcher("null"), "null"));
+        assert!(!matches(&matcher("null"), "false"));
+        assert!(matches(&matcher("{}"), r#"{"a": 1}"#));
+        assert!(!matches(&matcher("{}"), "[]"));
+    }
+
+    #[test]
+    fn matcher_operators() {
+        let m = matcher(
+            r#"{"id": {"$type": "string"}, "tags": {"$type": "array"}, "meta": {"$exists": true},
+                "debug": {"$exists": false}, "x": {"$type": "any"}}"#,
+        );
+        assert!(matches(
+            &m,
+            r#"{"id": "1", "tags": [], "meta": null, "x": {}}"#
+        ));
+        assert!(!matches(
+            &m,
+            r#"{"id": 1, "tags": [], "meta": null, "x": 0}"#
+        ));
+        assert!(!matches(&m, r#"{"id": "1", "tags": [], "x": 0}"#));
+        assert!(!matches(
+            &m,
+            r#"{"id": "1", "tags": [], "meta": 1, "debug": false, "x": 0}"#
+        ));
+        assert!(!matches(&m, r#"{"id": "1", "tags": [], "meta": 1}"#));
+
+        assert!(matches(&matcher(r#"{"$type": "number"}"#), "1.5"));
+        assert!(matches(&matcher(r#"[{"$exists": true}]"#), "[[1]]"));
+    }
+
+    #[test]
+    fn matcher_invalid_patterns() {
+        assert_eq!(
+            error(r#"{"a": [{"$type": "int"}]}"#),
+            r#"invalid pattern at "/a/0": unknown type for `$type`"#
+        );
+        assert_eq!(
+            error(r#"{"a": {"$exists": 1}}"#),
+            r#"invalid pattern at "/a": `$exists` requires a boolean"#
+        );
+        assert_eq!(
+            error(r#"{"$type": "string", "a": 1}"#),
+            r#"invalid pattern at "": operators can't be combined with other keys"#
+        );
+        assert_eq!(
+            error(r#"{"$regex": "a"}"#),
+            r#"invalid pattern at "": unknown operator"#
+        );
+        assert_eq!(
+            error(r#"{"$exists": false}"#),
+            r#"invalid pattern at "": `$exists: false` is only allowed for object members"#
+        );
+        assert_eq!(
+            error(r#"[{"$exists": false}]"#),
+            r#"invalid pattern at "/0": `$exists: false` is only allowed for object members"#
+        );
+    }
+}
@@ -0,0 +1,181 @@
+use crate::object_vec::key_str;
+use crate::{KeyStrType, ObjectAsVec, Value};
+
+/// How arrays are merged by [`Value::merge_with`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ArrayMergeStrategy {
+    /// The array of the other value replaces the array.
+    #[default]
+    Replace,
+    /// The elements of the array of the other value are appended to the array.
+    Concat,
+}
+
+impl<'ctx> Value<'ctx> {
+    /// Merges `other` recursively into `self`, arrays are replaced.
+    ///
+    /// See [`Value::merge_with`] for details.
+    ///
+    /// # Example
+    /// ```
+    /// use serde_json_borrow::Value;
+    ///
+    /// let mut config: Value = serde_json::from_str(r#"{"db": {"host": "localhost", "port": 5432}}"#).unwrap();
+    /// let user: Value = serde_json::from_str(r#"{"db": {"host": "db.example.com"}, "debug": true}"#).unwrap();
+    /// config.merge(user);
+    ///
+    /// assert_eq!(config, serde_json::json!({"db": {"host": "db.example.com", "port": 5432}, "debug": true}));
+    /// ```
+    pub fn merge(&mut self, other: Value<'ctx>) {
+        self.merge_with(other, ArrayMergeStrategy::Replace)
+    }
+
+    /// Merges `other` recursively into `self`.
+    ///
+    /// - If both values are objects, the entries of `other` are merged into the entries of `self`.
+    ///   Keys that don't exist in `self` are moved over, keys which exist in both are merged
+    ///   recursively.
+    /// - If both values are arrays, they are merged according to `arrays`.
+    /// - Otherwise `other` replaces `self`. This includes `null` values in `other`.
+    ///
+    /// Keys and strings are moved from `other`, so borrowed data is not copied.
+    pub fn merge_with(&mut self, other: Value<'ctx>, arrays: ArrayMergeStrategy) {
+        match (self, other) {
+            (Value::Object(target), Value::Object(other)) => {
+                for (key, value) in other.0 {
+                    match target.get_mut(key_str(&key)) {
+                        Some(existing) => existing.merge_with(value, arrays),
+                        None => target.insert_key_unchecked(key, value),
+                    }
+                }
+            }
+            (Value::Array(target), Value::Array(other)) if arrays == ArrayMergeStrategy::Concat => {
+                target.extend(other);
+            }
+            (target, other) => *target = other,
+        }
+    }
+
+    /// Applies a JSON Merge Patch (RFC 7386) to the value.
+    ///
+    /// If `patch` is an object, its entries are merged into `self` recursively, `null` values
+    /// remove the key. If `self` is no object, it is replaced by an empty object first. Any other
+    /// `patch` replaces `self`.
+    ///
+    /// # Example
+    /// ```
+    /// use serde_json_borrow::Value;
+    ///
+    /// let mut doc: Value = serde_json::from_str(r#"{"title": "Hello", "author": {"name": "John", "email": "j@x.com"}}"#).unwrap();
+    /// let patch: Value = serde_json::from_str(r#"{"title": "Hi", "author": {"email": null}, "tags": ["a"]}"#).unwrap();
+    /// doc.merge_patch(&patch);
+    ///
+    /// assert_eq!(doc, serde_json::json!({"title": "Hi", "author": {"name": "John"}, "tags": ["a"]}));
+    /// ```
+    pub fn merge_patch(&mut self, patch: &Value<'ctx>) {
+        let Value::Object(patch) = patch else {
+            *self = patch.clone();
+            return;
+        };
+        if !self.is_object() {
+            *self = Value::Object(ObjectAsVec::default());
+        }
+        let Value::Object(target) = self else {
+            unreachable!()
+        };
+        for (key, value) in &patch.0 {
+            if value.is_null() {
+                target.remove(key_str(key));
+            } else if let Some(existing) = target.get_mut(key_str(key)) {
+                existing.merge_patch(value);
+            } else {
+                // Merging into `null` removes `null` values from nested objects of the patch.
+                let mut new = Value::Null;
+                new.merge_patch(value);
+                target.insert_key_unchecked(KeyStrType::clone(key), new);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(json: &str) -> Value<'_> {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn merge_objects() {
+        let mut value = parse(r#"{"a": {"b": 1, "c": [1, 2]}, "d": "x", "e": {"f": 1}}"#);
+        value.merge(parse(
+            r#"{"a": {"b": {"x": 1}, "c": [3], "g": null}, "d": null, "e": 5, "h": []}"#,
+        ));
+        assert_eq!(
+            value,
+            serde_json::json!({"a": {"b": {"x": 1}, "c": [3], "g": null}, "d": null, "e": 5, "h": []})
+        );
+    }
+
+    #[test]
+    fn merge_concat_arrays() {
+        let mut value = parse(r#"{"a": [1, 2], "b": {"c": ["x"]}, "d": 1}"#);
+        value.merge_with(
+            parse(r#"{"a": [3], "b": {"c": ["y", "z"]}, "d": [1]}"#),
+            ArrayMergeStrategy::Concat,
+        );
+        assert_eq!(
+            value,
+            serde_json::json!({"a": [1, 2, 3], "b": {"c": ["x", "y", "z"]}, "d": [1]})
+        );
+
+        let mut value = parse("[1]");
+        value.merge_with(parse("[2]"), ArrayMergeStrategy::Replace);
+        assert_eq!(value, parse("[2]"));
+    }
+
+    #[test]
+    fn merge_scalars() {
+        let mut value = parse(r#"{"a": 1}"#);
+        value.merge(parse("true"));
+        assert_eq!(value, Value::Bool(true));
+        value.merge(parse(r#"{"a": 1}"#));
+        assert_eq!(value, parse(r#"{"a": 1}"#));
+    }
+
+    #[test]
+    fn merge_patch_rfc7386_examples() {
+        // Test cases from RFC 7386 Appendix A
+        let cases = [
+            (r#"{"a":"b"}"#, r#"{"a":"c"}"#, r#"{"a":"c"}"#),
+            (r#"{"a":"b"}"#, r#"{"b":"c"}"#, r#"{"a":"b","b":"c"}"#),
+            (r#"{"a":"b"}"#, r#"{"a":null}"#, r#"{}"#),
+            (r#"{"a":"b","b":"c"}"#, r#"{"a":null}"#, r#"{"b":"c"}"#),
+            (r#"{"a":["b"]}"#, r#"{"a":"c"}"#, r#"{"a":"c"}"#),
+            (r#"{"a":"c"}"#, r#"{"a":["b"]}"#, r#"{"a":["b"]}"#),
+            (
+                r#"{"a":{"b":"c"}}"#,
+                r#"{"a":{"b":"d","c":null}}"#,
+                r#"{"a":{"b":"d"}}"#,
+            ),
+            (r#"{"a":[{"b":"c"}]}"#, r#"{"a":[1]}"#, r#"{"a":[1]}"#),
+            (r#"["a","b"]"#, r#"["c","d"]"#, r#"["c","d"]"#),
+            (r#"{"a":"b"}"#, r#"["c"]"#, r#"["c"]"#),
+            (r#"{"a":"foo"}"#, "null", "null"),
+            (r#"{"a":"foo"}"#, r#""bar""#, r#""bar""#),
+            (r#"{"e":null}"#, r#"{"a":1}"#, r#"{"e":null,"a":1}"#),
+            (r#"[1,2]"#, r#"{"a":"b","c":null}"#, r#"{"a":"b"}"#),
+            (
+                r#"{}"#,
+                r#"{"a":{"bb":{"ccc":null}}}"#,
+                r#"{"a":{"bb":{}}}"#,
+            ),
+        ];
+        for (target, patch, expected) in cases {
+            let mut target = parse(target);
+            target.merge_patch(&parse(patch));
+            assert_eq!(target, parse(expected), "patch {patch}");
+        }
+    }
+}
@@ -0,0 +1,136 @@
+use std::io::{self, BufRead};
+
+use crate::OwnedValue;
+
+/// What [`NdjsonReader`] does with a line that isn't valid JSON.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NdjsonErrorPolicy {
+    /// Return the error and stop reading.
+    #[default]
+    Abort,
+    /// Skip the line and continue with the next one. The skipped lines are counted, see
+    /// [`NdjsonReader::skipped`].
+    Skip,
+}
+
+/// Reads newline delimited JSON (NDJSON) from a reader, one [`OwnedValue`] per line.
+///
+/// Empty lines and lines with only whitespace are skipped. A line, which isn't valid UTF-8 or
+/// JSON, is handled according to the [`NdjsonErrorPolicy`], its error contains the line number.
+/// Errors of the reader always stop reading.
+///
+/// To parse NDJSON, which is already in memory, see [`OwnedValue::parse_many`].
+///
+/// # Example
+/// ```
+/// use serde_json_borrow::{NdjsonErrorPolicy, NdjsonReader};
+///
+/// let data = "{\"id\": 1}\n\n{\"id\": \n{\"id\": 3}\n";
+/// let mut reader = NdjsonReader::new(data.as_bytes()).with_error_policy(NdjsonErrorPolicy::Skip);
+/// let ids: Vec<_> = reader
+///     .by_ref()
+///     .map(|value| value.unwrap().get("id").as_u64())
+///     .collect();
+/// assert_eq!(ids, [Some(1), Some(3)]);
+/// assert_eq!(reader.skipped(), 1);
+/// ```
+#[derive(Debug)]
+pub struct NdjsonReader<R> {
+    reader: R,
+    policy: NdjsonErrorPolicy,
+    line_number: usize,
+    skipped: usize,
+    done: bool,
+}
+
+impl<R: BufRead> NdjsonReader<R> {
+    /// Creates a reader with the default [`NdjsonErrorPolicy::Abort`].
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            policy: NdjsonErrorPolicy::default(),
+            line_number: 0,
+            skipped: 0,
+            done: false,
+        }
+    }
+
+    /// Sets what to do with lines, which aren't valid JSON.
+    pub fn with_error_policy(mut self, policy: NdjsonErrorPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Returns the number of lines read so far, including empty and skipped lines.
+    pub fn line_number(&self) -> usize {
+        self.line_number
+    }
+
+    /// Returns the number of invalid lines skipped with [`NdjsonErrorPolicy::Skip`].
+    pub fn skipped(&self) -> usize {
+        self.skipped
+    }
+
+    /// Returns the inner reader.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R: BufRead> Iterator for NdjsonReader<R> {
+    type Item = io::Result<OwnedValue>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.done {
+            // Each line gets its own buffer, which is moved into the `OwnedValue`.
+            let mut line = Vec::new();
+            match self.reader.read_until(b'\n', &mut line) {
+                Ok(0) => self.done = true,
+                Ok(_) => {
+                    self.line_number += 1;
+                    if line.iter().all(u8::is_ascii_whitespace) {
+                        continue;
+                    }
+                    match OwnedValue::from_vec(line) {
+                        Ok(value) => return Some(Ok(value)),
+                        Err(_) if self.policy == NdjsonErrorPolicy::Skip => self.skipped += 1,
+                        Err(err) => {
+                            self.done = true;
+                            let msg = format!("line {}: {err}", self.line_number);
+                            return Some(Err(io::Error::new(err.kind(), msg)));
+                        }
+                    }
+                }
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+                Err(err) => {
+                    self.done = true;
+                    return Some(Err(err));
+                }
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ndjson_reader_test() {
+        let data = b"{\"a\": 1}\r\n  \n[1,\n\xff\n\"x\"";
+        let values: Vec<_> = NdjsonReader::new(&data[..]).collect();
+        assert_eq!(values.len(), 2);
+        assert_eq!(values[0].as_ref().unwrap().get("a"), &1_u64);
+        let err = values[1].as_ref().unwrap_err();
+        assert!(err.to_string().starts_with("line 3: "), "{err}");
+
+        let mut reader = NdjsonReader::new(&data[..]).with_error_policy(NdjsonErrorPolicy::Skip);
+        let values: Vec<_> = reader.by_ref().map(Result::unwrap).collect();
+        assert_eq!(values.len(), 2);
+        assert_eq!(values[1].get_value(), "x");
+        assert_eq!(reader.skipped(), 2);
+        assert_eq!(reader.line_number(), 5);
+        assert!(reader.next().is_none());
+    }
+}
@@ -0,0 +1,178 @@
+use std::borrow::Cow;
+
+use crate::{ObjectAsVec, Value};
+
+/// The letter case for [`Value::normalize_keys`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Case {
+    /// Lowercase, e.g. `content-type`.
+    Lower,
+    /// Uppercase, e.g. `CONTENT-TYPE`.
+    Upper,
+}
+
+impl Case {
+    /// Returns true if converting `text` to this case would change it.
+    fn changes(self, text: &str) -> bool {
+        text.chars().any(|c| match self {
+            Case::Lower => !c.to_lowercase().eq([c]),
+            Case::Upper => !c.to_uppercase().eq([c]),
+        })
+    }
+
+    fn convert(self, text: &str) -> String {
+        match self {
+            Case::Lower => text.to_lowercase(),
+            Case::Upper => text.to_uppercase(),
+        }
+    }
+}
+
+impl Value<'_> {
+    /// Converts all object keys, including keys of nested objects, to `case`.
+    ///
+    /// Keys which are already in the requested case are kept as they are, so borrowed keys stay
+    /// borrowed and only changed keys are allocated.
+    ///
+    /// If multiple keys of an object are equal after the conversion, only one entry is kept, at
+    /// the position of the first of them. Its value is the value of the smallest original key in
+    /// byte order, e.g. `"A"` wins over `"a"`, and of the first entry for identical original keys.
+    /// The rule doesn't depend on the order of the entries, so the result is the same with and
+    /// without the `btree` feature flag, which sorts the entries during deserialization.
+    ///
+    /// # Example
+    /// ```
+    /// use serde_json_borrow::{Case, Value};
+    ///
+    /// let mut value: Value =
+    ///     serde_json::from_str(r#"{"Content-Type": "json", "headers": {"X-Id": 1}}"#).unwrap();
+    /// value.normalize_keys(Case::Lower);
+    /// assert_eq!(value, serde_json::json!({"content-type": "json", "headers": {"x-id": 1}}));
+    /// ```
+    pub fn normalize_keys(&mut self, case: Case) {
+        match self {
+            Value::Array(arr) => {
+                for value in arr {
+                    value.normalize_keys(case);
+                }
+            }
+            Value::Object(obj) => {
+                for (_, value) in obj.0.iter_mut() {
+                    value.normalize_keys(case);
+                }
+                if obj.0.iter().any(|(key, _)| case.changes(key)) {
+                    *obj = normalize_object(std::mem::take(&mut obj.0), case);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Converts the keys of `entries`, see [`Value::normalize_keys`] for the handling of collisions.
+fn normalize_object<'ctx>(
+    entries: Vec<(Cow<'ctx, str>, Value<'ctx>)>,
+    case: Case,
+) -> ObjectAsVec<'ctx> {
+    // (normalized key, original key, value)
+    let mut entries: Vec<_> = entries
+        .into_iter()
+        .map(|(key, value)| {
+            let normalized = if case.changes(&key) {
+                Cow::Owned(case.convert(&key))
+            } else {
+                key.clone()
+            };
+            (normalized, key, Some(value))
+        })
+        .collect();
+
+    // Entries with the same normalized key are adjacent in `order`, the winner first.
+    let mut order: Vec<usize> = (0..entries.len()).collect();
+    order.sort_by(|&a, &b| {
+        let (a, b) = (&entries[a], &entries[b]);
+        (&a.0, &a.1).cmp(&(&b.0, &b.1))
+    });
+    // (position, winner)
+    let mut kept: Vec<(usize, usize)> = Vec::with_capacity(order.len());
+    for idx in order {
+        match kept.last_mut() {
+            Some((pos, winner)) if entries[*winner].0 == entries[idx].0 => *pos = (*pos).min(idx),
+            _ => kept.push((idx, idx)),
+        }
+    }
+    kept.sort_unstable();
+
+    ObjectAsVec::from_entries(
+        kept.into_iter()
+            .map(|(_, winner)| {
+                let (key, _, value) = &mut entries[winner];
+                (
+                    std::mem::take(key),
+                    value.take().expect("each entry wins at most once"),
+                )
+            })
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn is_borrowed(value: &Value, key: &str) -> bool {
+        let obj = value.as_object().unwrap();
+        matches!(
+            obj.as_vec().iter().find(|(k, _)| k == key),
+            Some((Cow::Borrowed(_), _))
+        )
+    }
+
+    #[test]
+    fn normalize_keys_lower() {
+        // Converted from `serde_json::Value`, so all keys are borrowed
+        let json: serde_json::Value =
+            serde_json::from_str(r#"{"a": [{"B": 1}], "CamelCase": true, "ΣΑΣ": null}"#).unwrap();
+        let mut value = Value::from(&json);
+        value.normalize_keys(Case::Lower);
+        assert_eq!(
+            value,
+            serde_json::json!({"a": [{"b": 1}], "camelcase": true, "σας": null})
+        );
+        assert!(is_borrowed(&value, "a"));
+        assert!(!is_borrowed(&value, "camelcase"));
+
+        value.normalize_keys(Case::Upper);
+        assert_eq!(
+            value,
+            serde_json::json!({"A": [{"B": 1}], "CAMELCASE": true, "ΣΑΣ": null})
+        );
+    }
+
+    #[test]
+    fn normalize_keys_collision() {
+        // The smallest original key wins, independent of the order of the entries.
+        for json in [
+            r#"{"a": 1, "b": 2, "A": 3}"#,
+            r#"{"A": 3, "b": 2, "a": 1}"#,
+            r#"{"b": 2, "a": 1, "A": 3}"#,
+        ] {
+            let mut value: Value = serde_json::from_str(json).unwrap();
+            value.normalize_keys(Case::Lower);
+            assert_eq!(value, serde_json::json!({"a": 3, "b": 2}));
+            assert_eq!(value.as_object().unwrap().len(), 2);
+        }
+
+        let mut value: Value = serde_json::from_str(r#"{"aB": 1, "Ab": 2}"#).unwrap();
+        value.normalize_keys(Case::Upper);
+        assert_eq!(value, serde_json::json!({"AB": 2}));
+
+        // For identical original keys the first entry wins.
+        let mut value: Value = serde_json::from_str(r#"{"ab": 1, "x": 2, "ab": 3}"#).unwrap();
+        value.normalize_keys(Case::Upper);
+        assert_eq!(value, serde_json::json!({"AB": 1, "X": 2}));
+        let mut value: Value = serde_json::from_str(r#"{"x": 1, "B": 2, "b": 3}"#).unwrap();
+        value.normalize_keys(Case::Upper);
+        assert_eq!(value, serde_json::json!({"X": 1, "B": 2}));
+    }
+}
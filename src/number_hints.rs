@@ -0,0 +1,155 @@
+use std::io;
+
+use crate::value::{Number, N};
+use crate::Value;
+
+/// The numeric type a value at a path should be stored as.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NumberHint {
+    /// Store the value as an unsigned integer.
+    U64,
+    /// Store the value as a signed integer.
+    I64,
+    /// Store the value as a floating point number.
+    F64,
+}
+
+/// Per-path numeric type hints for parsing.
+///
+/// Paths are JSON Pointers (e.g. `/id` or `/items/0/score`). Values at these paths are converted
+/// into the requested `Number` variant, if that is possible without losing precision:
+///
+/// - `U64` and `I64` accept integers, floats without a fractional part and strings containing an
+///   integer (e.g. `"123"`).
+/// - `F64` accepts any number and strings containing a number.
+///
+/// Values that can't be converted are left untouched.
+///
+/// # Example
+/// ```
+/// use serde_json_borrow::{from_str_with_number_hints, NumberHint, NumberHints, Value};
+///
+/// let hints = NumberHints::new()
+///     .add("/id", NumberHint::U64)
+///     .add("/score", NumberHint::F64);
+///
+/// let value = from_str_with_number_hints(r#"{"id": "42", "score": 7}"#, &hints).unwrap();
+/// assert_eq!(value.get("id"), &Value::from(42u64));
+/// assert_eq!(value.get("score"), &Value::from(7.0));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NumberHints {
+    hints: Vec<(String, NumberHint)>,
+}
+
+impl NumberHints {
+    /// Creates an empty set of hints.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a hint for the value at the JSON Pointer `pointer`.
+    pub fn add(mut self, pointer: impl Into<String>, hint: NumberHint) -> Self {
+        self.hints.push((pointer.into(), hint));
+        self
+    }
+
+    /// Converts the values at the hinted paths in `value` into the requested number types.
+    pub fn apply(&self, value: &mut Value) {
+        for (pointer, hint) in &self.hints {
+            if let Some(target) = value.pointer_mut(pointer) {
+                if let Some(number) = convert(target, *hint) {
+                    *target = Value::Number(number);
+                }
+            }
+        }
+    }
+}
+
+/// Parses `json` into a `Value` and converts numbers according to the provided `hints`.
+///
+/// See [`NumberHints`] for the conversion rules.
+pub fn from_str_with_number_hints<'ctx>(
+    json: &'ctx str,
+    hints: &NumberHints,
+) -> io::Result<Value<'ctx>> {
+    let mut value: Value = serde_json::from_str(json)?;
+    hints.apply(&mut value);
+    Ok(value)
+}
+
+fn convert(value: &Value, hint: NumberHint) -> Option<Number> {
+    match hint {
+        NumberHint::U64 => to_i128(value)
+            .and_then(|v| u64::try_from(v).ok())
+            .map(Number::from),
+        NumberHint::I64 => to_i128(value)
+            .and_then(|v| i64::try_from(v).ok())
+            .map(Number::from),
+        NumberHint::F64 => match value {
+            Value::Number(n) => n.as_f64(),
+            Value::Str(text) => text.trim().parse::<f64>().ok().filter(|v| v.is_finite()),
+            _ => None,
+        }
+        .map(|v| N::Float(v).into()),
+    }
+}
+
+/// Returns the value as integer, if it is an integer, a float without fractional part or a string
+/// containing an integer.
+fn to_i128(value: &Value) -> Option<i128> {
+    match value {
+        Value::Number(n) => match n.n {
+            N::PosInt(v) => Some(v as i128),
+            N::NegInt(v) => Some(v as i128),
+            // The float is in range of i128 and has no fractional part, so the cast is exact.
+            N::Float(v) if v.fract() == 0.0 && v.abs() < 2f64.powi(64) => Some(v as i128),
+            N::Float(_) => None,
+        },
+        Value::Str(text) => text.trim().parse::<i128>().ok(),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn number_hints_integers() {
+        let json = r#"{"a": "12", "b": 3.0, "c": -4, "d": 2.5, "e": "x", "f": -1.0}"#;
+        let hints = NumberHints::new()
+            .add("/a", NumberHint::U64)
+            .add("/b", NumberHint::U64)
+            .add("/c", NumberHint::U64)
+            .add("/d", NumberHint::I64)
+            .add("/e", NumberHint::I64)
+            .add("/f", NumberHint::I64)
+            .add("/missing", NumberHint::I64);
+        let value = from_str_with_number_hints(json, &hints).unwrap();
+
+        assert_eq!(value.get("a"), &Value::Number(N::PosInt(12).into()));
+        assert_eq!(value.get("b"), &Value::Number(N::PosInt(3).into()));
+        // negative values are no u64
+        assert_eq!(value.get("c"), &Value::Number(N::NegInt(-4).into()));
+        assert_eq!(value.get("d"), &Value::Number(N::Float(2.5).into()));
+        assert_eq!(value.get("e"), &Value::Str("x".into()));
+        assert_eq!(value.get("f"), &Value::Number(N::NegInt(-1).into()));
+    }
+
+    #[test]
+    fn number_hints_floats() {
+        let json = r#"{"list": [1, "2.5", -3, true]}"#;
+        let hints = NumberHints::new()
+            .add("/list/0", NumberHint::F64)
+            .add("/list/1", NumberHint::F64)
+            .add("/list/2", NumberHint::F64)
+            .add("/list/3", NumberHint::F64);
+        let value = from_str_with_number_hints(json, &hints).unwrap();
+
+        assert_eq!(value.get("list").get(0), &Value::from(1.0));
+        assert_eq!(value.get("list").get(1), &Value::from(2.5));
+        assert_eq!(value.get("list").get(2), &Value::from(-3.0));
+        assert_eq!(value.get("list").get(3), &Value::Bool(true));
+    }
+}
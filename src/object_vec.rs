@@ -2,6 +2,7 @@
 #![allow(clippy::useless_asref)]
 
 use std::borrow::Cow;
+use std::collections::HashSet;
 
 use crate::Value;
 
@@ -22,7 +23,19 @@ pub type KeyStrType<'a> = &'a str;
 /// The ObjectAsVec struct is a wrapper around a Vec of (&str, Value) pairs.
 /// It provides methods to make it easy to migrate from serde_json::Value::Object or
 /// serde_json::Map.
-#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+///
+/// ## `btree` feature flag
+/// With the `btree` feature flag, the entries are kept sorted by key in the Vec. It is not a
+/// `BTreeMap`, but lookups use binary search and are `O(log n)`, at the cost of sorting the
+/// entries during deserialization and shifting entries on insertion. Iteration is in key order
+/// instead of insertion order. Duplicate keys are kept like without the feature flag, in their
+/// input order, and lookups return the first of them.
+///
+/// **The feature flag is not additive**: Cargo unifies features, so if any crate in the
+/// dependency graph enables `btree`, the key order changes for every crate using
+/// `serde_json_borrow`. Libraries should not enable it, and should not depend on the key order
+/// of objects.
+#[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct ObjectAsVec<'ctx>(pub(crate) Vec<(KeyStrType<'ctx>, Value<'ctx>)>);
 
 #[cfg(feature = "cowkeys")]
@@ -35,17 +48,142 @@ impl<'ctx> From<Vec<(&'ctx str, Value<'ctx>)>> for ObjectAsVec<'ctx> {
 #[cfg(not(feature = "cowkeys"))]
 impl<'ctx> From<Vec<(&'ctx str, Value<'ctx>)>> for ObjectAsVec<'ctx> {
     fn from(vec: Vec<(&'ctx str, Value<'ctx>)>) -> Self {
-        Self(vec)
+        Self::from_entries(vec)
+    }
+}
+
+/// Converts a `Cow<str>` into the key type. Returns `None` if the key is owned, but the
+/// `cowkeys` feature flag is not enabled.
+#[cfg(feature = "cowkeys")]
+#[inline]
+pub(crate) fn key_from_cow(key: Cow<'_, str>) -> Option<KeyStrType<'_>> {
+    Some(key)
+}
+
+/// Converts a `Cow<str>` into the key type. Returns `None` if the key is owned, but the
+/// `cowkeys` feature flag is not enabled.
+#[cfg(not(feature = "cowkeys"))]
+#[inline]
+pub(crate) fn key_from_cow(key: Cow<'_, str>) -> Option<KeyStrType<'_>> {
+    match key {
+        Cow::Borrowed(key) => Some(key),
+        Cow::Owned(_) => None,
+    }
+}
+
+/// Removes the first `len` bytes of the key. Borrowed keys stay borrowed.
+#[cfg(feature = "cowkeys")]
+#[inline]
+fn strip_key(key: &mut KeyStrType<'_>, len: usize) {
+    match key {
+        Cow::Borrowed(text) => *text = &text[len..],
+        Cow::Owned(text) => {
+            text.drain(..len);
+        }
     }
 }
 
+/// Removes the first `len` bytes of the key.
+#[cfg(not(feature = "cowkeys"))]
+#[inline]
+fn strip_key(key: &mut KeyStrType<'_>, len: usize) {
+    *key = &key[len..];
+}
+
+/// Returns the key as `&str`, independent of the `cowkeys` feature flag.
+#[inline]
+pub(crate) fn key_str<'a>(key: &'a KeyStrType<'_>) -> &'a str {
+    key.as_ref()
+}
+
 impl<'ctx> FromIterator<(&'ctx str, Value<'ctx>)> for ObjectAsVec<'ctx> {
     fn from_iter<T: IntoIterator<Item = (&'ctx str, Value<'ctx>)>>(iter: T) -> Self {
-        Self(iter.into_iter().map(|(k, v)| (k.into(), v)).collect())
+        Self::from_entries(iter.into_iter().map(|(k, v)| (k.into(), v)).collect())
     }
 }
 
 impl<'ctx> ObjectAsVec<'ctx> {
+    /// Creates an object from the entries. With the `btree` feature flag the entries are sorted
+    /// by key. The sort is stable, so duplicate keys keep their input order.
+    #[inline]
+    #[cfg_attr(not(feature = "btree"), allow(unused_mut))]
+    pub(crate) fn from_entries(mut entries: Vec<(KeyStrType<'ctx>, Value<'ctx>)>) -> Self {
+        #[cfg(feature = "btree")]
+        entries.sort_by(|a, b| key_str(&a.0).cmp(key_str(&b.0)));
+        Self(entries)
+    }
+
+    /// Returns the position of the first entry with `key`. If the key does not exist, `Err`
+    /// contains the position where it should be inserted.
+    #[inline]
+    fn search(&self, key: &str) -> Result<usize, usize> {
+        #[cfg(feature = "btree")]
+        {
+            let pos = self.0.partition_point(|(k, _)| key_str(k) < key);
+            match self.0.get(pos) {
+                Some((k, _)) if key_str(k) == key => Ok(pos),
+                _ => Err(pos),
+            }
+        }
+        #[cfg(not(feature = "btree"))]
+        {
+            self.0
+                .iter()
+                .position(|(k, _)| *k == key)
+                .ok_or(self.0.len())
+        }
+    }
+
+    /// Returns the position where `key` should be inserted, without checking if it exists. An
+    /// existing key is inserted after its duplicates, like it is appended without the `btree`
+    /// feature flag.
+    #[inline]
+    fn insert_position(&self, key: &str) -> usize {
+        #[cfg(feature = "btree")]
+        {
+            self.0.partition_point(|(k, _)| key_str(k) <= key)
+        }
+        #[cfg(not(feature = "btree"))]
+        {
+            let _ = key;
+            self.0.len()
+        }
+    }
+
+    /// Inserts the entry at `pos` and returns a mutable reference to the value.
+    #[inline]
+    fn insert_at(
+        &mut self,
+        pos: usize,
+        key: KeyStrType<'ctx>,
+        value: Value<'ctx>,
+    ) -> &mut Value<'ctx> {
+        self.0.insert(pos, (key, value));
+        &mut self.0[pos].1
+    }
+
+    /// Creates an object from entries with owned keys, e.g. generated key names.
+    ///
+    /// The keys are moved into the object, so they are not copied.
+    ///
+    /// # Example
+    /// ```
+    /// use serde_json_borrow::{ObjectAsVec, Value};
+    ///
+    /// let entries = (0..3).map(|i| (format!("field_{i}"), Value::from(i))).collect();
+    /// let obj = ObjectAsVec::from_owned_entries(entries);
+    /// assert_eq!(obj.get("field_2"), Some(&Value::from(2)));
+    /// ```
+    #[cfg(feature = "cowkeys")]
+    pub fn from_owned_entries(entries: Vec<(String, Value<'ctx>)>) -> Self {
+        Self::from_entries(
+            entries
+                .into_iter()
+                .map(|(key, value)| (Cow::Owned(key), value))
+                .collect(),
+        )
+    }
+
     /// Access to the underlying Vec.
     ///
     /// # Note
@@ -53,7 +191,7 @@ impl<'ctx> ObjectAsVec<'ctx> {
     /// methods instead. This could be a problem with feature unification, when one crate uses it
     /// as &str and another uses it as Cow<str>, both will get Cow<str?
     #[inline]
-    pub fn as_vec(&self) -> &Vec<(KeyStrType, Value<'ctx>)> {
+    pub fn as_vec(&self) -> &Vec<(KeyStrType<'_>, Value<'ctx>)> {
         &self.0
     }
 
@@ -65,42 +203,91 @@ impl<'ctx> ObjectAsVec<'ctx> {
 
     /// Returns a reference to the value corresponding to the key.
     ///
+    /// The key may be any type that can be viewed as `str`, e.g. `str`, `String` or `Cow<str>`.
+    /// This applies to all lookup methods of `ObjectAsVec`.
+    ///
     /// ## Performance
     /// As this is backed by a Vec, this searches linearly through the Vec as may be much more
-    /// expensive than a `Hashmap` for larger Objects.
+    /// expensive than a `Hashmap` for larger Objects. With the `btree` feature flag the key is
+    /// found with a binary search.
     #[inline]
-    pub fn get(&self, key: &str) -> Option<&Value<'ctx>> {
-        self.0
-            .iter()
-            .find_map(|(k, v)| if *k == key { Some(v) } else { None })
+    pub fn get<Q: AsRef<str> + ?Sized>(&self, key: &Q) -> Option<&Value<'ctx>> {
+        let pos = self.search(key.as_ref()).ok()?;
+        Some(&self.0[pos].1)
+    }
+
+    /// Returns the value of `key` if it is a string. Returns None if the key doesn't exist or the
+    /// value has a different type.
+    #[inline]
+    pub fn get_str<Q: AsRef<str> + ?Sized>(&self, key: &Q) -> Option<&str> {
+        self.get(key)?.as_str()
+    }
+
+    /// Returns the value of `key` if it is an integer representable as i64. Returns None if the key
+    /// doesn't exist or the value has a different type.
+    #[inline]
+    pub fn get_i64<Q: AsRef<str> + ?Sized>(&self, key: &Q) -> Option<i64> {
+        self.get(key)?.as_i64()
+    }
+
+    /// Returns the value of `key` if it is an integer representable as u64. Returns None if the key
+    /// doesn't exist or the value has a different type.
+    #[inline]
+    pub fn get_u64<Q: AsRef<str> + ?Sized>(&self, key: &Q) -> Option<u64> {
+        self.get(key)?.as_u64()
+    }
+
+    /// Returns the value of `key` if it is a number, represented as f64. Returns None if the key
+    /// doesn't exist or the value has a different type.
+    #[inline]
+    pub fn get_f64<Q: AsRef<str> + ?Sized>(&self, key: &Q) -> Option<f64> {
+        self.get(key)?.as_f64()
+    }
+
+    /// Returns the value of `key` if it is a boolean. Returns None if the key doesn't exist or the
+    /// value has a different type.
+    #[inline]
+    pub fn get_bool<Q: AsRef<str> + ?Sized>(&self, key: &Q) -> Option<bool> {
+        self.get(key)?.as_bool()
+    }
+
+    /// Returns the value of `key` if it is an array. Returns None if the key doesn't exist or the
+    /// value has a different type.
+    #[inline]
+    pub fn get_array<Q: AsRef<str> + ?Sized>(&self, key: &Q) -> Option<&[Value<'ctx>]> {
+        self.get(key)?.as_array()
+    }
+
+    /// Returns the value of `key` if it is an object. Returns None if the key doesn't exist or the
+    /// value has a different type.
+    #[inline]
+    pub fn get_object<Q: AsRef<str> + ?Sized>(&self, key: &Q) -> Option<&ObjectAsVec<'ctx>> {
+        self.get(key)?.as_object()
     }
 
     /// Returns a mutable reference to the value corresponding to the key, if it exists.
     ///
     /// ## Performance
     /// As this is backed by a Vec, this searches linearly through the Vec as may be much more
-    /// expensive than a `Hashmap` for larger Objects.
+    /// expensive than a `Hashmap` for larger Objects. With the `btree` feature flag the key is
+    /// found with a binary search.
     #[inline]
-    pub fn get_mut(&mut self, key: &str) -> Option<&mut Value<'ctx>> {
-        self.0
-            .iter_mut()
-            .find_map(|(k, v)| if *k == key { Some(v) } else { None })
+    pub fn get_mut<Q: AsRef<str> + ?Sized>(&mut self, key: &Q) -> Option<&mut Value<'ctx>> {
+        let pos = self.search(key.as_ref()).ok()?;
+        Some(&mut self.0[pos].1)
     }
 
     /// Returns the key-value pair corresponding to the supplied key.
     ///
     /// ## Performance
     /// As this is backed by a Vec, this searches linearly through the Vec as may be much more
-    /// expensive than a `Hashmap` for larger Objects.
+    /// expensive than a `Hashmap` for larger Objects. With the `btree` feature flag the key is
+    /// found with a binary search.
     #[inline]
-    pub fn get_key_value(&self, key: &str) -> Option<(&str, &Value<'ctx>)> {
-        self.0.iter().find_map(|(k, v)| {
-            if *k == key {
-                Some((k.as_ref(), v))
-            } else {
-                None
-            }
-        })
+    pub fn get_key_value<Q: AsRef<str> + ?Sized>(&self, key: &Q) -> Option<(&str, &Value<'ctx>)> {
+        let pos = self.search(key.as_ref()).ok()?;
+        let (k, v) = &self.0[pos];
+        Some((k.as_ref(), v))
     }
 
     /// An iterator visiting all key-value pairs
@@ -109,6 +296,25 @@ impl<'ctx> ObjectAsVec<'ctx> {
         self.0.iter().map(|(k, v)| (k.as_ref(), v))
     }
 
+    /// An iterator visiting all key-value pairs, with mutable references to the values.
+    ///
+    /// # Example
+    /// ```
+    /// use serde_json_borrow::Value;
+    ///
+    /// let mut value: Value = serde_json::from_str(r#"{"a": 1, "b": "x", "c": 2}"#).unwrap();
+    /// for (key, val) in value.as_object_mut().unwrap().iter_mut() {
+    ///     if key != "b" {
+    ///         *val = Value::Null;
+    ///     }
+    /// }
+    /// assert_eq!(value, serde_json::json!({"a": null, "b": "x", "c": null}));
+    /// ```
+    #[inline]
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (&str, &mut Value<'ctx>)> {
+        self.0.iter_mut().map(|(k, v)| (key_str(k), v))
+    }
+
     /// Returns the number of elements in the object
     #[inline]
     pub fn len(&self) -> usize {
@@ -121,12 +327,183 @@ impl<'ctx> ObjectAsVec<'ctx> {
         self.0.is_empty()
     }
 
+    /// Splits the object into two at the given index.
+    ///
+    /// Returns a newly allocated object containing the entries in the range `[at, len)`. After
+    /// the call, the original object will be left containing the entries `[0, at)`. The entries
+    /// keep their order, which is the insertion order, or the key order with the `btree` feature
+    /// flag.
+    ///
+    /// # Panics
+    /// Panics if `at > len`.
+    #[inline]
+    pub fn split_off(&mut self, at: usize) -> ObjectAsVec<'ctx> {
+        ObjectAsVec(self.0.split_off(at))
+    }
+
+    /// Shortens the object, keeping the first `len` entries and dropping the rest.
+    ///
+    /// If `len` is greater or equal to the object's current length, this has no effect.
+    #[inline]
+    pub fn truncate(&mut self, len: usize) {
+        self.0.truncate(len);
+    }
+
+    /// Releases the unused capacity of the entries. Nested values are not shrunk, see
+    /// [`Value::shrink_to_fit`] for that.
+    #[inline]
+    pub fn shrink_to_fit(&mut self) {
+        self.0.shrink_to_fit();
+    }
+
+    /// Returns the range of entries whose keys start with `prefix`. With the `btree` feature flag
+    /// these entries are adjacent.
+    #[cfg(feature = "btree")]
+    #[inline]
+    fn prefix_range(&self, prefix: &str) -> std::ops::Range<usize> {
+        let start = self.0.partition_point(|(k, _)| key_str(k) < prefix);
+        let len = self.0[start..]
+            .iter()
+            .take_while(|(k, _)| key_str(k).starts_with(prefix))
+            .count();
+        start..start + len
+    }
+
+    /// An iterator visiting all entries whose keys start with `prefix`, e.g. all attributes of
+    /// the namespace `http.`.
+    ///
+    /// ## Performance
+    /// This searches linearly through the Vec. With the `btree` feature flag the first entry is
+    /// found with a binary search.
+    #[inline]
+    pub fn iter_prefix<'a>(
+        &'a self,
+        prefix: &'a str,
+    ) -> impl Iterator<Item = (&'a str, &'a Value<'ctx>)> + 'a {
+        #[cfg(feature = "btree")]
+        let entries = &self.0[self.prefix_range(prefix)];
+        #[cfg(not(feature = "btree"))]
+        let entries = &self.0;
+        entries
+            .iter()
+            .map(|(k, v)| (key_str(k), v))
+            .filter(move |(k, _)| k.starts_with(prefix))
+    }
+
+    /// Removes all entries whose keys start with `prefix` and returns them as a new object.
+    ///
+    /// The keys are not modified, use [`ObjectAsVec::strip_prefix_keys`] on the returned object
+    /// to remove the prefix.
+    pub fn remove_prefix(&mut self, prefix: &str) -> ObjectAsVec<'ctx> {
+        #[cfg(feature = "btree")]
+        {
+            let range = self.prefix_range(prefix);
+            ObjectAsVec(self.0.drain(range).collect())
+        }
+        #[cfg(not(feature = "btree"))]
+        {
+            let (removed, kept) = std::mem::take(&mut self.0)
+                .into_iter()
+                .partition(|(k, _)| key_str(k).starts_with(prefix));
+            self.0 = kept;
+            ObjectAsVec(removed)
+        }
+    }
+
+    /// Removes `prefix` from all keys which start with it, e.g. `http.method` becomes `method`
+    /// for the prefix `http.`. Borrowed keys stay borrowed.
+    ///
+    /// If a stripped key already exists in the object, the existing entry is removed.
+    ///
+    /// ## Performance
+    /// This operation is linear in the size of the Vec. With the `btree` feature flag the entries
+    /// are sorted again afterwards.
+    pub fn strip_prefix_keys(&mut self, prefix: &str) {
+        let mut stripped = vec![false; self.0.len()];
+        for ((key, _), stripped) in self.0.iter_mut().zip(stripped.iter_mut()) {
+            if key_str(key).starts_with(prefix) {
+                strip_key(key, prefix.len());
+                *stripped = true;
+            }
+        }
+        if !stripped.contains(&true) {
+            return;
+        }
+        let stripped_keys: HashSet<&str> = self
+            .0
+            .iter()
+            .zip(&stripped)
+            .filter(|(_, is_stripped)| **is_stripped)
+            .map(|((key, _), _)| key_str(key))
+            .collect();
+        let replaced: Vec<bool> = self
+            .0
+            .iter()
+            .zip(&stripped)
+            .map(|((key, _), is_stripped)| !is_stripped && stripped_keys.contains(key_str(key)))
+            .collect();
+        let mut replaced = replaced.into_iter();
+        self.0.retain(|_| !replaced.next().unwrap_or(false));
+        #[cfg(feature = "btree")]
+        self.0.sort_by(|a, b| key_str(&a.0).cmp(key_str(&b.0)));
+    }
+
     /// An iterator visiting all keys
     #[inline]
     pub fn keys(&self) -> impl Iterator<Item = &str> {
         self.0.iter().map(|(k, _)| k.as_ref())
     }
 
+    /// Returns the keys sorted and without duplicates.
+    pub fn keys_set(&self) -> Vec<&str> {
+        let mut keys: Vec<&str> = self.keys().collect();
+        #[cfg(not(feature = "btree"))]
+        keys.sort_unstable();
+        keys.dedup();
+        keys
+    }
+
+    /// Returns true if the object contains all of `keys`, see [`ObjectAsVec::missing_keys`].
+    ///
+    /// # Example
+    /// ```
+    /// use serde_json_borrow::Value;
+    ///
+    /// let value: Value = serde_json::from_str(r#"{"id": 1, "name": "John"}"#).unwrap();
+    /// let obj = value.as_object().unwrap();
+    /// assert!(obj.contains_all(&["id", "name"]));
+    /// assert_eq!(obj.missing_keys(&["id", "email", "age"]), ["email", "age"]);
+    /// ```
+    pub fn contains_all(&self, keys: &[&str]) -> bool {
+        self.missing_keys(keys).is_empty()
+    }
+
+    /// Returns the keys of `keys`, which are not in the object, in the order of `keys`.
+    ///
+    /// ## Performance
+    /// The object is scanned once and the scan stops as soon as all keys are found, so this is
+    /// faster than calling `contains_key` per key for a few required keys.
+    pub fn missing_keys<'a>(&self, keys: &[&'a str]) -> Vec<&'a str> {
+        let mut found = vec![false; keys.len()];
+        let mut remaining = keys.len();
+        for (key, _) in &self.0 {
+            if remaining == 0 {
+                break;
+            }
+            for (required, found) in keys.iter().zip(found.iter_mut()) {
+                if !*found && key_str(key) == *required {
+                    *found = true;
+                    remaining -= 1;
+                }
+            }
+        }
+        keys.iter()
+            .zip(found)
+            .filter(|(_, found)| !found)
+            .map(|(key, _)| *key)
+            .collect()
+    }
+
     /// An iterator visiting all values
     #[inline]
     pub fn values(&self) -> impl Iterator<Item = &Value<'ctx>> {
@@ -137,10 +514,11 @@ impl<'ctx> ObjectAsVec<'ctx> {
     ///
     /// ## Performance
     /// As this is backed by a Vec, this searches linearly through the Vec as may be much more
-    /// expensive than a `Hashmap` for larger Objects.
+    /// expensive than a `Hashmap` for larger Objects. With the `btree` feature flag the key is
+    /// found with a binary search.
     #[inline]
-    pub fn contains_key(&self, key: &str) -> bool {
-        self.0.iter().any(|(k, _)| *k == key)
+    pub fn contains_key<Q: AsRef<str> + ?Sized>(&self, key: &Q) -> bool {
+        self.search(key.as_ref()).is_ok()
     }
 
     /// Inserts a key-value pair into the object.
@@ -150,17 +528,30 @@ impl<'ctx> ObjectAsVec<'ctx> {
     ///
     /// ## Performance
     /// This operation is linear in the size of the Vec because it potentially requires iterating
-    /// through all elements to find a matching key.
+    /// through all elements to find a matching key. With the `btree` feature flag the key is
+    /// found with a binary search, but a new entry shifts all elements after it.
     #[inline]
     pub fn insert(&mut self, key: &'ctx str, value: Value<'ctx>) -> Option<Value<'ctx>> {
-        for (k, v) in &mut self.0 {
-            if *k == key {
-                return Some(std::mem::replace(v, value));
+        self.insert_key(key.into(), value)
+    }
+
+    /// Same as `insert`, but takes the key as `KeyStrType`, which allows to insert owned keys
+    /// with the `cowkeys` feature flag.
+    #[inline]
+    pub(crate) fn insert_key(
+        &mut self,
+        key: KeyStrType<'ctx>,
+        value: Value<'ctx>,
+    ) -> Option<Value<'ctx>> {
+        match self.search(key_str(&key)) {
+            Ok(pos) => Some(std::mem::replace(&mut self.0[pos].1, value)),
+            Err(pos) => {
+                // If the key is not found, insert the new key-value pair, which is at the end of
+                // the Vec without the `btree` feature flag
+                self.insert_at(pos, key, value);
+                None
             }
         }
-        // If the key is not found, push the new key-value pair to the end of the Vec
-        self.0.push((key.into(), value));
-        None
     }
 
     /// Inserts a key-value pair into the object if the key does not yet exist, otherwise returns a
@@ -168,15 +559,123 @@ impl<'ctx> ObjectAsVec<'ctx> {
     ///
     /// ## Performance
     /// This operation might be linear in the size of the Vec because it requires iterating through
-    /// all elements to find a matching key, and might add to the end if not found.
+    /// all elements to find a matching key, and might add to the end if not found. With the
+    /// `btree` feature flag the key is found with a binary search, but a new entry shifts all
+    /// elements after it.
     #[inline]
     pub fn insert_or_get_mut(&mut self, key: &'ctx str, value: Value<'ctx>) -> &mut Value<'ctx> {
+        self.insert_key_or_get_mut(key.into(), value)
+    }
+
+    /// Same as `insert_or_get_mut`, but takes the key as `KeyStrType`, which allows to insert
+    /// owned keys with the `cowkeys` feature flag.
+    #[inline]
+    pub(crate) fn insert_key_or_get_mut(
+        &mut self,
+        key: KeyStrType<'ctx>,
+        value: Value<'ctx>,
+    ) -> &mut Value<'ctx> {
         // get position to circumvent lifetime issue
-        if let Some(pos) = self.0.iter_mut().position(|(k, _)| *k == key) {
-            &mut self.0[pos].1
-        } else {
-            self.0.push((key.into(), value));
-            &mut self.0.last_mut().unwrap().1
+        match self.search(key_str(&key)) {
+            Ok(pos) => &mut self.0[pos].1,
+            Err(pos) => self.insert_at(pos, key, value),
+        }
+    }
+
+    /// Inserts a key-value pair into the object, storing the key as owned `Cow`.
+    /// If the object did not have this key present, `None` is returned.
+    /// If the object did have this key present, the value is updated, and the old value is
+    /// returned.
+    ///
+    /// This allows to insert keys, which don't live as long as the object, e.g. generated key
+    /// names.
+    ///
+    /// ## Performance
+    /// This operation is linear in the size of the Vec because it potentially requires iterating
+    /// through all elements to find a matching key. With the `btree` feature flag the key is
+    /// found with a binary search, but a new entry shifts all elements after it.
+    #[cfg(feature = "cowkeys")]
+    #[inline]
+    pub fn insert_owned(&mut self, key: String, value: Value<'ctx>) -> Option<Value<'ctx>> {
+        self.insert_key(Cow::Owned(key), value)
+    }
+
+    /// Returns a mutable reference to the value of `key`. If the key does not exist, it is
+    /// inserted as owned key with a `Value::Null`.
+    ///
+    /// # Panics
+    /// Panics if the key does not exist and the `cowkeys` feature flag is disabled, as owned keys
+    /// can't be stored then.
+    #[inline]
+    pub(crate) fn get_mut_or_insert_null(&mut self, key: &str) -> &mut Value<'ctx> {
+        match self.search(key) {
+            Ok(pos) => &mut self.0[pos].1,
+            #[cfg(feature = "cowkeys")]
+            Err(pos) => self.insert_at(pos, Cow::Owned(key.to_owned()), Value::Null),
+            #[cfg(not(feature = "cowkeys"))]
+            Err(_) => panic!("inserting the key {key:?} requires the `cowkeys` feature flag"),
+        }
+    }
+
+    /// Removes a key from the object, returning the value at the key if the key was previously in
+    /// the object.
+    ///
+    /// ## Performance
+    /// This operation is linear in the size of the Vec because it potentially requires iterating
+    /// through all elements to find a matching key, and shifts all elements after it. With the
+    /// `btree` feature flag the key is found with a binary search.
+    #[inline]
+    pub fn remove<Q: AsRef<str> + ?Sized>(&mut self, key: &Q) -> Option<Value<'ctx>> {
+        self.remove_entry(key).map(|(_, v)| v)
+    }
+
+    /// Removes a key from the object, returning the stored key and value if the key was
+    /// previously in the object.
+    ///
+    /// The key and value are moved out of the object, borrowed data is not copied.
+    ///
+    /// ## Performance
+    /// This operation is linear in the size of the Vec because it potentially requires iterating
+    /// through all elements to find a matching key, and shifts all elements after it. With the
+    /// `btree` feature flag the key is found with a binary search.
+    #[inline]
+    pub fn remove_entry<Q: AsRef<str> + ?Sized>(
+        &mut self,
+        key: &Q,
+    ) -> Option<(KeyStrType<'ctx>, Value<'ctx>)> {
+        let pos = self.search(key.as_ref()).ok()?;
+        Some(self.0.remove(pos))
+    }
+
+    /// Removes the string stored at `key` from the object and returns it.
+    ///
+    /// Returns `None` and leaves the object untouched, if the key does not exist or the value is
+    /// not a string.
+    #[inline]
+    pub fn take_str<Q: AsRef<str> + ?Sized>(&mut self, key: &Q) -> Option<Cow<'ctx, str>> {
+        let pos = self.search(key.as_ref()).ok()?;
+        if !matches!(self.0[pos].1, Value::Str(_)) {
+            return None;
+        }
+        match self.0.remove(pos).1 {
+            Value::Str(text) => Some(text),
+            _ => unreachable!(),
+        }
+    }
+
+    /// Removes the array stored at `key` from the object and returns it.
+    ///
+    /// Returns `None` and leaves the object untouched, if the key does not exist or the value is
+    /// not an array.
+    #[inline]
+    pub fn take_array<Q: AsRef<str> + ?Sized>(&mut self, key: &Q) -> Option<Vec<Value<'ctx>>> {
+        let pos = self.search(key.as_ref()).ok()?;
+        if !matches!(self.0[pos].1, Value::Array(_)) {
+            return None;
+        }
+        match self.0.remove(pos).1 {
+            Value::Array(arr) => Some(arr),
+            _ => unreachable!(),
         }
     }
 
@@ -189,16 +688,23 @@ impl<'ctx> ObjectAsVec<'ctx> {
     ///
     /// ## Performance
     /// This operation is amortized constant time, worst case linear time in the size of the Vec
-    /// because it potentially requires a reallocation to grow the Vec.
+    /// because it potentially requires a reallocation to grow the Vec. With the `btree` feature
+    /// flag, it is linear time, as the entry is inserted at its sorted position.
     #[inline]
     pub fn insert_unchecked_and_get_mut(
         &mut self,
         key: &'ctx str,
         value: Value<'ctx>,
     ) -> &mut Value<'ctx> {
-        self.0.push((key.into(), value));
-        let idx = self.0.len() - 1;
-        &mut self.0[idx].1
+        let pos = self.insert_position(key);
+        self.insert_at(pos, key.into(), value)
+    }
+
+    /// Inserts a key-value pair without checking if the key already exists.
+    #[inline]
+    pub(crate) fn insert_key_unchecked(&mut self, key: KeyStrType<'ctx>, value: Value<'ctx>) {
+        let pos = self.insert_position(key_str(&key));
+        self.insert_at(pos, key, value);
     }
 }
 
@@ -262,6 +768,75 @@ mod tests {
         assert_eq!(obj.get("e"), Some(&Value::Number(4u64.into())));
     }
 
+    #[test]
+    fn test_split_off_and_truncate() {
+        let mut obj = ObjectAsVec::from(vec![
+            ("a", Value::Number(0u64.into())),
+            ("b", Value::Number(1u64.into())),
+            ("c", Value::Number(2u64.into())),
+            ("d", Value::Number(3u64.into())),
+        ]);
+
+        let rest = obj.split_off(2);
+        assert_eq!(obj.keys().collect::<Vec<_>>(), vec!["a", "b"]);
+        assert_eq!(rest.keys().collect::<Vec<_>>(), vec!["c", "d"]);
+        assert_eq!(rest.get("d"), Some(&Value::Number(3u64.into())));
+
+        obj.truncate(5);
+        assert_eq!(obj.len(), 2);
+        obj.truncate(1);
+        assert_eq!(obj.keys().collect::<Vec<_>>(), vec!["a"]);
+        assert!(obj.split_off(1).is_empty());
+    }
+
+    #[test]
+    fn test_prefix_operations() {
+        let mut obj = ObjectAsVec::from(vec![
+            ("http.method", Value::from("GET")),
+            ("db", Value::Null),
+            ("http.status", Value::Number(200u64.into())),
+            ("http", Value::Bool(true)),
+            ("status", Value::Bool(false)),
+        ]);
+
+        let mut keys: Vec<_> = obj.iter_prefix("http.").map(|(k, _)| k).collect();
+        keys.sort();
+        assert_eq!(keys, vec!["http.method", "http.status"]);
+        assert_eq!(obj.iter_prefix("x").count(), 0);
+        assert_eq!(obj.iter_prefix("").count(), 5);
+
+        let mut stripped = obj.clone();
+        stripped.strip_prefix_keys("http.");
+        assert_eq!(stripped.len(), 4);
+        assert_eq!(stripped.get("method"), Some(&Value::from("GET")));
+        assert_eq!(stripped.get("status"), Some(&Value::Number(200u64.into())));
+        assert_eq!(stripped.get("http"), Some(&Value::Bool(true)));
+
+        let mut http = obj.remove_prefix("http.");
+        assert_eq!(http.len(), 2);
+        assert_eq!(obj.len(), 3);
+        assert!(obj.iter_prefix("http.").next().is_none());
+        http.strip_prefix_keys("http.");
+        assert_eq!(http.get("method"), Some(&Value::from("GET")));
+        assert_eq!(http.get("status"), Some(&Value::Number(200u64.into())));
+    }
+
+    #[test]
+    fn test_typed_getters() {
+        let value: Value =
+            serde_json::from_str(r#"{"s": "x", "i": -2, "b": false, "a": [], "o": {}}"#).unwrap();
+        let obj = value.as_object().unwrap();
+        assert_eq!(obj.get_str("s"), Some("x"));
+        assert_eq!(obj.get_i64("i"), Some(-2));
+        assert_eq!(obj.get_u64("i"), None);
+        assert_eq!(obj.get_f64("i"), Some(-2.0));
+        assert_eq!(obj.get_bool("b"), Some(false));
+        assert_eq!(obj.get_array("a"), Some(&[][..]));
+        assert!(obj.get_object("o").is_some());
+        assert_eq!(obj.get_bool(&String::from("s")), None);
+        assert_eq!(obj.get_str("missing"), None);
+    }
+
     #[test]
     fn test_non_empty_initialization() {
         let obj = ObjectAsVec(vec![("key".into(), Value::Null)]);
@@ -297,6 +872,31 @@ mod tests {
         assert_eq!(keys, vec!["key1", "key2"]);
     }
 
+    #[test]
+    fn test_required_keys() {
+        let obj = ObjectAsVec::from(vec![
+            ("b", Value::Null),
+            ("a", Value::Null),
+            ("c", Value::Null),
+            ("a", Value::Null),
+        ]);
+        assert_eq!(obj.keys_set(), vec!["a", "b", "c"]);
+        assert!(obj.contains_all(&["c", "a", "a"]));
+        assert!(obj.contains_all(&[]));
+        assert_eq!(obj.missing_keys(&["x", "b", "y", "x"]), vec!["x", "y", "x"]);
+        assert!(ObjectAsVec::default().keys_set().is_empty());
+    }
+
+    #[test]
+    fn test_iter_mut() {
+        let mut obj = ObjectAsVec::from(vec![("a", Value::from(1u64)), ("b", Value::from(2u64))]);
+        for (key, value) in obj.iter_mut() {
+            *value = Value::from(format!("{key}={}", value.as_u64().unwrap()));
+        }
+        assert_eq!(obj.get("a"), Some(&Value::from("a=1")));
+        assert_eq!(obj.get("b"), Some(&Value::from("b=2")));
+    }
+
     #[test]
     fn test_values_iterator() {
         let obj = ObjectAsVec(vec![
@@ -363,6 +963,43 @@ mod tests {
     }
 
     #[test]
+    fn test_remove_entry() {
+        let mut obj = ObjectAsVec::from(vec![
+            ("a", Value::Bool(true)),
+            ("b", Value::Null),
+            ("c", Value::Bool(false)),
+        ]);
+        let (key, value) = obj.remove_entry("b").unwrap();
+        assert_eq!(key, "b");
+        assert_eq!(value, Value::Null);
+        assert_eq!(obj.keys().collect::<Vec<_>>(), vec!["a", "c"]);
+        assert_eq!(obj.remove_entry("b"), None);
+        assert_eq!(obj.remove("c"), Some(Value::Bool(false)));
+        assert_eq!(obj.len(), 1);
+    }
+
+    #[test]
+    fn test_take_str() {
+        let json = r#"{"name": "John", "age": 30, "tags": ["a", "b"]}"#;
+        let mut obj = serde_json::from_str::<Value>(json)
+            .unwrap()
+            .as_object()
+            .unwrap()
+            .clone();
+
+        assert_eq!(obj.take_str("age"), None);
+        let name = obj.take_str("name").unwrap();
+        assert!(matches!(name, Cow::Borrowed("John")));
+        assert_eq!(obj.take_str("name"), None);
+
+        assert_eq!(obj.take_array("age"), None);
+        let tags = obj.take_array("tags").unwrap();
+        assert_eq!(tags, vec![Value::Str("a".into()), Value::Str("b".into())]);
+        assert_eq!(obj.keys().collect::<Vec<_>>(), vec!["age"]);
+    }
+
+    #[test]
+    #[allow(clippy::approx_constant)]
     fn test_insert_multiple_types() {
         let mut obj = ObjectAsVec::default();
         obj.insert("boolean", Value::Bool(true));
@@ -390,4 +1027,81 @@ mod tests {
             ]))
         );
     }
+
+    #[cfg(feature = "cowkeys")]
+    #[test]
+    fn test_insert_owned() {
+        let mut obj = ObjectAsVec::default();
+        for i in 0..2 {
+            assert_eq!(obj.insert_owned(format!("key{i}"), Value::Bool(true)), None);
+        }
+        assert_eq!(
+            obj.insert_owned("key1".to_string(), Value::Null),
+            Some(Value::Bool(true))
+        );
+        assert_eq!(obj.len(), 2);
+        assert_eq!(obj.get("key0"), Some(&Value::Bool(true)));
+        assert_eq!(obj.get("key1"), Some(&Value::Null));
+    }
+
+    #[test]
+    fn test_get_with_owned_key_types() {
+        let mut obj = ObjectAsVec::from(vec![("a", Value::Bool(true)), ("b", Value::Null)]);
+        let key = String::from("a");
+        let cow_key: Cow<str> = Cow::Owned("b".to_string());
+        let ref_key: &&str = &"a";
+
+        assert_eq!(obj.get(&key), Some(&Value::Bool(true)));
+        assert_eq!(obj.get(&cow_key), Some(&Value::Null));
+        assert_eq!(obj.get(ref_key), Some(&Value::Bool(true)));
+        assert_eq!(obj.get_key_value(&key), Some(("a", &Value::Bool(true))));
+        assert!(obj.contains_key(&cow_key));
+        assert!(obj.get_mut(&key).is_some());
+        assert_eq!(obj.remove(&key), Some(Value::Bool(true)));
+        assert!(!obj.contains_key(&key));
+    }
+
+    #[cfg(feature = "cowkeys")]
+    #[test]
+    fn test_from_owned_entries() {
+        let key = String::from("generated");
+        let key_ptr = key.as_ptr();
+        let obj: ObjectAsVec<'static> =
+            ObjectAsVec::from_owned_entries(vec![(key, Value::Bool(true))]);
+
+        assert_eq!(obj.get("generated"), Some(&Value::Bool(true)));
+        // the key is moved, not copied
+        assert!(matches!(&obj.0[0].0, Cow::Owned(key) if key.as_ptr() == key_ptr));
+    }
+
+    #[cfg(feature = "btree")]
+    #[test]
+    fn test_btree_sorted_keys() {
+        let value: Value = serde_json::from_str(r#"{"c": 1, "a": 2, "b": 3, "a": 4}"#).unwrap();
+        let mut obj = value.as_object().unwrap().clone();
+        // duplicate keys are kept in input order, lookups return the first one
+        assert_eq!(obj.keys().collect::<Vec<_>>(), vec!["a", "a", "b", "c"]);
+        assert_eq!(obj.get("a"), Some(&Value::from(2u64)));
+        assert_eq!(obj.values().nth(1), Some(&Value::from(4u64)));
+
+        obj.insert("bb", Value::Null);
+        obj.insert("0", Value::Null);
+        obj.insert_unchecked_and_get_mut("d", Value::Null);
+        obj.insert_unchecked_and_get_mut("b", Value::Bool(false));
+        assert_eq!(
+            obj.keys().collect::<Vec<_>>(),
+            vec!["0", "a", "a", "b", "b", "bb", "c", "d"]
+        );
+        assert_eq!(obj.get("b"), Some(&Value::from(3u64)));
+        assert_eq!(obj.insert("bb", Value::Bool(true)), Some(Value::Null));
+        assert_eq!(obj.remove("a"), Some(Value::from(2u64)));
+        assert_eq!(obj.remove("a"), Some(Value::from(4u64)));
+        assert_eq!(
+            obj.keys().collect::<Vec<_>>(),
+            vec!["0", "b", "b", "bb", "c", "d"]
+        );
+
+        let obj = ObjectAsVec::from(vec![("y", Value::Null), ("x", Value::Null)]);
+        assert_eq!(obj.keys().collect::<Vec<_>>(), vec!["x", "y"]);
+    }
 }
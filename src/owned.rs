@@ -1,25 +1,222 @@
-use std::io;
+use std::borrow::Cow;
+use std::hash::{Hash, Hasher};
 use std::ops::Deref;
+use std::rc::Rc;
+use std::sync::Arc;
+use std::{fmt, io};
 
-use crate::Value;
+use crate::index::Index;
+use crate::{ObjectAsVec, Value};
 
 /// Parses a `String` into `Value`, by taking ownership of `String` and reference slices from it in
 /// contrast to copying the contents.
 ///
 /// This is done to mitigate lifetime issues.
-#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+///
+/// The data may be shared by multiple `OwnedValue`s, see [`OwnedValue::parse_many`] and
+/// [`OwnedValue::from_arc`]. Comparing and hashing only considers the `Value`.
+///
+/// Cloning copies the `Value`, but not the data. To share a parsed document between threads or
+/// in a cache without copying the `Value`, wrap it in an `Arc<OwnedValue>`.
+///
+/// The `Value` is accessed via [`OwnedValue::as_value`], [`OwnedValue::scoped`] or the
+/// forwarding methods like [`OwnedValue::get`]. These shorten the lifetime of the borrowed data
+/// to the lifetime of `self`. `OwnedValue` doesn't implement `Deref`, since its target would be
+/// a `Value<'static>`, which could be cloned out and outlive the data it borrows from:
+/// ```compile_fail
+/// use serde_json_borrow::{OwnedValue, Value};
+///
+/// let owned = OwnedValue::from_str(r#""text""#).unwrap();
+/// let escaped: Value<'static> = owned.as_value().clone();
+/// drop(owned);
+/// ```
+#[derive(Clone, Debug)]
 pub struct OwnedValue {
     /// Keep owned data, to be able to safely reference it from Value<'static>
-    _data: String,
+    _data: Buffer,
     value: Value<'static>,
 }
 
+/// The reference counted data of an [`OwnedValue`].
+#[derive(Clone)]
+enum Buffer {
+    String(Arc<String>),
+    Str(Arc<str>),
+    /// The input of the SIMD parser, which unescapes strings in place. So the data is no longer
+    /// the JSON text and may not even be valid utf-8.
+    #[cfg(feature = "simd")]
+    Unescaped(Arc<Vec<u8>>),
+}
+
+impl Buffer {
+    /// Returns the JSON text, or None if it was modified by the SIMD parser.
+    #[cfg(test)]
+    fn as_str(&self) -> Option<&str> {
+        match self {
+            Buffer::String(data) => Some(data),
+            Buffer::Str(data) => Some(data),
+            #[cfg(feature = "simd")]
+            Buffer::Unescaped(_) => None,
+        }
+    }
+}
+
+impl fmt::Debug for Buffer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Buffer::String(data) => fmt::Debug::fmt(data.as_str(), f),
+            Buffer::Str(data) => fmt::Debug::fmt(&**data, f),
+            #[cfg(feature = "simd")]
+            Buffer::Unescaped(data) => write!(f, "<{} unescaped bytes>", data.len()),
+        }
+    }
+}
+
 impl OwnedValue {
     /// Validates `&[u8]` for utf-8 and parses it into a [crate::Value].
     pub fn from_slice(data: &[u8]) -> io::Result<Self> {
-        let data = String::from_utf8(data.to_vec())
-            .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "Invalid UTF-8"))?;
-        Self::from_string(data)
+        validate_utf8(data)?;
+        Self::from_vec_unvalidated(data.to_vec())
+    }
+
+    /// Parses `&[u8]` into a [crate::Value], replacing invalid utf-8 sequences with
+    /// `U+FFFD REPLACEMENT CHARACTER` instead of returning an error.
+    ///
+    /// This is useful for data like logs, where a few corrupt lines should degrade instead of
+    /// being dropped. Invalid sequences outside of strings still make the JSON invalid.
+    ///
+    /// # Example
+    /// ```
+    /// use serde_json_borrow::OwnedValue;
+    ///
+    /// let owned = OwnedValue::from_slice_lossy(b"{\"msg\": \"caf\xe9\"}").unwrap();
+    /// assert_eq!(owned.get("msg"), &"caf\u{FFFD}");
+    /// ```
+    pub fn from_slice_lossy(data: &[u8]) -> io::Result<Self> {
+        Self::from_string(String::from_utf8_lossy(data).into_owned())
+    }
+
+    /// Validates `Vec<u8>` for utf-8 and parses it into a [crate::Value], without copying the
+    /// data.
+    ///
+    /// With the `simdutf8` feature flag, the validation uses SIMD instructions, which is faster
+    /// for large documents.
+    pub fn from_vec(data: Vec<u8>) -> io::Result<Self> {
+        validate_utf8(&data)?;
+        Self::from_vec_unvalidated(data)
+    }
+
+    /// Parses `Vec<u8>` into a [crate::Value], without validating it for utf-8.
+    ///
+    /// This avoids validating the data twice, if it was already validated, e.g. when it was
+    /// received.
+    ///
+    /// # Safety
+    /// `data` must be valid utf-8, see [`String::from_utf8_unchecked`]. The parser only checks
+    /// the validity of escape sequences, other invalid utf-8 is undefined behavior.
+    pub unsafe fn from_utf8_unchecked(data: Vec<u8>) -> io::Result<Self> {
+        Self::from_vec_unvalidated(data)
+    }
+
+    fn from_vec_unvalidated(data: Vec<u8>) -> io::Result<Self> {
+        // SAFETY: all callers validated the data or require valid utf-8.
+        Self::from_string(unsafe { String::from_utf8_unchecked(data) })
+    }
+
+    /// Converts a `serde_json::Value` into an `OwnedValue`.
+    ///
+    /// All strings and keys are copied into a single buffer, which the result borrows from,
+    /// instead of one allocation per string. Unlike parsing, this also supports keys with
+    /// escapes without the `cowkeys` feature flag.
+    ///
+    /// # Example
+    /// ```
+    /// use serde_json_borrow::OwnedValue;
+    ///
+    /// let value = serde_json::json!({"name": "John", "tags": ["a", "b"]});
+    /// let owned = OwnedValue::from_serde_json_value(value);
+    /// assert_eq!(owned.get("tags").get(1), &"b");
+    /// ```
+    pub fn from_serde_json_value(value: serde_json::Value) -> Self {
+        let mut data = String::new();
+        pack_serde_json_strings(&value, &mut data);
+        let data = Arc::new(data);
+        let value = unpack_serde_json(value, &mut data.as_str());
+        // SAFETY: the value only borrows from `data`, which is stored with it, see
+        // `extend_lifetime`.
+        let value = unsafe { extend_lifetime(value) };
+        Self {
+            _data: Buffer::String(data),
+            value,
+        }
+    }
+
+    /// Copies all strings and keys of `value` into a single buffer and returns an `OwnedValue`
+    /// borrowing from it.
+    ///
+    /// After heavy mutation the strings of a value are scattered across many small allocations.
+    /// Compacting it restores locality and reduces the memory usage. Binary data of the `bytes`
+    /// feature flag is copied into owned allocations.
+    ///
+    /// # Example
+    /// ```
+    /// use serde_json_borrow::{OwnedValue, Value};
+    ///
+    /// let mut value: Value = serde_json::from_str(r#"{"name": "John"}"#).unwrap();
+    /// value.as_object_mut().unwrap().insert("id", Value::from(String::from("u-1")));
+    /// let owned = OwnedValue::from_value(value);
+    /// assert_eq!(owned.get("id"), &"u-1");
+    /// ```
+    pub fn from_value(value: Value<'_>) -> Self {
+        let mut data = String::new();
+        pack_strings(&value, &mut data);
+        let data = Arc::new(data);
+        let value = unpack(value, &mut data.as_str());
+        // SAFETY: the value only borrows from `data`, which is stored with it, see
+        // `extend_lifetime`.
+        let value = unsafe { extend_lifetime(value) };
+        Self {
+            _data: Buffer::String(data),
+            value,
+        }
+    }
+
+    /// Parses `String` with the SIMD parser of `simd-json` into a [crate::Value].
+    ///
+    /// The result is the same as for [`OwnedValue::from_string`], but parsing is faster for most
+    /// documents. The fastest instruction set is detected at runtime. The parser unescapes
+    /// strings in place, so strings with escapes are borrowed too, instead of being allocated.
+    ///
+    /// Requires the `simd` feature flag.
+    ///
+    /// # Example
+    /// ```
+    /// use serde_json_borrow::OwnedValue;
+    ///
+    /// let owned = OwnedValue::from_string_simd(r#"{"name": "Jo\"hn"}"#.to_string()).unwrap();
+    /// assert_eq!(owned.get("name"), &"Jo\"hn");
+    /// ```
+    #[cfg(feature = "simd")]
+    pub fn from_string_simd(json_str: String) -> io::Result<Self> {
+        Self::from_vec_simd(json_str.into_bytes())
+    }
+
+    /// Validates `Vec<u8>` for utf-8 and parses it with the SIMD parser of `simd-json`, see
+    /// [`OwnedValue::from_string_simd`].
+    ///
+    /// Requires the `simd` feature flag.
+    #[cfg(feature = "simd")]
+    pub fn from_vec_simd(data: Vec<u8>) -> io::Result<Self> {
+        let mut data = Arc::new(data);
+        let buf = Arc::get_mut(&mut data).expect("the data is not shared yet");
+        let value = Value::from_slice_in_situ(buf)?;
+        // SAFETY: the value only borrows from `data`, which is stored with it, see
+        // `extend_lifetime`.
+        let value = unsafe { extend_lifetime(value) };
+        Ok(Self {
+            _data: Buffer::Unescaped(data),
+            value,
+        })
     }
 
     /// Takes serialized JSON `&str` and parses it into a [crate::Value].
@@ -33,14 +230,77 @@ impl OwnedValue {
 
     /// Takes serialized JSON `String` and parses it into a [crate::Value].
     pub fn from_string(json_str: String) -> io::Result<Self> {
-        let value: Value = serde_json::from_str(&json_str)?;
+        let data = Arc::new(json_str);
+        let value: Value = serde_json::from_str(&data)?;
+        // SAFETY: the value only borrows from `data`, which is stored with it, see
+        // `extend_lifetime`.
         let value = unsafe { extend_lifetime(value) };
         Ok(Self {
-            _data: json_str,
+            _data: Buffer::String(data),
             value,
         })
     }
 
+    /// Parses a reference counted `Arc<str>` into a [crate::Value], without copying the data.
+    ///
+    /// The value keeps a reference to `data`, so the same buffer can be shared with other
+    /// owners, e.g. a cache of the raw documents.
+    ///
+    /// # Example
+    /// ```
+    /// use std::sync::Arc;
+    ///
+    /// use serde_json_borrow::OwnedValue;
+    ///
+    /// let data: Arc<str> = Arc::from(r#"{"name": "John"}"#);
+    /// let owned = OwnedValue::from_arc(Arc::clone(&data)).unwrap();
+    /// assert_eq!(owned.get("name"), &"John");
+    /// ```
+    pub fn from_arc(data: Arc<str>) -> io::Result<Self> {
+        let value: Value = serde_json::from_str(&data)?;
+        // SAFETY: the value only borrows from `data`, which is stored with it, see
+        // `extend_lifetime`.
+        let value = unsafe { extend_lifetime(value) };
+        Ok(Self {
+            _data: Buffer::Str(data),
+            value,
+        })
+    }
+
+    /// Parses newline delimited JSON (NDJSON), one document per line.
+    ///
+    /// All returned values share `json_lines` as backing buffer, so there is no allocation per
+    /// line for the data. Empty lines are skipped. Returns an error if a line is not valid JSON.
+    /// To read NDJSON from a reader, see [`crate::NdjsonReader`].
+    ///
+    /// # Example
+    /// ```
+    /// use serde_json_borrow::OwnedValue;
+    ///
+    /// let lines = String::from("{\"id\": 1}\n{\"id\": 2}\n\n");
+    /// let values = OwnedValue::parse_many(lines).unwrap();
+    /// assert_eq!(values.len(), 2);
+    /// assert_eq!(values[1].get("id"), &2_u64);
+    /// ```
+    pub fn parse_many(json_lines: String) -> io::Result<Vec<Self>> {
+        let data = Arc::new(json_lines);
+        let mut values = Vec::new();
+        for line in data.lines() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let value: Value = serde_json::from_str(line)?;
+            // SAFETY: the value only borrows from `data`, which is stored with it, see
+            // `extend_lifetime`.
+            let value = unsafe { extend_lifetime(value) };
+            values.push(Self {
+                _data: Buffer::String(Arc::clone(&data)),
+                value,
+            });
+        }
+        Ok(values)
+    }
+
     /// Takes serialized JSON `String` and parses it into a [crate::Value].
     pub fn parse_from(json_str: String) -> io::Result<Self> {
         Self::from_string(json_str)
@@ -50,16 +310,409 @@ impl OwnedValue {
     pub fn get_value(&self) -> &Value<'_> {
         &self.value
     }
+
+    /// Returns the `Value` reference, with the lifetime of the borrowed data shortened to the
+    /// lifetime of `self`.
+    ///
+    /// `Value` is covariant in its lifetime, so the result can be passed to any API expecting a
+    /// `&'a Value<'a>`.
+    pub fn as_value<'a>(&'a self) -> &'a Value<'a> {
+        &self.value
+    }
+
+    /// Calls `f` with the `Value` and returns its result.
+    ///
+    /// `f` has to accept a value of any lifetime, so references into the value can't escape the
+    /// closure. This is useful to pass the value to generic code, which requires a single
+    /// lifetime for the reference and the borrowed data.
+    ///
+    /// # Example
+    /// ```
+    /// use serde_json_borrow::OwnedValue;
+    ///
+    /// let owned = OwnedValue::from_str(r#"{"name": "John"}"#).unwrap();
+    /// let len = owned.scoped(|value| value.get_str("name").map(str::len));
+    /// assert_eq!(len, Some(4));
+    /// ```
+    pub fn scoped<R>(&self, f: impl for<'a> FnOnce(&'a Value<'a>) -> R) -> R {
+        f(&self.value)
+    }
+
+    /// Drops the value and returns the JSON text it was parsed from.
+    ///
+    /// This doesn't copy the text, unless the data is shared, i.e. by other values from
+    /// [`OwnedValue::parse_many`], or it was passed to [`OwnedValue::from_arc`]. For
+    /// `parse_many`, the text contains all lines. The SIMD parser of the `simd` feature flag
+    /// modifies the text while parsing, so these values are serialized again.
+    ///
+    /// # Example
+    /// ```
+    /// use serde_json_borrow::OwnedValue;
+    ///
+    /// let owned = OwnedValue::from_string(r#"{"id": 1}"#.to_string()).unwrap();
+    /// let id = owned.get("id").as_u64();
+    /// assert_eq!(id, Some(1));
+    /// assert_eq!(owned.into_string(), r#"{"id": 1}"#);
+    /// ```
+    pub fn into_string(self) -> String {
+        let OwnedValue { _data, value } = self;
+        #[cfg(feature = "simd")]
+        if let Buffer::Unescaped(_) = _data {
+            return value.to_string();
+        }
+        // The value borrows from the data, so it is dropped first.
+        drop(value);
+        match _data {
+            Buffer::String(data) => Arc::try_unwrap(data).unwrap_or_else(|data| (*data).clone()),
+            Buffer::Str(data) => data.to_string(),
+            #[cfg(feature = "simd")]
+            Buffer::Unescaped(_) => unreachable!("serialized above"),
+        }
+    }
+
+    /// Calls `f` with a mutable reference to the `Value` and returns its result.
+    ///
+    /// Like in [`OwnedValue::scoped`], `f` has to accept a value of any lifetime. So only data
+    /// which is `'static`, e.g. owned strings, or data taken from the value itself can be
+    /// inserted, and the data of `self` can't escape the closure.
+    ///
+    /// # Example
+    /// ```
+    /// use serde_json_borrow::{OwnedValue, Value};
+    ///
+    /// let mut owned = OwnedValue::from_str(r#"{"name": "John", "password": "secret"}"#).unwrap();
+    /// owned.with_value_mut(|value| {
+    ///     let obj = value.as_object_mut().unwrap();
+    ///     obj.insert("password", Value::from("***"));
+    ///     let len = obj.get("name").and_then(|name| name.as_str()).map_or(0, str::len);
+    ///     obj.insert("name_len", Value::from(len as u64));
+    /// });
+    /// assert_eq!(owned.get("password"), &"***");
+    /// assert_eq!(owned.get("name_len"), &4_u64);
+    /// ```
+    pub fn with_value_mut<R>(&mut self, f: impl for<'a> FnOnce(&mut Value<'a>) -> R) -> R {
+        f(&mut self.value)
+    }
+
+    /// Releases unused capacity of the value, see [`Value::shrink_to_fit`].
+    pub fn shrink_to_fit(&mut self) {
+        self.value.shrink_to_fit();
+    }
+
+    /// Index into the value, see [`Value::get`].
+    ///
+    /// The result borrows from `self`, so it can't outlive the `OwnedValue`.
+    ///
+    /// # Example
+    /// ```
+    /// use serde_json_borrow::OwnedValue;
+    ///
+    /// let owned = OwnedValue::from_str(r#"{"names": ["John", "Jane"]}"#).unwrap();
+    /// assert_eq!(owned.get("names").get(1), &"Jane");
+    /// assert_eq!(owned.get(("names", 0)), &"John");
+    /// assert!(owned.get("missing").is_null());
+    /// ```
+    pub fn get<'a, I: Index<'a>>(&'a self, index: I) -> &'a Value<'a> {
+        self.as_value().get(index)
+    }
+
+    /// Looks up a value by a dot separated path, see [`Value::get_path`].
+    pub fn get_path<'a>(&'a self, path: &str) -> &'a Value<'a> {
+        self.as_value().get_path(path)
+    }
+
+    /// Looks up a value by a JSON Pointer, see [`Value::pointer`].
+    pub fn pointer<'a>(&'a self, pointer: &str) -> Option<&'a Value<'a>> {
+        self.as_value().pointer(pointer)
+    }
+}
+
+impl PartialEq for OwnedValue {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl Eq for OwnedValue {}
+
+impl Hash for OwnedValue {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.value.hash(state);
+    }
+}
+
+/// A smart pointer, whose target stays at the same address when the pointer is moved.
+///
+/// This is required for the storage of [`OwnedValueIn`], as its `Value` borrows from the target.
+///
+/// # Safety
+/// The target must not move or be invalidated while the pointer is moved, and `deref` must
+/// always return the same target, as long as the pointer isn't mutated. E.g. `String` and
+/// `Box<str>` store their data on the heap, while an array stored inline would move with the
+/// pointer.
+///
+/// The target must also not be modified while the pointer is alive, not even from outside of the
+/// process. E.g. a memory map of a file only upholds this if the file isn't modified or truncated
+/// while it is mapped, otherwise reading the parsed `Value` is undefined behavior. An
+/// implementation for such a storage must make sure of this, or document it as a requirement of
+/// its (unsafe) constructor.
+pub unsafe trait StableDeref: Deref {}
+
+unsafe impl StableDeref for String {}
+unsafe impl<T> StableDeref for Vec<T> {}
+unsafe impl<T: ?Sized> StableDeref for Box<T> {}
+unsafe impl<T: ?Sized> StableDeref for Arc<T> {}
+unsafe impl<T: ?Sized> StableDeref for Rc<T> {}
+
+/// Like [`OwnedValue`], but generic over the storage of the JSON text, e.g. `String`,
+/// `Box<str>`, `Arc<str>` or a custom buffer implementing [`StableDeref`]. Byte storages like
+/// `Vec<u8>` are supported by [`OwnedValueIn::from_utf8`].
+///
+/// `OwnedValue` stays a separate type, as it shares its storage between values, see
+/// [`OwnedValue::parse_many`], and can be cloned. Cloning `OwnedValueIn` isn't supported, as the
+/// clone of a storage like `String` has a different address.
+///
+/// # Example
+/// ```
+/// use std::rc::Rc;
+///
+/// use serde_json_borrow::OwnedValueIn;
+///
+/// let owned = OwnedValueIn::new(Box::<str>::from(r#"{"id": 1}"#)).unwrap();
+/// assert_eq!(owned.get("id"), &1_u64);
+/// let owned = OwnedValueIn::new(Rc::<str>::from(r#"[true]"#)).unwrap();
+/// assert_eq!(owned.get(0), &true);
+/// ```
+#[derive(Debug)]
+pub struct OwnedValueIn<S> {
+    /// Keep owned data, to be able to safely reference it from Value<'static>. Only used via
+    /// `value`, so the storage is never dereferenced mutably.
+    data: S,
+    value: Value<'static>,
+}
+
+impl<S: StableDeref<Target = str>> OwnedValueIn<S> {
+    /// Takes ownership of `data` and parses it into a [crate::Value].
+    pub fn new(data: S) -> io::Result<Self> {
+        let value: Value = serde_json::from_str(&data)?;
+        // SAFETY: the value only borrows from `data`, which is stored with it, see
+        // `extend_lifetime`.
+        let value = unsafe { extend_lifetime(value) };
+        Ok(Self { data, value })
+    }
+
+    /// Returns the JSON text.
+    pub fn json(&self) -> &str {
+        &self.data
+    }
 }
 
-impl Deref for OwnedValue {
-    type Target = Value<'static>;
+impl<S: StableDeref<Target = [u8]>> OwnedValueIn<S> {
+    /// Takes ownership of `data`, validates it for utf-8 and parses it into a [crate::Value].
+    ///
+    /// The data isn't copied, so this works with any byte storage. E.g. for a memory map of a
+    /// file, a newtype around the map implementing [`StableDeref`] allows to parse files larger
+    /// than the memory, without reading them into a `String` first.
+    ///
+    /// A mapped file must not be modified or truncated while the `OwnedValueIn` is alive, e.g. by
+    /// another process. The data is only validated once, so changes afterwards would be
+    /// undefined behavior when reading the `Value`, see the safety section of [`StableDeref`].
+    ///
+    /// # Example
+    /// ```
+    /// use serde_json_borrow::OwnedValueIn;
+    ///
+    /// let owned = OwnedValueIn::from_utf8(br#"{"id": 1}"#.to_vec()).unwrap();
+    /// assert_eq!(owned.get("id"), &1_u64);
+    /// assert!(OwnedValueIn::from_utf8(b"\"\xff\"".to_vec()).is_err());
+    /// ```
+    pub fn from_utf8(data: S) -> io::Result<Self> {
+        validate_utf8(&data)?;
+        // SAFETY: the data was validated above.
+        let json = unsafe { std::str::from_utf8_unchecked(&data) };
+        let value: Value = serde_json::from_str(json)?;
+        // SAFETY: the value only borrows from `data`, which is stored with it, see
+        // `extend_lifetime`.
+        let value = unsafe { extend_lifetime(value) };
+        Ok(Self { data, value })
+    }
+}
 
-    fn deref(&self) -> &Self::Target {
+impl<S> OwnedValueIn<S> {
+    /// Returns the `Value` reference, see [`OwnedValue::as_value`].
+    pub fn as_value<'a>(&'a self) -> &'a Value<'a> {
         &self.value
     }
+
+    /// Calls `f` with the `Value` and returns its result, see [`OwnedValue::scoped`].
+    pub fn scoped<R>(&self, f: impl for<'a> FnOnce(&'a Value<'a>) -> R) -> R {
+        f(&self.value)
+    }
+
+    /// Index into the value, see [`Value::get`].
+    pub fn get<'a, I: Index<'a>>(&'a self, index: I) -> &'a Value<'a> {
+        self.as_value().get(index)
+    }
+
+    /// Looks up a value by a JSON Pointer, see [`Value::pointer`].
+    pub fn pointer<'a>(&'a self, pointer: &str) -> Option<&'a Value<'a>> {
+        self.as_value().pointer(pointer)
+    }
+
+    /// Drops the value and returns the storage of the JSON text.
+    pub fn into_inner(self) -> S {
+        let OwnedValueIn { data, value } = self;
+        // The value borrows from the data, so it is dropped first.
+        drop(value);
+        data
+    }
+}
+
+impl<S> PartialEq for OwnedValueIn<S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<S> Eq for OwnedValueIn<S> {}
+
+impl<S> Hash for OwnedValueIn<S> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.value.hash(state);
+    }
+}
+
+/// Appends all strings and keys of `value` to `data`, in the order of [`unpack`].
+fn pack_strings(value: &Value, data: &mut String) {
+    match value {
+        Value::Str(text) => data.push_str(text),
+        Value::Array(arr) => {
+            for value in arr {
+                pack_strings(value, data);
+            }
+        }
+        Value::Object(obj) => {
+            for (key, value) in obj.iter() {
+                data.push_str(key);
+                pack_strings(value, data);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Converts `value`, with the strings and keys borrowed from the start of `data`, see
+/// [`pack_strings`]. Advances `data` past the used strings.
+#[allow(clippy::useless_conversion)]
+fn unpack<'a>(value: Value<'_>, data: &mut &'a str) -> Value<'a> {
+    match value {
+        Value::Null => Value::Null,
+        Value::Bool(val) => Value::Bool(val),
+        Value::Number(val) => Value::Number(val),
+        Value::Str(text) => Value::Str(Cow::Borrowed(take_str(data, text.len()))),
+        #[cfg(feature = "bytes")]
+        Value::Bytes(bytes) => Value::Bytes(Cow::Owned(bytes.into_owned())),
+        Value::Array(arr) => {
+            Value::Array(arr.into_iter().map(|value| unpack(value, data)).collect())
+        }
+        Value::Object(obj) => {
+            let mut entries = Vec::with_capacity(obj.len());
+            for (key, value) in obj.0 {
+                let key = take_str(data, key.len());
+                entries.push((key.into(), unpack(value, data)));
+            }
+            Value::Object(ObjectAsVec::from_entries(entries))
+        }
+    }
+}
+
+/// Appends all strings and keys of `value` to `data`, in the order of [`unpack_serde_json`].
+fn pack_serde_json_strings(value: &serde_json::Value, data: &mut String) {
+    match value {
+        serde_json::Value::String(text) => data.push_str(text),
+        serde_json::Value::Array(arr) => {
+            for value in arr {
+                pack_serde_json_strings(value, data);
+            }
+        }
+        serde_json::Value::Object(obj) => {
+            for (key, value) in obj {
+                data.push_str(key);
+                pack_serde_json_strings(value, data);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Converts `value`, with the strings and keys borrowed from the start of `data`, see
+/// [`pack_serde_json_strings`]. Advances `data` past the used strings.
+#[allow(clippy::useless_conversion)]
+fn unpack_serde_json<'a>(value: serde_json::Value, data: &mut &'a str) -> Value<'a> {
+    match value {
+        serde_json::Value::Null => Value::Null,
+        serde_json::Value::Bool(val) => Value::Bool(val),
+        serde_json::Value::Number(val) => match (val.as_u64(), val.as_i64()) {
+            (Some(val), _) => Value::from(val),
+            (None, Some(val)) => Value::from(val),
+            _ => val.as_f64().map_or(Value::Null, Value::from),
+        },
+        serde_json::Value::String(text) => Value::Str(Cow::Borrowed(take_str(data, text.len()))),
+        serde_json::Value::Array(arr) => Value::Array(
+            arr.into_iter()
+                .map(|value| unpack_serde_json(value, data))
+                .collect(),
+        ),
+        serde_json::Value::Object(obj) => {
+            let mut entries = Vec::with_capacity(obj.len());
+            for (key, value) in obj {
+                let key = take_str(data, key.len());
+                entries.push((key.into(), unpack_serde_json(value, data)));
+            }
+            Value::Object(ObjectAsVec::from_entries(entries))
+        }
+    }
+}
+
+/// Returns the first `len` bytes of `data` and advances it past them.
+fn take_str<'a>(data: &mut &'a str, len: usize) -> &'a str {
+    let (text, rest) = data.split_at(len);
+    *data = rest;
+    text
 }
 
+fn validate_utf8(data: &[u8]) -> io::Result<()> {
+    #[cfg(feature = "simdutf8")]
+    let valid = simdutf8::basic::from_utf8(data).is_ok();
+    #[cfg(not(feature = "simdutf8"))]
+    let valid = std::str::from_utf8(data).is_ok();
+    if valid {
+        Ok(())
+    } else {
+        Err(io::Error::new(io::ErrorKind::InvalidData, "Invalid UTF-8"))
+    }
+}
+
+/// Extends the lifetime of the borrowed data of `r` to `'static`, so it can be stored next to the
+/// data in [`OwnedValue`] and [`OwnedValueIn`].
+///
+/// # Safety
+/// The caller must store `r` in the same struct as the data it borrows from, and the struct must
+/// uphold these invariants:
+/// - The data is behind a pointer, whose target doesn't move when the struct is moved, i.e. `Arc`
+///   in `OwnedValue` and [`StableDeref`] in `OwnedValueIn`. Clones of `OwnedValue` share the `Arc`,
+///   so the cloned value borrows from the same address.
+/// - The data is private and never mutated or dropped while the value exists. `into_string` and
+///   `into_inner` drop the value first. When the struct is dropped, the order doesn't matter, as
+///   dropping a `Value` doesn't read its borrowed data.
+/// - The `'static` lifetime doesn't escape. Accessors shorten it to the lifetime of `&self`, e.g.
+///   `as_value` and `get`, or hide it behind a closure taking any lifetime, e.g. `scoped` and
+///   `with_value_mut`. There is no `Deref` impl, as its target would be `Value<'static>`.
+///
+/// The lifetime is extended with a transmute instead of a self-referential abstraction like
+/// `yoke`, since `OwnedValueIn` would have to take the `StableDeref` trait of
+/// `stable_deref_trait` instead of [`StableDeref`], which is a breaking change for a new
+/// dependency that doesn't simplify the accessors.
 unsafe fn extend_lifetime<'b>(r: Value<'b>) -> Value<'static> {
     std::mem::transmute::<Value<'b>, Value<'static>>(r)
 }
@@ -68,9 +721,9 @@ unsafe fn extend_lifetime<'b>(r: Value<'b>) -> Value<'static> {
 mod tests {
     use super::*;
 
-    /// Test reading from the internal Value via Deref.
+    /// Test reading from the internal Value.
     #[test]
-    fn test_deref_access() {
+    fn test_get_access() {
         let raw_json = r#"{"name": "John", "age": 30}"#;
         let owned_value = OwnedValue::from_string(raw_json.to_string()).unwrap();
 
@@ -78,6 +731,216 @@ mod tests {
         assert_eq!(owned_value.get("age"), &Value::Number(30_u64.into()));
     }
 
+    /// Test the borrowing accessors.
+    #[test]
+    fn test_as_value_and_scoped() {
+        fn first_key<'a>(value: &'a Value<'a>) -> Option<&'a str> {
+            value.as_object()?.keys().next()
+        }
+
+        let owned_value = OwnedValue::from_str(r#"{"name": "John"}"#).unwrap();
+        assert_eq!(first_key(owned_value.as_value()), Some("name"));
+        let name = owned_value.scoped(|value| value.get_str("name").map(str::to_string));
+        assert_eq!(name.as_deref(), Some("John"));
+    }
+
+    /// Test mutating the value.
+    #[test]
+    fn test_with_value_mut() {
+        let mut owned_value = OwnedValue::from_str(r#"{"a": [1, "x"], "b": "y"}"#).unwrap();
+        let removed = owned_value.with_value_mut(|value| {
+            let arr = value.get_mut("a").and_then(Value::as_array_mut).unwrap();
+            arr.push(Value::from(format!("{}!", arr.len())));
+            arr.swap(0, 1);
+            arr.remove(1).as_u64()
+        });
+        assert_eq!(removed, Some(1));
+        let expected = OwnedValue::from_str(r#"{"a": ["x", "2!"], "b": "y"}"#).unwrap();
+        assert_eq!(owned_value, expected);
+    }
+
+    /// Test the forwarding lookups.
+    #[test]
+    fn test_lookups() {
+        let owned_value = OwnedValue::from_str(r#"{"a": {"b": [1, {"c": "x"}]}}"#).unwrap();
+        assert_eq!(owned_value.get("a").get("b").get(0), &1_u64);
+        assert_eq!(owned_value.get(("a", "b", 1, "c")), &"x");
+        assert_eq!(owned_value.get_path("a.b.1.c"), &"x");
+        assert_eq!(owned_value.pointer("/a/b/1/c"), Some(&Value::from("x")));
+        assert_eq!(owned_value.pointer("/a/x"), None);
+        assert!(owned_value.get_path("a.x").is_null());
+    }
+
+    /// Test the byte based constructors.
+    #[test]
+    fn test_from_bytes() {
+        let json = r#"{"name": "Jöhn"}"#;
+        let expected = OwnedValue::from_str(json).unwrap();
+        assert_eq!(OwnedValue::from_slice(json.as_bytes()).unwrap(), expected);
+        assert_eq!(
+            OwnedValue::from_vec(json.as_bytes().to_vec()).unwrap(),
+            expected
+        );
+        let unchecked = unsafe { OwnedValue::from_utf8_unchecked(json.as_bytes().to_vec()) };
+        assert_eq!(unchecked.unwrap(), expected);
+
+        let invalid = b"\"\xff\"".to_vec();
+        let err = OwnedValue::from_vec(invalid.clone()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(OwnedValue::from_slice(&invalid).is_err());
+    }
+
+    /// Test replacing invalid utf-8.
+    #[test]
+    fn test_from_slice_lossy() {
+        let json = b"[\"a\xff\xfeb\", \"ok\"]";
+        let owned_value = OwnedValue::from_slice_lossy(json).unwrap();
+        assert_eq!(owned_value.get(0), &"a\u{FFFD}\u{FFFD}b");
+        assert_eq!(owned_value.get(1), &"ok");
+        assert!(OwnedValue::from_slice(json).is_err());
+        assert!(OwnedValue::from_slice_lossy(b"[1, \xff]").is_err());
+    }
+
+    /// Test the SIMD parser.
+    #[cfg(feature = "simd")]
+    #[test]
+    fn test_simd() {
+        let json = r#"{"a": ["x\ty", 1, -2, 1.5, 18446744073709551615, null, true], "b": {}}"#;
+        let owned_value = OwnedValue::from_string_simd(json.to_string()).unwrap();
+        assert_eq!(owned_value, OwnedValue::from_str(json).unwrap());
+        assert!(matches!(
+            owned_value.get("a").get(0),
+            Value::Str(Cow::Borrowed("x\ty"))
+        ));
+        assert_eq!(
+            owned_value.clone().into_string(),
+            OwnedValue::from_str(json).unwrap().as_value().to_string()
+        );
+        assert!(format!("{owned_value:?}").contains("unescaped bytes>"));
+
+        assert!(OwnedValue::from_vec_simd(b"[1,".to_vec()).is_err());
+        assert!(OwnedValue::from_vec_simd(b"[1] 2".to_vec()).is_err());
+        assert!(OwnedValue::from_vec_simd(b"[\"\xff\"]".to_vec()).is_err());
+    }
+
+    /// Test parsing NDJSON into values sharing the buffer.
+    #[test]
+    fn test_parse_many() {
+        let lines = "{\"a\": \"x\"}\r\n[1, 2]\n\n  \n\"s\"".to_string();
+        let values = OwnedValue::parse_many(lines).unwrap();
+        assert_eq!(values.len(), 3);
+        assert!(std::ptr::eq(
+            values[0]._data.as_str().unwrap(),
+            values[2]._data.as_str().unwrap()
+        ));
+        assert_eq!(values[0].get("a"), &"x");
+        assert_eq!(values[1].get(1), &2_u64);
+        assert_eq!(values[2].as_value(), &"s");
+        assert_eq!(values[0], OwnedValue::from_str(r#"{"a":"x"}"#).unwrap());
+
+        assert!(OwnedValue::parse_many("1\n{\n}".to_string()).is_err());
+        assert!(OwnedValue::parse_many(String::new()).unwrap().is_empty());
+    }
+
+    /// Test parsing a shared `Arc<str>`.
+    #[test]
+    fn test_from_arc() {
+        let data: Arc<str> = Arc::from(r#"{"name": "John"}"#);
+        let owned_value = OwnedValue::from_arc(Arc::clone(&data)).unwrap();
+        assert_eq!(Arc::strong_count(&data), 2);
+        assert!(std::ptr::eq(owned_value._data.as_str().unwrap(), &*data));
+        assert_eq!(owned_value.get("name"), &"John");
+        assert_eq!(
+            format!("{:?}", owned_value.clone()),
+            format!("{owned_value:?}")
+        );
+        drop(owned_value);
+        assert_eq!(Arc::strong_count(&data), 1);
+        assert!(OwnedValue::from_arc(Arc::from("[")).is_err());
+
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<Arc<OwnedValue>>();
+    }
+
+    /// Test recovering the JSON text.
+    #[test]
+    fn test_into_string() {
+        let json = String::from(r#"{"name": "John"}"#);
+        let ptr = json.as_ptr();
+        let text = OwnedValue::from_string(json).unwrap().into_string();
+        assert_eq!(text.as_ptr(), ptr);
+        assert_eq!(text, r#"{"name": "John"}"#);
+
+        let values = OwnedValue::parse_many("1\n2".to_string()).unwrap();
+        assert_eq!(values[0].clone().into_string(), "1\n2");
+        assert_eq!(
+            OwnedValue::from_arc(Arc::from("[]")).unwrap().into_string(),
+            "[]"
+        );
+    }
+
+    /// Test the generic storages.
+    #[test]
+    fn test_owned_value_in() {
+        let json = r#"{"a": ["x"]}"#;
+        let string = OwnedValueIn::new(json.to_string()).unwrap();
+        let boxed = OwnedValueIn::new(Box::<str>::from(json)).unwrap();
+        let arc = OwnedValueIn::new(Arc::<str>::from(json)).unwrap();
+        assert_eq!(string.get("a").get(0), &"x");
+        assert_eq!(boxed.pointer("/a/0"), Some(&Value::from("x")));
+        assert_eq!(
+            arc.scoped(|value| value.get("a").as_array().map(|a| a.len())),
+            Some(1)
+        );
+        assert_eq!(
+            string,
+            OwnedValueIn::new(String::from(r#"{"a":["x"]}"#)).unwrap()
+        );
+        assert_eq!(boxed.json(), json);
+
+        // Moving the storage doesn't invalidate the value.
+        let moved = std::hint::black_box(boxed);
+        assert_eq!(moved.get("a").get(0), &"x");
+        let ptr = string.json().as_ptr();
+        let inner = string.into_inner();
+        assert_eq!(inner.as_ptr(), ptr);
+        assert!(OwnedValueIn::new(String::from("[")).is_err());
+
+        let bytes = OwnedValueIn::from_utf8(Box::<[u8]>::from(json.as_bytes())).unwrap();
+        assert_eq!(bytes.get("a").get(0), &"x");
+        assert_eq!(&*bytes.into_inner(), json.as_bytes());
+        assert!(OwnedValueIn::from_utf8(Arc::<[u8]>::from(&b"[1,"[..])).is_err());
+    }
+
+    /// Test converting from serde_json.
+    #[test]
+    fn test_from_serde_json_value() {
+        let value = serde_json::json!({"a": [1, -2.5, null, true], "k\"ey": "x\ny"});
+        let owned_value = OwnedValue::from_serde_json_value(value.clone());
+        assert_eq!(owned_value.as_value(), &value);
+        assert_eq!(owned_value.get("a").get(1), &Value::from(-2.5));
+        assert!(matches!(
+            owned_value.get("k\"ey"),
+            Value::Str(Cow::Borrowed("x\ny"))
+        ));
+    }
+
+    /// Test compacting a value.
+    #[test]
+    fn test_from_value() {
+        let mut value: Value = serde_json::from_str(r#"{"a": ["x", 1], "b": "y\n"}"#).unwrap();
+        value.get_mut("a").and_then(Value::as_array_mut).unwrap()[1] =
+            Value::from(String::from("z"));
+        let expected = value.clone();
+        let owned_value = OwnedValue::from_value(value);
+        assert_eq!(owned_value.as_value(), &expected);
+        assert_eq!(owned_value._data.as_str(), Some("axzby\n"));
+        assert!(matches!(
+            owned_value.get("a").get(1),
+            Value::Str(Cow::Borrowed("z"))
+        ));
+    }
+
     /// Test that clone clones OwnedValue
     #[test]
     fn test_deref_clone() {
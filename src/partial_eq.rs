@@ -0,0 +1,218 @@
+// The macro casts e.g. `i64` to `i64` or `bool` to `bool`.
+#![allow(trivial_casts, trivial_numeric_casts)]
+
+use crate::value::{Number, N};
+use crate::Value;
+
+fn eq_i64(value: &Value, other: i64) -> bool {
+    value.as_i64() == Some(other)
+}
+
+fn eq_u64(value: &Value, other: u64) -> bool {
+    value.as_u64() == Some(other)
+}
+
+fn eq_f32(value: &Value, other: f32) -> bool {
+    match value {
+        Value::Number(n) => n.as_f64() == Some(other as f64),
+        _ => false,
+    }
+}
+
+fn eq_f64(value: &Value, other: f64) -> bool {
+    value.as_f64() == Some(other)
+}
+
+fn eq_bool(value: &Value, other: bool) -> bool {
+    value.as_bool() == Some(other)
+}
+
+fn eq_str(value: &Value, other: &str) -> bool {
+    value.as_str() == Some(other)
+}
+
+impl PartialEq<str> for Value<'_> {
+    fn eq(&self, other: &str) -> bool {
+        eq_str(self, other)
+    }
+}
+
+impl PartialEq<&str> for Value<'_> {
+    fn eq(&self, other: &&str) -> bool {
+        eq_str(self, other)
+    }
+}
+
+impl PartialEq<Value<'_>> for str {
+    fn eq(&self, other: &Value) -> bool {
+        eq_str(other, self)
+    }
+}
+
+impl PartialEq<Value<'_>> for &str {
+    fn eq(&self, other: &Value) -> bool {
+        eq_str(other, self)
+    }
+}
+
+impl PartialEq<String> for Value<'_> {
+    fn eq(&self, other: &String) -> bool {
+        eq_str(self, other.as_str())
+    }
+}
+
+impl PartialEq<Value<'_>> for String {
+    fn eq(&self, other: &Value) -> bool {
+        eq_str(other, self.as_str())
+    }
+}
+
+macro_rules! partialeq_numeric {
+    ($($eq:ident [$($ty:ty)*])*) => {
+        $($(
+            impl PartialEq<$ty> for Value<'_> {
+                fn eq(&self, other: &$ty) -> bool {
+                    $eq(self, *other as _)
+                }
+            }
+
+            impl PartialEq<Value<'_>> for $ty {
+                fn eq(&self, other: &Value) -> bool {
+                    $eq(other, *self as _)
+                }
+            }
+
+            impl<'a> PartialEq<$ty> for &'a Value<'_> {
+                fn eq(&self, other: &$ty) -> bool {
+                    $eq(*self, *other as _)
+                }
+            }
+
+            impl<'a> PartialEq<$ty> for &'a mut Value<'_> {
+                fn eq(&self, other: &$ty) -> bool {
+                    $eq(*self, *other as _)
+                }
+            }
+        )*)*
+    }
+}
+
+partialeq_numeric! {
+    eq_i64[i8 i16 i32 i64 isize]
+    eq_u64[u8 u16 u32 u64 usize]
+    eq_f32[f32]
+    eq_f64[f64]
+    eq_bool[bool]
+}
+
+/// Compares structurally without converting one of the values. Numbers are equal if they have
+/// the same value and both are either integers or floats, like in `serde_json`. Objects are
+/// compared independently of the key order.
+impl PartialEq<serde_json::Value> for Value<'_> {
+    fn eq(&self, other: &serde_json::Value) -> bool {
+        eq_serde_json(self, other)
+    }
+}
+
+impl PartialEq<Value<'_>> for serde_json::Value {
+    fn eq(&self, other: &Value) -> bool {
+        eq_serde_json(other, self)
+    }
+}
+
+fn eq_serde_json(value: &Value, other: &serde_json::Value) -> bool {
+    match (value, other) {
+        (Value::Null, serde_json::Value::Null) => true,
+        (Value::Bool(a), serde_json::Value::Bool(b)) => a == b,
+        (Value::Number(a), serde_json::Value::Number(b)) => eq_number(a, b),
+        (Value::Str(a), serde_json::Value::String(b)) => a == b,
+        #[cfg(feature = "bytes")]
+        (Value::Bytes(a), serde_json::Value::Array(b)) => {
+            a.len() == b.len() && a.iter().zip(b).all(|(a, b)| b.as_u64() == Some(*a as u64))
+        }
+        (Value::Array(a), serde_json::Value::Array(b)) => {
+            a.len() == b.len() && a.iter().zip(b).all(|(a, b)| eq_serde_json(a, b))
+        }
+        (Value::Object(a), serde_json::Value::Object(b)) => {
+            // Checking all keys of `b` also handles duplicate keys in `a`.
+            a.len() == b.len()
+                && b.iter()
+                    .all(|(key, b)| a.get(key).is_some_and(|a| eq_serde_json(a, b)))
+        }
+        _ => false,
+    }
+}
+
+fn eq_number(number: &Number, other: &serde_json::Number) -> bool {
+    match number.n {
+        N::PosInt(a) => other.as_u64() == Some(a),
+        N::NegInt(a) => other.is_i64() && other.as_i64() == Some(a),
+        N::Float(a) => other.is_f64() && other.as_f64() == Some(a),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Value;
+
+    #[test]
+    fn partial_eq_primitives() {
+        let value: Value = serde_json::from_str(
+            r#"{"name": "John", "age": 43, "neg": -1, "score": 1.5, "ok": true}"#,
+        )
+        .unwrap();
+
+        assert_eq!(value.get("name"), "John");
+        assert_eq!(value["name"], "John");
+        assert_eq!("John", value["name"]);
+        assert_eq!(value["name"], String::from("John"));
+        assert_ne!(value["name"], "Jane");
+        assert_eq!(value["age"], 43);
+        assert_eq!(value.get("age"), 43u8);
+        assert_eq!(43usize, value["age"]);
+        assert_eq!(value["age"], 43.0);
+        assert_eq!(value["neg"], -1i64);
+        assert_ne!(value["neg"], u64::MAX);
+        assert_eq!(value["score"], 1.5f32);
+        assert_eq!(value["score"], 1.5);
+        assert_ne!(value["score"], 1);
+        assert_eq!(value["ok"], true);
+        assert_eq!(value.get("ok"), true);
+        assert_ne!(value["missing"], false);
+        assert_ne!(value["ok"], 1);
+    }
+
+    #[test]
+    fn partial_eq_serde_json() {
+        let json = r#"{"a": [1, -2, 2.5, "x", null, true], "b": {"c": {}}}"#;
+        let value: Value = serde_json::from_str(json).unwrap();
+        let serde_value: serde_json::Value = serde_json::from_str(json).unwrap();
+        assert_eq!(value, serde_value);
+        assert_eq!(serde_value, value);
+
+        // key order doesn't matter
+        let reordered: serde_json::Value =
+            serde_json::from_str(r#"{"b": {"c": {}}, "a": [1, -2, 2.5, "x", null, true]}"#)
+                .unwrap();
+        assert_eq!(value, reordered);
+
+        for other in [
+            r#"{"a": [1, -2, 2.5, "x", null, true]}"#,
+            r#"{"a": [1, -2, 2.5, "x", null, true], "b": {"c": []}}"#,
+            r#"{"a": [1.0, -2, 2.5, "x", null, true], "b": {"c": {}}}"#,
+            r#"{"a": [1, -2, 2.5, "x", null], "b": {"c": {}}}"#,
+            r#"{"a": [1, -2, 2.5, "y", null, true], "b": {"c": {}}}"#,
+            r#"{"a": [1, -2, 2.5, "x", null, true], "d": {"c": {}}}"#,
+        ] {
+            let other: serde_json::Value = serde_json::from_str(other).unwrap();
+            assert_ne!(value, other);
+        }
+    }
+
+    #[test]
+    fn partial_eq_serde_json_duplicate_keys() {
+        let value: Value = serde_json::from_str(r#"{"a": 1, "a": 1}"#).unwrap();
+        let other: serde_json::Value = serde_json::from_str(r#"{"a": 1, "b": 1}"#).unwrap();
+        assert_ne!(value, other);
+    }
+}
@@ -0,0 +1,864 @@
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::fmt;
+
+use crate::object_vec::{key_from_cow, key_str};
+#[cfg(feature = "cowkeys")]
+use crate::value::push_pointer_token;
+use crate::value::{parse_index, unescape_token};
+use crate::Value;
+
+/// A single operation of a JSON Patch document (RFC 6902).
+///
+/// Paths are JSON Pointers (RFC 6901). They are borrowed from the patch document if possible.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PatchOp<'ctx> {
+    /// Adds `value` at `path`. An existing object member is replaced, array elements are
+    /// shifted. The path `/-` on an array appends to it.
+    Add {
+        /// The target location.
+        path: Cow<'ctx, str>,
+        /// The value to add.
+        value: Value<'ctx>,
+    },
+    /// Removes the value at `path`, which must exist.
+    Remove {
+        /// The target location.
+        path: Cow<'ctx, str>,
+    },
+    /// Replaces the value at `path`, which must exist.
+    Replace {
+        /// The target location.
+        path: Cow<'ctx, str>,
+        /// The replacement value.
+        value: Value<'ctx>,
+    },
+    /// Removes the value at `from` and adds it at `path`.
+    Move {
+        /// The source location.
+        from: Cow<'ctx, str>,
+        /// The target location.
+        path: Cow<'ctx, str>,
+    },
+    /// Adds a copy of the value at `from` at `path`.
+    Copy {
+        /// The source location.
+        from: Cow<'ctx, str>,
+        /// The target location.
+        path: Cow<'ctx, str>,
+    },
+    /// Checks that the value at `path` is equal to `value`.
+    ///
+    /// Objects are compared independent of their key order and numbers by their numeric value,
+    /// e.g. `1` and `1.0` are equal. `NaN` is only equal to `NaN`, like in the order of
+    /// [`crate::Number`].
+    Test {
+        /// The target location.
+        path: Cow<'ctx, str>,
+        /// The expected value.
+        value: Value<'ctx>,
+    },
+}
+
+impl<'ctx> PatchOp<'ctx> {
+    /// Returns the target location of the operation.
+    pub fn path(&self) -> &str {
+        match self {
+            PatchOp::Add { path, .. }
+            | PatchOp::Remove { path }
+            | PatchOp::Replace { path, .. }
+            | PatchOp::Move { path, .. }
+            | PatchOp::Copy { path, .. }
+            | PatchOp::Test { path, .. } => path,
+        }
+    }
+
+    /// Parses a JSON Patch document, which is an array of operation objects.
+    ///
+    /// Members other than `op`, `path`, `from` and `value` are ignored.
+    ///
+    /// # Example
+    /// ```
+    /// use serde_json_borrow::{PatchOp, Value};
+    ///
+    /// let patch: Value = serde_json::from_str(r#"[{"op": "remove", "path": "/a"}]"#).unwrap();
+    /// let ops = PatchOp::parse_patch(&patch).unwrap();
+    /// assert_eq!(ops, vec![PatchOp::Remove { path: "/a".into() }]);
+    /// ```
+    pub fn parse_patch(patch: &Value<'ctx>) -> Result<Vec<PatchOp<'ctx>>, PatchError> {
+        let ops = patch.as_array().ok_or(PatchError {
+            index: None,
+            kind: PatchErrorKind::InvalidPatch("the patch must be an array"),
+        })?;
+        ops.iter()
+            .enumerate()
+            .map(|(index, op)| {
+                PatchOp::parse(op).map_err(|reason| PatchError {
+                    index: Some(index),
+                    kind: PatchErrorKind::InvalidPatch(reason),
+                })
+            })
+            .collect()
+    }
+
+    /// Parses a single operation object.
+    pub fn parse(op: &Value<'ctx>) -> Result<PatchOp<'ctx>, &'static str> {
+        let obj = op.as_object().ok_or("the operation must be an object")?;
+        let member = |name: &'static str, missing: &'static str| obj.get(name).ok_or(missing);
+        let pointer = |name: &'static str, missing: &'static str| match member(name, missing)? {
+            Value::Str(text) => Ok(text.clone()),
+            _ => Err(missing),
+        };
+        let path = pointer("path", "missing or invalid member `path`")?;
+        let value = || member("value", "missing member `value`").cloned();
+        let from = || pointer("from", "missing or invalid member `from`");
+        let op = match obj.get("op").and_then(Value::as_str) {
+            Some("add") => PatchOp::Add {
+                path,
+                value: value()?,
+            },
+            Some("remove") => PatchOp::Remove { path },
+            Some("replace") => PatchOp::Replace {
+                path,
+                value: value()?,
+            },
+            Some("move") => PatchOp::Move {
+                from: from()?,
+                path,
+            },
+            Some("copy") => PatchOp::Copy {
+                from: from()?,
+                path,
+            },
+            Some("test") => PatchOp::Test {
+                path,
+                value: value()?,
+            },
+            _ => return Err("missing or unknown member `op`"),
+        };
+        Ok(op)
+    }
+}
+
+/// Error returned when parsing or applying a JSON Patch fails.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PatchError {
+    /// The index of the failed operation in the patch, `None` if the patch itself is invalid.
+    pub index: Option<usize>,
+    /// The cause of the error.
+    pub kind: PatchErrorKind,
+}
+
+/// The cause of a [`PatchError`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PatchErrorKind {
+    /// The patch document is malformed.
+    InvalidPatch(&'static str),
+    /// The path is not a valid JSON Pointer.
+    InvalidPointer(String),
+    /// The location doesn't exist, or for `add` its parent doesn't exist.
+    PathNotFound(String),
+    /// A `move` operation tried to move a value into one of its children.
+    MoveIntoChild(String),
+    /// The key at the location needs to be owned, which requires the `cowkeys` feature flag.
+    OwnedKey(String),
+    /// A `test` operation failed.
+    TestFailed(String),
+}
+
+impl fmt::Display for PatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            PatchErrorKind::InvalidPatch(reason) => write!(f, "invalid patch: {reason}")?,
+            PatchErrorKind::InvalidPointer(path) => write!(f, "invalid JSON Pointer {path:?}")?,
+            PatchErrorKind::PathNotFound(path) => write!(f, "path {path:?} does not exist")?,
+            PatchErrorKind::MoveIntoChild(path) => {
+                write!(f, "can't move {path:?} into one of its children")?
+            }
+            PatchErrorKind::OwnedKey(path) => write!(
+                f,
+                "inserting at {path:?} requires the `cowkeys` feature flag"
+            )?,
+            PatchErrorKind::TestFailed(path) => write!(f, "test of path {path:?} failed")?,
+        }
+        if let Some(index) = self.index {
+            write!(f, " (operation {index})")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for PatchError {}
+
+impl<'ctx> Value<'ctx> {
+    /// Applies a JSON Patch (RFC 6902).
+    ///
+    /// The patch is applied atomically: if any operation fails, the value is left unchanged.
+    ///
+    /// Without the `cowkeys` feature flag, keys can only be inserted if they are borrowed from
+    /// the patch, i.e. they don't contain escape sequences and the path is not owned.
+    ///
+    /// ## Performance
+    /// The value is cloned before applying the operations, to be able to restore it on errors.
+    ///
+    /// # Example
+    /// ```
+    /// use serde_json_borrow::{PatchOp, Value};
+    ///
+    /// let mut value: Value = serde_json::from_str(r#"{"a": [1, 2], "b": {"c": true}}"#).unwrap();
+    /// let patch: Value = serde_json::from_str(
+    ///     r#"[
+    ///         {"op": "add", "path": "/a/-", "value": 3},
+    ///         {"op": "move", "from": "/b/c", "path": "/c"},
+    ///         {"op": "remove", "path": "/b"}
+    ///     ]"#,
+    /// )
+    /// .unwrap();
+    /// value.apply_patch(&PatchOp::parse_patch(&patch).unwrap()).unwrap();
+    /// assert_eq!(value, serde_json::json!({"a": [1, 2, 3], "c": true}));
+    /// ```
+    pub fn apply_patch(&mut self, patch: &[PatchOp<'ctx>]) -> Result<(), PatchError> {
+        let mut doc = self.clone();
+        for (index, op) in patch.iter().enumerate() {
+            apply_op(&mut doc, op).map_err(|kind| PatchError {
+                index: Some(index),
+                kind,
+            })?;
+        }
+        *self = doc;
+        Ok(())
+    }
+}
+
+fn apply_op<'ctx>(doc: &mut Value<'ctx>, op: &PatchOp<'ctx>) -> Result<(), PatchErrorKind> {
+    match op {
+        PatchOp::Add { path, value } => add(doc, path, &tokens(path)?, value.clone()),
+        PatchOp::Remove { path } => remove(doc, path, &tokens(path)?).map(drop),
+        PatchOp::Replace { path, value } => {
+            let target = resolve_mut(doc, &tokens(path)?)
+                .ok_or_else(|| PatchErrorKind::PathNotFound(path.to_string()))?;
+            *target = value.clone();
+            Ok(())
+        }
+        PatchOp::Move { from, path } => {
+            let from_tokens = tokens(from)?;
+            let path_tokens = tokens(path)?;
+            if path_tokens.len() > from_tokens.len() && path_tokens.starts_with(&from_tokens) {
+                return Err(PatchErrorKind::MoveIntoChild(from.to_string()));
+            }
+            let value = remove(doc, from, &from_tokens)?;
+            add(doc, path, &path_tokens, value)
+        }
+        PatchOp::Copy { from, path } => {
+            let value = resolve_mut(doc, &tokens(from)?)
+                .ok_or_else(|| PatchErrorKind::PathNotFound(from.to_string()))?
+                .clone();
+            add(doc, path, &tokens(path)?, value)
+        }
+        PatchOp::Test { path, value } => {
+            let target = resolve_mut(doc, &tokens(path)?)
+                .ok_or_else(|| PatchErrorKind::PathNotFound(path.to_string()))?;
+            if json_eq(target, value) {
+                Ok(())
+            } else {
+                Err(PatchErrorKind::TestFailed(path.to_string()))
+            }
+        }
+    }
+}
+
+/// Splits a JSON Pointer into its unescaped reference tokens. Tokens of a borrowed pointer are
+/// borrowed, unless they contain escape sequences.
+fn tokens<'ctx>(path: &Cow<'ctx, str>) -> Result<Vec<Cow<'ctx, str>>, PatchErrorKind> {
+    if path.is_empty() {
+        return Ok(Vec::new());
+    }
+    let invalid = || PatchErrorKind::InvalidPointer(path.to_string());
+    match path {
+        Cow::Borrowed(path) => Ok(path
+            .strip_prefix('/')
+            .ok_or_else(invalid)?
+            .split('/')
+            .map(unescape_token)
+            .collect()),
+        Cow::Owned(path) => Ok(path
+            .strip_prefix('/')
+            .ok_or_else(invalid)?
+            .split('/')
+            .map(|token| Cow::Owned(unescape_token(token).into_owned()))
+            .collect()),
+    }
+}
+
+fn resolve_mut<'a, 'ctx>(
+    doc: &'a mut Value<'ctx>,
+    tokens: &[Cow<'ctx, str>],
+) -> Option<&'a mut Value<'ctx>> {
+    tokens.iter().try_fold(doc, |target, token| match target {
+        Value::Object(map) => map.get_mut(token),
+        Value::Array(list) => parse_index(token).and_then(move |idx| list.get_mut(idx)),
+        _ => None,
+    })
+}
+
+fn add<'ctx>(
+    doc: &mut Value<'ctx>,
+    path: &str,
+    tokens: &[Cow<'ctx, str>],
+    value: Value<'ctx>,
+) -> Result<(), PatchErrorKind> {
+    let Some((last, parent)) = tokens.split_last() else {
+        *doc = value;
+        return Ok(());
+    };
+    let not_found = || PatchErrorKind::PathNotFound(path.to_string());
+    match resolve_mut(doc, parent).ok_or_else(not_found)? {
+        Value::Object(map) => {
+            if let Some(target) = map.get_mut(last) {
+                *target = value;
+            } else {
+                let key = key_from_cow(last.clone())
+                    .ok_or_else(|| PatchErrorKind::OwnedKey(path.to_string()))?;
+                debug_assert_eq!(key_str(&key), last);
+                map.insert_key_unchecked(key, value);
+            }
+        }
+        Value::Array(list) if last == "-" => list.push(value),
+        Value::Array(list) => {
+            let idx = parse_index(last)
+                .filter(|idx| *idx <= list.len())
+                .ok_or_else(not_found)?;
+            list.insert(idx, value);
+        }
+        _ => return Err(not_found()),
+    }
+    Ok(())
+}
+
+fn remove<'ctx>(
+    doc: &mut Value<'ctx>,
+    path: &str,
+    tokens: &[Cow<'ctx, str>],
+) -> Result<Value<'ctx>, PatchErrorKind> {
+    let not_found = || PatchErrorKind::PathNotFound(path.to_string());
+    let Some((last, parent)) = tokens.split_last() else {
+        // Removing the root leaves nothing behind, which is not a valid document.
+        return Err(not_found());
+    };
+    match resolve_mut(doc, parent).ok_or_else(not_found)? {
+        Value::Object(map) => map.remove(last).ok_or_else(not_found),
+        Value::Array(list) => {
+            let idx = parse_index(last)
+                .filter(|idx| *idx < list.len())
+                .ok_or_else(not_found)?;
+            Ok(list.remove(idx))
+        }
+        _ => Err(not_found()),
+    }
+}
+
+/// Computes a JSON Patch (RFC 6902), which transforms `from` into `to`.
+///
+/// Objects are compared member by member and arrays element by element, after skipping their
+/// common prefix and suffix. Only `add`, `remove` and `replace` operations are generated, so the
+/// patch is small, but not necessarily minimal.
+///
+/// Requires the `cowkeys` feature flag: the paths are built from several keys, so they are
+/// owned, and applying an `add` with an owned path inserts an owned key.
+///
+/// # Example
+/// ```
+/// use serde_json_borrow::{diff, PatchOp, Value};
+///
+/// let from: Value = serde_json::from_str(r#"{"a": 1, "b": [1, 2]}"#).unwrap();
+/// let to: Value = serde_json::from_str(r#"{"a": 2, "b": [1]}"#).unwrap();
+/// let patch = diff(&from, &to);
+/// assert_eq!(
+///     serde_json::to_string(&patch).unwrap(),
+///     r#"[{"op":"replace","path":"/a","value":2},{"op":"remove","path":"/b/1"}]"#
+/// );
+/// ```
+#[cfg(feature = "cowkeys")]
+pub fn diff<'ctx>(from: &Value<'_>, to: &Value<'ctx>) -> Vec<PatchOp<'ctx>> {
+    let mut ops = Vec::new();
+    diff_into(from, to, &mut String::new(), &mut ops);
+    ops
+}
+
+#[cfg(feature = "cowkeys")]
+fn diff_into<'ctx>(
+    from: &Value<'_>,
+    to: &Value<'ctx>,
+    path: &mut String,
+    ops: &mut Vec<PatchOp<'ctx>>,
+) {
+    match (from, to) {
+        (Value::Object(from), Value::Object(to)) => {
+            let len = path.len();
+            for key in from.keys() {
+                if !to.contains_key(key) {
+                    push_pointer_token(path, key);
+                    ops.push(PatchOp::Remove {
+                        path: Cow::Owned(path.clone()),
+                    });
+                    path.truncate(len);
+                }
+            }
+            for (key, value) in to.iter() {
+                push_pointer_token(path, key);
+                match from.get(key) {
+                    Some(old) => diff_into(old, value, path, ops),
+                    None => ops.push(PatchOp::Add {
+                        path: Cow::Owned(path.clone()),
+                        value: value.clone(),
+                    }),
+                }
+                path.truncate(len);
+            }
+        }
+        (Value::Array(from), Value::Array(to)) => {
+            let prefix = from.iter().zip(to).take_while(|(a, b)| a == b).count();
+            let suffix = from[prefix..]
+                .iter()
+                .rev()
+                .zip(to[prefix..].iter().rev())
+                .take_while(|(a, b)| a == b)
+                .count();
+            let from = &from[prefix..from.len() - suffix];
+            let to = &to[prefix..to.len() - suffix];
+            let len = path.len();
+            for (idx, (old, new)) in from.iter().zip(to).enumerate() {
+                push_pointer_token(path, &(prefix + idx).to_string());
+                diff_into(old, new, path, ops);
+                path.truncate(len);
+            }
+            let common = from.len().min(to.len());
+            push_pointer_token(path, &(prefix + common).to_string());
+            // Removing at the same index repeatedly shifts the following elements into place.
+            for _ in common..from.len() {
+                ops.push(PatchOp::Remove {
+                    path: Cow::Owned(path.clone()),
+                });
+            }
+            path.truncate(len);
+            for (idx, value) in to.iter().enumerate().skip(common) {
+                push_pointer_token(path, &(prefix + idx).to_string());
+                ops.push(PatchOp::Add {
+                    path: Cow::Owned(path.clone()),
+                    value: value.clone(),
+                });
+                path.truncate(len);
+            }
+        }
+        (from, to) if from == to => {}
+        (_, to) => ops.push(PatchOp::Replace {
+            path: Cow::Owned(path.clone()),
+            value: to.clone(),
+        }),
+    }
+}
+
+/// Compares like the `test` operation: objects independent of the key order and numbers by
+/// their numeric value. `cmp_numeric` is total, so non-finite numbers don't need special care.
+fn json_eq(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::Number(a), Value::Number(b)) => a.n.cmp_numeric(&b.n) == Ordering::Equal,
+        (Value::Array(a), Value::Array(b)) => {
+            a.len() == b.len() && a.iter().zip(b).all(|(a, b)| json_eq(a, b))
+        }
+        (Value::Object(a), Value::Object(b)) => {
+            a.len() == b.len()
+                && a.iter()
+                    .all(|(key, a)| b.get(key).is_some_and(|b| json_eq(a, b)))
+        }
+        (a, b) => a == b,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn apply(doc: &str, patch: &str) -> Result<serde_json::Value, PatchError> {
+        let mut doc: Value = serde_json::from_str(doc).unwrap();
+        let patch: Value = serde_json::from_str(patch).unwrap();
+        doc.apply_patch(&PatchOp::parse_patch(&patch)?)?;
+        Ok(doc.into())
+    }
+
+    fn error_kind(doc: &str, patch: &str) -> PatchErrorKind {
+        apply(doc, patch).unwrap_err().kind
+    }
+
+    #[test]
+    fn patch_rfc_examples() {
+        // Examples from RFC 6902 Appendix A
+        assert_eq!(
+            apply(
+                r#"{"foo": "bar"}"#,
+                r#"[{"op": "add", "path": "/baz", "value": "qux"}]"#
+            )
+            .unwrap(),
+            serde_json::json!({"baz": "qux", "foo": "bar"})
+        );
+        assert_eq!(
+            apply(
+                r#"{"foo": ["bar", "baz"]}"#,
+                r#"[{"op": "add", "path": "/foo/1", "value": "qux"}]"#
+            )
+            .unwrap(),
+            serde_json::json!({"foo": ["bar", "qux", "baz"]})
+        );
+        assert_eq!(
+            apply(
+                r#"{"baz": "qux", "foo": "bar"}"#,
+                r#"[{"op": "remove", "path": "/baz"}]"#
+            )
+            .unwrap(),
+            serde_json::json!({"foo": "bar"})
+        );
+        assert_eq!(
+            apply(
+                r#"{"foo": ["bar", "qux", "baz"]}"#,
+                r#"[{"op": "remove", "path": "/foo/1"}]"#
+            )
+            .unwrap(),
+            serde_json::json!({"foo": ["bar", "baz"]})
+        );
+        assert_eq!(
+            apply(
+                r#"{"baz": "qux", "foo": "bar"}"#,
+                r#"[{"op": "replace", "path": "/baz", "value": "boo"}]"#
+            )
+            .unwrap(),
+            serde_json::json!({"baz": "boo", "foo": "bar"})
+        );
+        assert_eq!(
+            apply(
+                r#"{"foo": {"bar": "baz", "waldo": "fred"}, "qux": {"corge": "grault"}}"#,
+                r#"[{"op": "move", "from": "/foo/waldo", "path": "/qux/thud"}]"#
+            )
+            .unwrap(),
+            serde_json::json!({"foo": {"bar": "baz"}, "qux": {"corge": "grault", "thud": "fred"}})
+        );
+        assert_eq!(
+            apply(
+                r#"{"foo": ["all", "grass", "cows", "eat"]}"#,
+                r#"[{"op": "move", "from": "/foo/1", "path": "/foo/3"}]"#
+            )
+            .unwrap(),
+            serde_json::json!({"foo": ["all", "cows", "eat", "grass"]})
+        );
+        assert_eq!(
+            apply(
+                r#"{"baz": "qux", "foo": ["a", 2, "c"]}"#,
+                r#"[{"op": "test", "path": "/baz", "value": "qux"},
+                    {"op": "test", "path": "/foo/1", "value": 2}]"#
+            )
+            .unwrap(),
+            serde_json::json!({"baz": "qux", "foo": ["a", 2, "c"]})
+        );
+        assert_eq!(
+            error_kind(
+                r#"{"baz": "qux"}"#,
+                r#"[{"op": "test", "path": "/baz", "value": "bar"}]"#
+            ),
+            PatchErrorKind::TestFailed("/baz".to_string())
+        );
+        assert_eq!(
+            apply(
+                r#"{"foo": "bar"}"#,
+                r#"[{"op": "add", "path": "/child", "value": {"grandchild": {}}}]"#
+            )
+            .unwrap(),
+            serde_json::json!({"child": {"grandchild": {}}, "foo": "bar"})
+        );
+        assert_eq!(
+            apply(
+                r#"{"foo": "bar"}"#,
+                r#"[{"op": "add", "path": "/baz", "value": "qux", "xyz": 123}]"#
+            )
+            .unwrap(),
+            serde_json::json!({"baz": "qux", "foo": "bar"})
+        );
+        assert_eq!(
+            error_kind(
+                r#"{"foo": "bar"}"#,
+                r#"[{"op": "add", "path": "/baz/bat", "value": "qux"}]"#
+            ),
+            PatchErrorKind::PathNotFound("/baz/bat".to_string())
+        );
+        assert_eq!(
+            apply(
+                r#"{"/": 9, "~1": 10}"#,
+                r#"[{"op": "test", "path": "/~01", "value": 10}]"#
+            )
+            .unwrap(),
+            serde_json::json!({"/": 9, "~1": 10})
+        );
+        assert_eq!(
+            error_kind(
+                r#"{"/": 9, "~1": 10}"#,
+                r#"[{"op": "test", "path": "/~01", "value": "10"}]"#
+            ),
+            PatchErrorKind::TestFailed("/~01".to_string())
+        );
+        assert_eq!(
+            apply(
+                r#"{"foo": ["bar"]}"#,
+                r#"[{"op": "add", "path": "/foo/-", "value": ["abc", "def"]}]"#
+            )
+            .unwrap(),
+            serde_json::json!({"foo": ["bar", ["abc", "def"]]})
+        );
+    }
+
+    #[test]
+    fn patch_copy_and_root() {
+        assert_eq!(
+            apply(
+                r#"{"a": {"b": [1]}}"#,
+                r#"[{"op": "copy", "from": "/a", "path": "/c"},
+                    {"op": "add", "path": "/c/b/0", "value": 0}]"#
+            )
+            .unwrap(),
+            serde_json::json!({"a": {"b": [1]}, "c": {"b": [0, 1]}})
+        );
+        assert_eq!(
+            apply(
+                r#"{"a": 1}"#,
+                r#"[{"op": "replace", "path": "", "value": [1]}]"#
+            )
+            .unwrap(),
+            serde_json::json!([1])
+        );
+        assert_eq!(
+            apply(
+                r#"{"a": 1}"#,
+                r#"[{"op": "move", "from": "/a", "path": "/a"}]"#
+            )
+            .unwrap(),
+            serde_json::json!({"a": 1})
+        );
+    }
+
+    #[test]
+    fn patch_test_equality() {
+        assert!(apply(
+            r#"{"a": {"x": 1, "y": [2.0]}}"#,
+            r#"[{"op": "test", "path": "/a", "value": {"y": [2], "x": 1.0}}]"#
+        )
+        .is_ok());
+        assert_eq!(
+            error_kind(
+                r#"{"a": {"x": 1}}"#,
+                r#"[{"op": "test", "path": "/a", "value": {"x": 1, "y": null}}]"#
+            ),
+            PatchErrorKind::TestFailed("/a".to_string())
+        );
+
+        // Non-finite numbers can't be parsed from standard JSON, but may be in the document.
+        let test = |value: f64| PatchOp::Test {
+            path: "/0".into(),
+            value: Value::from(value),
+        };
+        let mut doc = Value::from(vec![f64::NAN]);
+        assert!(doc.apply_patch(&[test(f64::NAN)]).is_ok());
+        let err = doc.apply_patch(&[test(1.5)]).unwrap_err();
+        assert_eq!(err.kind, PatchErrorKind::TestFailed("/0".to_string()));
+        let mut doc = Value::from(vec![1.5]);
+        assert!(doc.apply_patch(&[test(f64::NAN)]).is_err());
+        assert!(doc.apply_patch(&[test(f64::INFINITY)]).is_err());
+    }
+
+    #[test]
+    fn patch_errors() {
+        assert_eq!(
+            error_kind(r#"[1]"#, r#"[{"op": "add", "path": "/2", "value": 0}]"#),
+            PatchErrorKind::PathNotFound("/2".to_string())
+        );
+        assert_eq!(
+            error_kind(r#"[1]"#, r#"[{"op": "remove", "path": "/01"}]"#),
+            PatchErrorKind::PathNotFound("/01".to_string())
+        );
+        assert_eq!(
+            error_kind(
+                r#"{"a": 1}"#,
+                r#"[{"op": "replace", "path": "/b", "value": 0}]"#
+            ),
+            PatchErrorKind::PathNotFound("/b".to_string())
+        );
+        assert_eq!(
+            error_kind(
+                r#"{"a": {}}"#,
+                r#"[{"op": "move", "from": "/a", "path": "/a/b"}]"#
+            ),
+            PatchErrorKind::MoveIntoChild("/a".to_string())
+        );
+        assert_eq!(
+            error_kind(r#"{}"#, r#"[{"op": "add", "path": "a", "value": 0}]"#),
+            PatchErrorKind::InvalidPointer("a".to_string())
+        );
+        let err = apply(
+            r#"{}"#,
+            r#"[{"op": "add", "path": "/a", "value": 0}, {"op": "invalid", "path": "/a"}]"#,
+        )
+        .unwrap_err();
+        assert_eq!(err.index, Some(1));
+        assert_eq!(
+            err.to_string(),
+            "invalid patch: missing or unknown member `op` (operation 1)"
+        );
+        assert_eq!(
+            error_kind(r#"{}"#, r#"[{"op": "add", "path": "/a"}]"#),
+            PatchErrorKind::InvalidPatch("missing member `value`")
+        );
+        assert_eq!(
+            error_kind(r#"{}"#, r#"{"op": "add"}"#),
+            PatchErrorKind::InvalidPatch("the patch must be an array")
+        );
+    }
+
+    #[test]
+    fn patch_is_atomic() {
+        let mut doc: Value = serde_json::from_str(r#"{"a": 1}"#).unwrap();
+        let ops = vec![
+            PatchOp::Add {
+                path: "/b".into(),
+                value: Value::from(2u64),
+            },
+            PatchOp::Remove { path: "/c".into() },
+        ];
+        let err = doc.apply_patch(&ops).unwrap_err();
+        assert_eq!(err.index, Some(1));
+        assert_eq!(doc, serde_json::json!({"a": 1}));
+    }
+
+    #[cfg(feature = "cowkeys")]
+    fn check_diff<'a>(from: &str, to: &'a str) -> Vec<PatchOp<'a>> {
+        let from: Value = serde_json::from_str(from).unwrap();
+        let to: Value = serde_json::from_str(to).unwrap();
+        let patch = diff(&from, &to);
+        let mut patched = from.clone();
+        patched.apply_patch(&patch).unwrap();
+        assert_eq!(
+            serde_json::Value::from(patched),
+            serde_json::Value::from(to.clone())
+        );
+        patch
+    }
+
+    #[cfg(feature = "cowkeys")]
+    #[test]
+    fn diff_scalars_and_objects() {
+        assert_eq!(
+            check_diff(r#"{"a": [1, {"b": 2}]}"#, r#"{"a": [1, {"b": 2}]}"#),
+            vec![]
+        );
+        assert_eq!(
+            check_diff(r#"1"#, r#"1.0"#),
+            vec![PatchOp::Replace {
+                path: "".into(),
+                value: Value::from(1.0),
+            }]
+        );
+        assert_eq!(
+            check_diff(
+                r#"{"a": 1, "b": {"c": "x", "d": true}, "e~/": null}"#,
+                r#"{"a": 1, "b": {"c": "y", "f": false}}"#
+            ),
+            vec![
+                PatchOp::Remove {
+                    path: "/e~0~1".into()
+                },
+                PatchOp::Remove {
+                    path: "/b/d".into()
+                },
+                PatchOp::Replace {
+                    path: "/b/c".into(),
+                    value: Value::from("y"),
+                },
+                PatchOp::Add {
+                    path: "/b/f".into(),
+                    value: Value::Bool(false),
+                },
+            ]
+        );
+    }
+
+    #[cfg(feature = "cowkeys")]
+    #[test]
+    fn diff_added_keys() {
+        let patch = check_diff(
+            r#"{"a": {}, "b": [{"c": 1}]}"#,
+            r#"{"a": {"x/y": {"z": [1]}}, "b": [{"c": 1, "d~": null}], "e": "new"}"#,
+        );
+        assert_eq!(
+            patch.iter().map(PatchOp::path).collect::<Vec<_>>(),
+            ["/a/x~1y", "/b/0/d~0", "/e"]
+        );
+        assert!(patch.iter().all(|op| matches!(op, PatchOp::Add { .. })));
+    }
+
+    #[cfg(feature = "cowkeys")]
+    #[test]
+    fn diff_arrays() {
+        assert_eq!(
+            check_diff(r#"[1, 2, 3, 4, 5]"#, r#"[1, 5]"#),
+            vec![
+                PatchOp::Remove { path: "/1".into() },
+                PatchOp::Remove { path: "/1".into() },
+                PatchOp::Remove { path: "/1".into() },
+            ]
+        );
+        assert_eq!(
+            check_diff(r#"[1, 4]"#, r#"[1, 2, 3, 4]"#),
+            vec![
+                PatchOp::Add {
+                    path: "/1".into(),
+                    value: Value::from(2u64),
+                },
+                PatchOp::Add {
+                    path: "/2".into(),
+                    value: Value::from(3u64),
+                },
+            ]
+        );
+        assert_eq!(
+            check_diff(r#"[[1], {"a": 1}]"#, r#"[[1, 2], {"a": 2}, null]"#),
+            vec![
+                PatchOp::Add {
+                    path: "/0/1".into(),
+                    value: Value::from(2u64),
+                },
+                PatchOp::Replace {
+                    path: "/1/a".into(),
+                    value: Value::from(2u64),
+                },
+                PatchOp::Add {
+                    path: "/2".into(),
+                    value: Value::Null,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn patch_owned_key() {
+        let mut doc: Value = serde_json::from_str(r#"{}"#).unwrap();
+        let ops = vec![PatchOp::Add {
+            path: Cow::Owned("/a~1b".to_string()),
+            value: Value::Null,
+        }];
+        let result = doc.apply_patch(&ops);
+        if cfg!(feature = "cowkeys") {
+            assert!(result.is_ok());
+            assert_eq!(doc, serde_json::json!({"a/b": null}));
+        } else {
+            assert_eq!(
+                result.unwrap_err().kind,
+                PatchErrorKind::OwnedKey("/a~1b".to_string())
+            );
+        }
+    }
+}
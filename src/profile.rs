@@ -0,0 +1,235 @@
+use core::fmt;
+use std::borrow::Cow;
+use std::io;
+
+use serde::de::{Deserialize, DeserializeSeed, MapAccess, SeqAccess, Visitor};
+
+use crate::object_vec::{key_str, KeyStrType, ObjectAsVec};
+use crate::Value;
+
+/// The shape of sample documents, to pre-size arrays and objects when parsing similar documents.
+///
+/// A profile records the largest observed length of the arrays and objects at every position
+/// of the samples. [`ParseProfile::parse`] allocates every array and object with that capacity
+/// upfront, so for homogeneous streams, e.g. of log events, nearly no reallocation happens
+/// while the `Value` is built.
+///
+/// All elements of an array share one profile. Positions not covered by the profile are parsed
+/// as usual.
+///
+/// # Example
+/// ```
+/// use serde_json_borrow::{ParseProfile, Value};
+///
+/// let sample: Value = serde_json::from_str(r#"{"id": 1, "tags": ["a", "b", "c"]}"#).unwrap();
+/// let profile = ParseProfile::learn(&sample);
+///
+/// let value = profile.parse(r#"{"id": 2, "tags": ["d"]}"#).unwrap();
+/// assert_eq!(value.get("tags").as_array().unwrap().len(), 1);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ParseProfile {
+    /// The capacity of the array or object at this position.
+    capacity: usize,
+    /// The profile of the elements, if the value was an array.
+    elements: Option<Box<ParseProfile>>,
+    /// The profiles of the members, if the value was an object.
+    members: Vec<(String, ParseProfile)>,
+}
+
+impl ParseProfile {
+    /// Learns the profile of a sample document.
+    pub fn learn(value: &Value) -> Self {
+        let mut profile = Self::default();
+        profile.update(value);
+        profile
+    }
+
+    /// Updates the profile with another sample document. Capacities grow to the largest
+    /// observed length.
+    pub fn update(&mut self, value: &Value) {
+        match value {
+            Value::Array(arr) => {
+                self.capacity = self.capacity.max(arr.len());
+                let elements = self.elements.get_or_insert_with(Box::default);
+                for element in arr {
+                    elements.update(element);
+                }
+            }
+            Value::Object(obj) => {
+                self.capacity = self.capacity.max(obj.len());
+                for (key, value) in obj.iter() {
+                    match self.members.iter_mut().find(|(member, _)| *member == key) {
+                        Some((_, profile)) => profile.update(value),
+                        None => self.members.push((key.to_string(), Self::learn(value))),
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Parses `json` into a `Value`, pre-sizing arrays and objects according to the profile.
+    pub fn parse<'ctx>(&self, json: &'ctx str) -> io::Result<Value<'ctx>> {
+        let mut deserializer = serde_json::Deserializer::from_str(json);
+        let value = ProfileSeed(Some(self)).deserialize(&mut deserializer)?;
+        deserializer.end()?;
+        Ok(value)
+    }
+
+    /// Returns the profile of the member `key`. Members usually appear in the same order as in
+    /// the samples, so the member at `pos` is checked first.
+    fn member(&self, pos: usize, key: &str) -> Option<&ParseProfile> {
+        match self.members.get(pos) {
+            Some((member, profile)) if *member == key => Some(profile),
+            _ => self
+                .members
+                .iter()
+                .find(|(member, _)| *member == key)
+                .map(|(_, profile)| profile),
+        }
+    }
+}
+
+/// Deserializes a `Value` with the profile of its position, if there is one.
+struct ProfileSeed<'p>(Option<&'p ParseProfile>);
+
+impl<'de> DeserializeSeed<'de> for ProfileSeed<'_> {
+    type Value = Value<'de>;
+
+    #[inline]
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where D: serde::Deserializer<'de> {
+        match self.0 {
+            Some(profile) => deserializer.deserialize_any(ProfileVisitor(profile)),
+            None => Value::deserialize(deserializer),
+        }
+    }
+}
+
+struct ProfileVisitor<'p>(&'p ParseProfile);
+
+impl<'de> Visitor<'de> for ProfileVisitor<'_> {
+    type Value = Value<'de>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("any valid JSON value")
+    }
+
+    #[inline]
+    fn visit_bool<E>(self, value: bool) -> Result<Value<'de>, E> {
+        Ok(Value::Bool(value))
+    }
+
+    #[inline]
+    fn visit_i64<E>(self, value: i64) -> Result<Value<'de>, E> {
+        Ok(Value::Number(value.into()))
+    }
+
+    #[inline]
+    fn visit_u64<E>(self, value: u64) -> Result<Value<'de>, E> {
+        Ok(Value::Number(value.into()))
+    }
+
+    #[inline]
+    fn visit_f64<E>(self, value: f64) -> Result<Value<'de>, E> {
+        Ok(Value::Number(value.into()))
+    }
+
+    #[inline]
+    fn visit_string<E>(self, v: String) -> Result<Self::Value, E> {
+        Ok(Value::Str(v.into()))
+    }
+
+    #[inline]
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E> {
+        Ok(Value::Str(Cow::Owned(v.to_owned())))
+    }
+
+    #[inline]
+    fn visit_borrowed_str<E>(self, v: &'de str) -> Result<Self::Value, E> {
+        Ok(Value::Str(Cow::Borrowed(v)))
+    }
+
+    #[cfg(feature = "bytes")]
+    #[inline]
+    fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+        Ok(Value::Bytes(v.into()))
+    }
+
+    #[cfg(feature = "bytes")]
+    #[inline]
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E> {
+        Ok(Value::Bytes(Cow::Owned(v.to_owned())))
+    }
+
+    #[cfg(feature = "bytes")]
+    #[inline]
+    fn visit_borrowed_bytes<E>(self, v: &'de [u8]) -> Result<Self::Value, E> {
+        Ok(Value::Bytes(Cow::Borrowed(v)))
+    }
+
+    #[inline]
+    fn visit_unit<E>(self) -> Result<Value<'de>, E> {
+        Ok(Value::Null)
+    }
+
+    #[inline]
+    fn visit_seq<V>(self, mut visitor: V) -> Result<Value<'de>, V::Error>
+    where V: SeqAccess<'de> {
+        let capacity = visitor.size_hint().unwrap_or(0).max(self.0.capacity);
+        let mut vec = Vec::with_capacity(capacity);
+        let elements = self.0.elements.as_deref();
+        while let Some(elem) = visitor.next_element_seed(ProfileSeed(elements))? {
+            vec.push(elem);
+        }
+        Ok(Value::Array(vec))
+    }
+
+    #[inline]
+    fn visit_map<V>(self, mut visitor: V) -> Result<Value<'de>, V::Error>
+    where V: MapAccess<'de> {
+        let capacity = visitor.size_hint().unwrap_or(0).max(self.0.capacity);
+        let mut values = Vec::with_capacity(capacity);
+        while let Some(key) = visitor.next_key::<KeyStrType>()? {
+            let profile = self.0.member(values.len(), key_str(&key));
+            let value = visitor.next_value_seed(ProfileSeed(profile))?;
+            values.push((key, value));
+        }
+        Ok(Value::Object(ObjectAsVec::from_entries(values)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_profile_test() {
+        let sample: Value =
+            serde_json::from_str(r#"{"a": [[1, 2], [3, 4, 5]], "b": {"c": null}}"#).unwrap();
+        let mut profile = ParseProfile::learn(&sample);
+        let other: Value = serde_json::from_str(r#"{"b": {"d": 1, "c": [1]}}"#).unwrap();
+        profile.update(&other);
+
+        let json = r#"{"b": {"c": [], "x": [1]}, "a": [[6]], "z": {"y": 1}}"#;
+        let value = profile.parse(json).unwrap();
+        assert_eq!(value, serde_json::from_str::<Value>(json).unwrap());
+        let Value::Array(inner) = value.get("a").get(0) else {
+            panic!("expected array");
+        };
+        assert!(inner.capacity() >= 3);
+        let Value::Array(c) = value.get("b").get("c") else {
+            panic!("expected array");
+        };
+        assert!(c.capacity() >= 1);
+        assert_eq!(value.get("b").as_object().unwrap().len(), 2);
+
+        assert!(profile.parse(r#"{"a": [}"#).is_err());
+        assert!(profile.parse("1 2").is_err());
+        assert_eq!(
+            ParseProfile::learn(&Value::Null).parse("\"s\"").unwrap(),
+            "s"
+        );
+    }
+}
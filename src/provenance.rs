@@ -0,0 +1,196 @@
+use std::collections::BTreeMap;
+
+use crate::object_vec::key_str;
+use crate::value::push_pointer_token;
+use crate::Value;
+
+/// Merges layered documents (e.g. default config, environment config, user config) and records
+/// for each leaf which layer supplied it.
+///
+/// Objects are merged recursively, any other value of a later layer replaces the value of an
+/// earlier layer. Leaves are all values that are not objects, e.g. arrays are leaves.
+///
+/// # Example
+/// ```
+/// use serde_json_borrow::{Layered, Value};
+///
+/// let defaults: Value = serde_json::from_str(r#"{"db": {"host": "localhost", "port": 5432}}"#).unwrap();
+/// let user: Value = serde_json::from_str(r#"{"db": {"host": "db.example.com"}}"#).unwrap();
+///
+/// let mut config = Layered::new();
+/// config.merge("defaults", defaults);
+/// config.merge("user.json", user);
+///
+/// assert_eq!(config.value().pointer("/db/host"), Some(&Value::from("db.example.com")));
+/// assert_eq!(config.provenance("/db/host"), Some("user.json"));
+/// assert_eq!(config.provenance("/db/port"), Some("defaults"));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Layered<'ctx> {
+    value: Value<'ctx>,
+    sources: Vec<String>,
+    /// JSON Pointer of a leaf to the index in `sources`.
+    provenance: BTreeMap<String, usize>,
+}
+
+impl<'ctx> Layered<'ctx> {
+    /// Creates an empty `Layered` document. The merged value is `Value::Null`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Merges `layer` on top of the current document and records `source` as origin of all leaves
+    /// supplied by `layer`.
+    pub fn merge(&mut self, source: impl Into<String>, layer: Value<'ctx>) {
+        let source_idx = self.sources.len();
+        self.sources.push(source.into());
+        let mut pointer = String::new();
+        merge_recursive(
+            &mut self.value,
+            layer,
+            &mut pointer,
+            source_idx,
+            &mut self.provenance,
+        );
+    }
+
+    /// Returns the name of the source which supplied the leaf at the JSON Pointer `pointer`.
+    ///
+    /// Returns `None` if there is no leaf at `pointer`. Objects are no leaves, so they don't have
+    /// a provenance.
+    pub fn provenance(&self, pointer: &str) -> Option<&str> {
+        self.provenance
+            .get(pointer)
+            .map(|idx| self.sources[*idx].as_str())
+    }
+
+    /// An iterator over the JSON Pointers of all leaves and the name of their source.
+    pub fn iter_provenance(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.provenance
+            .iter()
+            .map(|(pointer, idx)| (pointer.as_str(), self.sources[*idx].as_str()))
+    }
+
+    /// Returns the merged document.
+    pub fn value(&self) -> &Value<'ctx> {
+        &self.value
+    }
+
+    /// Returns the merged document and drops the provenance information.
+    pub fn into_value(self) -> Value<'ctx> {
+        self.value
+    }
+}
+
+fn merge_recursive<'ctx>(
+    target: &mut Value<'ctx>,
+    layer: Value<'ctx>,
+    pointer: &mut String,
+    source_idx: usize,
+    provenance: &mut BTreeMap<String, usize>,
+) {
+    match (target, layer) {
+        (Value::Object(target), Value::Object(layer)) => {
+            for (key, value) in layer.0 {
+                let len = pointer.len();
+                push_pointer_token(pointer, key_str(&key));
+                match target.get_mut(key_str(&key)) {
+                    Some(existing) => {
+                        merge_recursive(existing, value, pointer, source_idx, provenance)
+                    }
+                    None => {
+                        record_leaves(&value, pointer, source_idx, provenance);
+                        target.insert_key_unchecked(key, value);
+                    }
+                }
+                pointer.truncate(len);
+            }
+        }
+        (target, layer) => {
+            remove_subtree(pointer, provenance);
+            record_leaves(&layer, pointer, source_idx, provenance);
+            *target = layer;
+        }
+    }
+}
+
+/// Removes the provenance of the value at `pointer` and all of its children.
+fn remove_subtree(pointer: &str, provenance: &mut BTreeMap<String, usize>) {
+    provenance.remove(pointer);
+    let prefix = format!("{pointer}/");
+    let children: Vec<String> = provenance
+        .range(prefix.clone()..)
+        .take_while(|(child, _)| child.starts_with(&prefix))
+        .map(|(child, _)| child.clone())
+        .collect();
+    for child in children {
+        provenance.remove(&child);
+    }
+}
+
+fn record_leaves(
+    value: &Value,
+    pointer: &mut String,
+    source_idx: usize,
+    provenance: &mut BTreeMap<String, usize>,
+) {
+    match value {
+        Value::Object(obj) => {
+            for (key, value) in obj.iter() {
+                let len = pointer.len();
+                push_pointer_token(pointer, key);
+                record_leaves(value, pointer, source_idx, provenance);
+                pointer.truncate(len);
+            }
+        }
+        _ => {
+            provenance.insert(pointer.clone(), source_idx);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(json: &str) -> Value<'_> {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn provenance_merge_objects() {
+        let mut layered = Layered::new();
+        layered.merge("a", parse(r#"{"x": 1, "y": {"z": [1, 2], "w": true}}"#));
+        layered.merge("b", parse(r#"{"y": {"z": [3]}, "v/1": null}"#));
+
+        assert_eq!(
+            layered.value(),
+            &parse(r#"{"x": 1, "y": {"z": [3], "w": true}, "v/1": null}"#)
+        );
+        assert_eq!(layered.provenance("/x"), Some("a"));
+        assert_eq!(layered.provenance("/y/z"), Some("b"));
+        assert_eq!(layered.provenance("/y/w"), Some("a"));
+        assert_eq!(layered.provenance("/v~11"), Some("b"));
+        assert_eq!(layered.provenance("/y"), None);
+        assert_eq!(layered.provenance("/y/z/0"), None);
+    }
+
+    #[test]
+    fn provenance_replace_object_with_scalar() {
+        let mut layered = Layered::new();
+        layered.merge("a", parse(r#"{"x": {"y": 1, "z": 2}, "xx": 3}"#));
+        layered.merge("b", parse(r#"{"x": false}"#));
+        assert_eq!(layered.provenance("/x"), Some("b"));
+        assert_eq!(layered.provenance("/x/y"), None);
+        assert_eq!(layered.provenance("/xx"), Some("a"));
+
+        layered.merge("c", parse(r#"{"x": {"y": 5}}"#));
+        assert_eq!(layered.provenance("/x"), None);
+        assert_eq!(layered.provenance("/x/y"), Some("c"));
+        assert_eq!(
+            layered.iter_provenance().collect::<Vec<_>>(),
+            vec![("/x/y", "c"), ("/xx", "a")]
+        );
+        assert_eq!(layered.into_value(), parse(r#"{"x": {"y": 5}, "xx": 3}"#));
+    }
+}
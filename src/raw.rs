@@ -0,0 +1,114 @@
+use std::{fmt, io};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::Value;
+
+/// The exact, unparsed source text of a JSON value.
+///
+/// When deserialized, the subtree is only validated and borrowed from the input, but not parsed
+/// into a [`Value`]. When serialized, the text is written verbatim. This allows proxies, which
+/// only inspect a few fields, to skip parsing the rest of the document.
+///
+/// Like `Value`, it borrows from the input, so it can only be deserialized from a `&str` or
+/// `&[u8]`, not from a reader.
+///
+/// # Example
+/// ```
+/// use std::collections::BTreeMap;
+///
+/// use serde_json_borrow::RawValue;
+///
+/// let json = r#"{"route": "users", "payload": {"name": "John", "tags": [1, 2]}}"#;
+/// let doc: BTreeMap<&str, RawValue> = serde_json::from_str(json).unwrap();
+///
+/// assert_eq!(doc["route"].parse().unwrap(), "users");
+/// assert_eq!(doc["payload"].get(), r#"{"name": "John", "tags": [1, 2]}"#);
+/// assert_eq!(
+///     serde_json::to_string(&doc).unwrap(),
+///     r#"{"payload":{"name": "John", "tags": [1, 2]},"route":"users"}"#
+/// );
+/// ```
+#[derive(Clone, Copy)]
+pub struct RawValue<'ctx>(&'ctx serde_json::value::RawValue);
+
+impl<'ctx> RawValue<'ctx> {
+    /// Validates that `json` is a single JSON value and wraps it.
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(json: &'ctx str) -> io::Result<Self> {
+        Ok(RawValue(serde_json::from_str(json)?))
+    }
+
+    /// Returns the source text of the value.
+    pub fn get(&self) -> &'ctx str {
+        self.0.get()
+    }
+
+    /// Parses the source text into a [`Value`], borrowing from the original input.
+    pub fn parse(&self) -> io::Result<Value<'ctx>> {
+        Ok(serde_json::from_str(self.get())?)
+    }
+}
+
+impl<'de> Deserialize<'de> for RawValue<'de> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where D: Deserializer<'de> {
+        <&serde_json::value::RawValue>::deserialize(deserializer).map(RawValue)
+    }
+}
+
+/// Serializes the source text verbatim with `serde_json`.
+impl Serialize for RawValue<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where S: Serializer {
+        self.0.serialize(serializer)
+    }
+}
+
+impl fmt::Debug for RawValue<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("RawValue").field(&self.get()).finish()
+    }
+}
+
+/// Writes the source text verbatim.
+impl fmt::Display for RawValue<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.get())
+    }
+}
+
+impl PartialEq for RawValue<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.get() == other.get()
+    }
+}
+
+impl Eq for RawValue<'_> {}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+
+    #[test]
+    fn raw_value_test() {
+        let json = r#"{"body": [ {"a": "\n"}, 1.50 ], "id": 1}"#;
+        let doc: BTreeMap<&str, RawValue> = serde_json::from_str(json).unwrap();
+        let body = doc["body"];
+        assert_eq!(body.get(), r#"[ {"a": "\n"}, 1.50 ]"#);
+        assert_eq!(body.to_string(), body.get());
+        assert_eq!(body.parse().unwrap(), serde_json::json!([{"a": "\n"}, 1.5]));
+        assert_eq!(
+            serde_json::to_string(&doc).unwrap(),
+            r#"{"body":[ {"a": "\n"}, 1.50 ],"id":1}"#
+        );
+
+        let raw = RawValue::from_str("true").unwrap();
+        assert_eq!(raw.get(), "true");
+        assert_eq!(raw, RawValue::from_str("true").unwrap());
+        assert!(RawValue::from_str("[1,").is_err());
+        assert!(RawValue::from_str("1 2").is_err());
+    }
+}
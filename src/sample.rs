@@ -0,0 +1,79 @@
+use crate::{ObjectAsVec, Value};
+
+/// The key of the marker object, which [`Value::sample_arrays`] appends to truncated arrays. Its
+/// value is the original length of the array.
+pub const TRUNCATED_MARKER_KEY: &str = "$truncated_len";
+
+impl Value<'_> {
+    /// Truncates all arrays, including nested ones, to their first `max_elements` elements.
+    ///
+    /// A marker object `{"$truncated_len": <original length>}` is appended to every truncated
+    /// array, so consumers can tell that elements are missing. The marker is not counted in
+    /// `max_elements`. See [`TRUNCATED_MARKER_KEY`].
+    ///
+    /// # Example
+    /// ```
+    /// use serde_json_borrow::Value;
+    ///
+    /// let mut value: Value =
+    ///     serde_json::from_str(r#"{"spans": [1, 2, 3, {"events": [1, 2, 3, 4]}]}"#).unwrap();
+    /// value.sample_arrays(2);
+    /// assert_eq!(value, serde_json::json!({"spans": [1, 2, {"$truncated_len": 4}]}));
+    /// ```
+    pub fn sample_arrays(&mut self, max_elements: usize) {
+        match self {
+            Value::Array(arr) => {
+                let len = arr.len();
+                arr.truncate(max_elements);
+                for value in arr.iter_mut() {
+                    value.sample_arrays(max_elements);
+                }
+                if len > max_elements {
+                    arr.push(Value::Object(ObjectAsVec::from(vec![(
+                        TRUNCATED_MARKER_KEY,
+                        Value::from(len as u64),
+                    )])));
+                }
+            }
+            Value::Object(obj) => {
+                for (_, value) in obj.0.iter_mut() {
+                    value.sample_arrays(max_elements);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sample_arrays_nested() {
+        let mut value: Value = serde_json::from_str(
+            r#"{"a": [[1, 2, 3], [4], {"b": [5, 6, 7]}], "c": [1, 2], "d": "x"}"#,
+        )
+        .unwrap();
+        value.sample_arrays(2);
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "a": [[1, 2, {"$truncated_len": 3}], [4], {"$truncated_len": 3}],
+                "c": [1, 2],
+                "d": "x"
+            })
+        );
+    }
+
+    #[test]
+    fn sample_arrays_zero() {
+        let mut value: Value = serde_json::from_str(r#"[[1], []]"#).unwrap();
+        value.sample_arrays(0);
+        assert_eq!(value, serde_json::json!([{"$truncated_len": 2}]));
+
+        let mut value: Value = serde_json::from_str(r#"[]"#).unwrap();
+        value.sample_arrays(0);
+        assert_eq!(value, serde_json::json!([]));
+    }
+}
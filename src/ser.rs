@@ -1,9 +1,16 @@
-use serde::ser::{Serialize, Serializer};
+use std::{fmt, io, slice};
 
+use serde::ser::{Serialize, SerializeMap, Serializer};
+
+use crate::object_vec::{key_str, KeyStrType};
 use crate::owned::OwnedValue;
+use crate::patch::PatchOp;
 use crate::value::{Number, Value, N};
-use crate::Map;
+use crate::value_ref::ValueRef;
+use crate::{CachedValue, Map};
 
+/// Serializes the value recursively, one call per nesting level. Use [`to_string`] for values
+/// nested deeper than the parsing limit, see [`Value`](crate::Value#nesting).
 impl Serialize for Value<'_> {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where S: Serializer {
@@ -12,6 +19,8 @@ impl Serialize for Value<'_> {
             Value::Bool(b) => serializer.serialize_bool(*b),
             Value::Number(n) => n.serialize(serializer),
             Value::Str(s) => serializer.serialize_str(s),
+            #[cfg(feature = "bytes")]
+            Value::Bytes(bytes) => serializer.serialize_bytes(bytes),
             Value::Array(v) => serializer.collect_seq(v),
             Value::Object(m) => m.serialize(serializer),
         }
@@ -42,8 +51,452 @@ impl Serialize for Number {
     }
 }
 
+impl Serialize for PatchOp<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where S: Serializer {
+        let (op, from, value) = match self {
+            PatchOp::Add { value, .. } => ("add", None, Some(value)),
+            PatchOp::Remove { .. } => ("remove", None, None),
+            PatchOp::Replace { value, .. } => ("replace", None, Some(value)),
+            PatchOp::Move { from, .. } => ("move", Some(from), None),
+            PatchOp::Copy { from, .. } => ("copy", Some(from), None),
+            PatchOp::Test { value, .. } => ("test", None, Some(value)),
+        };
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("op", op)?;
+        if let Some(from) = from {
+            map.serialize_entry("from", from)?;
+        }
+        map.serialize_entry("path", self.path())?;
+        if let Some(value) = value {
+            map.serialize_entry("value", value)?;
+        }
+        map.end()
+    }
+}
+
+impl Serialize for ValueRef<'_, '_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where S: Serializer {
+        match self {
+            ValueRef::Borrowed(value) => value.serialize(serializer),
+            ValueRef::Array(arr) => serializer.collect_seq(arr),
+            ValueRef::Object(entries) => {
+                serializer.collect_map(entries.iter().map(|(key, value)| (key.as_ref(), value)))
+            }
+        }
+    }
+}
+
+impl Serialize for CachedValue<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where S: Serializer {
+        self.as_value_ref().serialize(serializer)
+    }
+}
+
+/// Serializes `value` as a JSON string.
+///
+/// The JSON is written directly, without going through the `Serialize` impl. Non-finite float
+/// numbers are written as `null`, like in `serde_json`. Nesting is tracked on the heap, so
+/// writing deeply nested values can't overflow the stack, unlike with the recursive `Serialize`
+/// impl. Dropping such values still recurses, see [`Value`](crate::Value#nesting).
+///
+/// # Example
+/// ```
+/// use serde_json_borrow::Value;
+///
+/// let value: Value = serde_json::from_str(r#"{"age": 30, "name": "John"}"#).unwrap();
+/// assert_eq!(serde_json_borrow::to_string(&value), r#"{"age":30,"name":"John"}"#);
+/// ```
+pub fn to_string(value: &Value) -> String {
+    let mut out = String::new();
+    write_value(value, &mut out).expect("writing into a String can't fail");
+    out
+}
+
+/// Serializes `value` as JSON into a `Vec<u8>`, see [`to_string`].
+pub fn to_vec(value: &Value) -> Vec<u8> {
+    to_string(value).into_bytes()
+}
+
+/// Serializes `value` as JSON into `writer`, see [`to_string`].
+///
+/// The JSON is written in many small pieces, so `writer` should be buffered, e.g. by a
+/// `BufWriter`.
+pub fn to_writer<W: io::Write>(writer: W, value: &Value) -> io::Result<()> {
+    write_io(writer, |out| write_value(value, out))
+}
+
+/// Options for the JSON serialization, see [`SerializeOptions::to_string`].
+///
+/// # Example
+/// ```
+/// use serde_json_borrow::{SerializeOptions, Value};
+///
+/// let value: Value = serde_json::from_str(r#"{"price": 2.0, "ratio": 0.5}"#).unwrap();
+/// let options = SerializeOptions {
+///     float_trailing_zero: false,
+///     ..SerializeOptions::default()
+/// };
+/// assert_eq!(options.to_string(&value), r#"{"price":2,"ratio":0.5}"#);
+/// assert_eq!(value.to_string(), r#"{"price":2.0,"ratio":0.5}"#);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SerializeOptions<'a> {
+    /// With `indent`, every array element and object member is written on its own line,
+    /// indented by `indent` per nesting level. Default: `None`, compact JSON.
+    pub indent: Option<&'a str>,
+    /// Writes non-finite numbers as the non-standard tokens `NaN`, `Infinity` and `-Infinity`
+    /// instead of `null`. Default: `false`.
+    pub non_finite_tokens: bool,
+    /// Writes floats without fractional part with a trailing `.0`, e.g. `2.0`, like
+    /// `serde_json`. Otherwise they are written like integers, e.g. `2`. Default: `true`.
+    pub float_trailing_zero: bool,
+}
+
+impl Default for SerializeOptions<'_> {
+    fn default() -> Self {
+        Self {
+            indent: None,
+            non_finite_tokens: false,
+            float_trailing_zero: true,
+        }
+    }
+}
+
+impl SerializeOptions<'_> {
+    /// Serializes `value` as a JSON string with the options.
+    pub fn to_string(&self, value: &Value) -> String {
+        let mut out = String::new();
+        write_json(value, &mut out, self).expect("writing into a String can't fail");
+        out
+    }
+
+    /// Serializes `value` as JSON into `writer` with the options, see [`to_writer`].
+    pub fn to_writer<W: io::Write>(&self, writer: W, value: &Value) -> io::Result<()> {
+        write_io(writer, |out| write_json(value, out, self))
+    }
+}
+
+/// Writes `value` as compact JSON.
+pub(crate) fn write_value<W: fmt::Write>(value: &Value, out: &mut W) -> fmt::Result {
+    write_json(value, out, &SerializeOptions::default())
+}
+
+/// Writes `value` as JSON with the given options.
+///
+/// Nested arrays and objects are tracked on the heap instead of by recursion, so writing deeply
+/// nested values can't overflow the stack.
+pub(crate) fn write_json<W: fmt::Write>(
+    value: &Value,
+    out: &mut W,
+    options: &SerializeOptions,
+) -> fmt::Result {
+    /// An array or object, whose elements are being written.
+    enum Frame<'a, 'ctx> {
+        Array(slice::Iter<'a, Value<'ctx>>),
+        Object(slice::Iter<'a, (KeyStrType<'ctx>, Value<'ctx>)>),
+    }
+
+    let indent = options.indent;
+    let mut stack: Vec<Frame> = Vec::new();
+    let mut next = Some(value);
+    loop {
+        if let Some(value) = next.take() {
+            match value {
+                Value::Null => out.write_str("null")?,
+                Value::Bool(true) => out.write_str("true")?,
+                Value::Bool(false) => out.write_str("false")?,
+                Value::Number(n) => write_number(n, out, options)?,
+                Value::Str(text) => write_escaped_str(text, out)?,
+                #[cfg(feature = "bytes")]
+                Value::Bytes(bytes) if bytes.is_empty() => out.write_str("[]")?,
+                #[cfg(feature = "bytes")]
+                Value::Bytes(bytes) => {
+                    let level = stack.len() + 1;
+                    out.write_char('[')?;
+                    write_newline(out, indent, level)?;
+                    for (idx, byte) in bytes.iter().enumerate() {
+                        if idx != 0 {
+                            write_separator(out, indent, level)?;
+                        }
+                        out.write_str(itoa::Buffer::new().format(*byte))?;
+                    }
+                    write_newline(out, indent, level - 1)?;
+                    out.write_char(']')?;
+                }
+                Value::Array(arr) if arr.is_empty() => out.write_str("[]")?,
+                Value::Array(arr) => {
+                    out.write_char('[')?;
+                    write_newline(out, indent, stack.len() + 1)?;
+                    next = arr.first();
+                    stack.push(Frame::Array(arr[1..].iter()));
+                    continue;
+                }
+                Value::Object(obj) if obj.is_empty() => out.write_str("{}")?,
+                Value::Object(obj) => {
+                    out.write_char('{')?;
+                    write_newline(out, indent, stack.len() + 1)?;
+                    let (key, value) = &obj.0[0];
+                    write_key(key_str(key), out, indent)?;
+                    next = Some(value);
+                    stack.push(Frame::Object(obj.0[1..].iter()));
+                    continue;
+                }
+            }
+        }
+        let level = stack.len();
+        match stack.last_mut() {
+            None => return Ok(()),
+            Some(Frame::Array(iter)) => {
+                if let Some(value) = iter.next() {
+                    write_separator(out, indent, level)?;
+                    next = Some(value);
+                } else {
+                    write_newline(out, indent, level - 1)?;
+                    out.write_char(']')?;
+                    stack.pop();
+                }
+            }
+            Some(Frame::Object(iter)) => {
+                if let Some((key, value)) = iter.next() {
+                    write_separator(out, indent, level)?;
+                    write_key(key_str(key), out, indent)?;
+                    next = Some(value);
+                } else {
+                    write_newline(out, indent, level - 1)?;
+                    out.write_char('}')?;
+                    stack.pop();
+                }
+            }
+        }
+    }
+}
+
+/// Writes the separator before an array element or object member, except the first one.
+fn write_separator<W: fmt::Write>(out: &mut W, indent: Option<&str>, level: usize) -> fmt::Result {
+    out.write_char(',')?;
+    write_newline(out, indent, level)
+}
+
+fn write_key<W: fmt::Write>(key: &str, out: &mut W, indent: Option<&str>) -> fmt::Result {
+    write_escaped_str(key, out)?;
+    out.write_str(if indent.is_some() { ": " } else { ":" })
+}
+
+fn write_newline<W: fmt::Write>(out: &mut W, indent: Option<&str>, level: usize) -> fmt::Result {
+    if let Some(indent) = indent {
+        out.write_char('\n')?;
+        for _ in 0..level {
+            out.write_str(indent)?;
+        }
+    }
+    Ok(())
+}
+
+fn write_number<W: fmt::Write>(
+    number: &Number,
+    out: &mut W,
+    options: &SerializeOptions,
+) -> fmt::Result {
+    match number.n {
+        N::PosInt(n) => out.write_str(itoa::Buffer::new().format(n)),
+        N::NegInt(n) => out.write_str(itoa::Buffer::new().format(n)),
+        N::Float(n) if n.is_finite() => {
+            let mut buffer = ryu::Buffer::new();
+            let text = buffer.format_finite(n);
+            if options.float_trailing_zero {
+                out.write_str(text)
+            } else {
+                out.write_str(text.strip_suffix(".0").unwrap_or(text))
+            }
+        }
+        N::Float(_) if !options.non_finite_tokens => out.write_str("null"),
+        N::Float(n) if n.is_nan() => out.write_str("NaN"),
+        N::Float(n) if n > 0.0 => out.write_str("Infinity"),
+        N::Float(_) => out.write_str("-Infinity"),
+    }
+}
+
+/// Writes the string quoted, `"`, `\` and control characters are escaped.
+pub(crate) fn write_escaped_str<W: fmt::Write>(text: &str, out: &mut W) -> fmt::Result {
+    out.write_char('"')?;
+    let mut start = 0;
+    for (idx, byte) in text.bytes().enumerate() {
+        let escape = match byte {
+            b'"' => "\\\"",
+            b'\\' => "\\\\",
+            b'\x08' => "\\b",
+            b'\x0c' => "\\f",
+            b'\n' => "\\n",
+            b'\r' => "\\r",
+            b'\t' => "\\t",
+            0..=0x1f => "",
+            _ => continue,
+        };
+        // `idx` is at an ASCII byte, so it's a char boundary.
+        out.write_str(&text[start..idx])?;
+        if escape.is_empty() {
+            write!(out, "\\u{byte:04x}")?;
+        } else {
+            out.write_str(escape)?;
+        }
+        start = idx + 1;
+    }
+    out.write_str(&text[start..])?;
+    out.write_char('"')
+}
+
+/// Calls `write` with a `fmt::Write` writing into `writer`, and returns the `io::Error` of
+/// `writer`, which `fmt::Error` can't carry.
+pub(crate) fn write_io<W: io::Write>(
+    writer: W,
+    write: impl FnOnce(&mut IoAdapter<W>) -> fmt::Result,
+) -> io::Result<()> {
+    let mut adapter = IoAdapter {
+        writer,
+        error: None,
+    };
+    write(&mut adapter).map_err(|_| {
+        adapter
+            .error
+            .take()
+            .unwrap_or_else(|| io::Error::other("formatter error"))
+    })
+}
+
+/// Passes formatted text to an `io::Write` and keeps the error, which `fmt::Error` can't carry.
+pub(crate) struct IoAdapter<W> {
+    writer: W,
+    error: Option<io::Error>,
+}
+
+impl<W: io::Write> fmt::Write for IoAdapter<W> {
+    #[inline]
+    fn write_str(&mut self, text: &str) -> fmt::Result {
+        self.writer.write_all(text.as_bytes()).map_err(|err| {
+            self.error = Some(err);
+            fmt::Error
+        })
+    }
+}
+
+/// Serializes `value` as an indented JSON string, with 2 spaces per level.
+///
+/// Use `format!("{value:#4}")` for a different indentation, see [`Value`]'s `Display` impl.
+///
+/// # Example
+/// ```
+/// use serde_json_borrow::{to_string_pretty, Value};
+///
+/// let value: Value = serde_json::from_str(r#"{"name": "John", "tags": [1]}"#).unwrap();
+/// assert_eq!(
+///     to_string_pretty(&value),
+///     "{\n  \"name\": \"John\",\n  \"tags\": [\n    1\n  ]\n}"
+/// );
+/// ```
+pub fn to_string_pretty(value: &Value) -> String {
+    SerializeOptions {
+        indent: Some("  "),
+        ..SerializeOptions::default()
+    }
+    .to_string(value)
+}
+
+/// Serializes `value` as a JSON string like [`to_string`], but writes non-finite numbers as the
+/// non-standard tokens `NaN`, `Infinity` and `-Infinity` instead of `null`.
+///
+/// The output is not valid JSON if it contains non-finite numbers, but it can be read by e.g.
+/// Python's `json` module or [`Value::from_slice_in_place_non_finite`].
+///
+/// # Example
+/// ```
+/// use serde_json_borrow::{to_string, to_string_non_finite, Value};
+///
+/// let value = Value::Array(vec![f64::NAN.into(), f64::NEG_INFINITY.into(), 1.5.into()]);
+/// assert_eq!(to_string_non_finite(&value), "[NaN,-Infinity,1.5]");
+/// assert_eq!(to_string(&value), "[null,null,1.5]");
+/// ```
+pub fn to_string_non_finite(value: &Value) -> String {
+    SerializeOptions {
+        non_finite_tokens: true,
+        ..SerializeOptions::default()
+    }
+    .to_string(value)
+}
+
+/// Serializes `value` as a JSON string, but stops as soon as the output would exceed `max_bytes`.
+///
+/// This avoids serializing large, user-controlled documents completely, when they would be
+/// rejected anyway.
+///
+/// # Example
+/// ```
+/// use serde_json_borrow::{to_string_bounded, Value};
+///
+/// let value: Value = serde_json::from_str(r#"{"name": "John"}"#).unwrap();
+/// assert_eq!(to_string_bounded(&value, 64).unwrap(), r#"{"name":"John"}"#);
+/// assert!(to_string_bounded(&value, 8).is_err());
+/// ```
+pub fn to_string_bounded(value: &Value, max_bytes: usize) -> Result<String, SizeLimitExceeded> {
+    let mut writer = BoundedWriter {
+        buf: Vec::new(),
+        max_bytes,
+    };
+    // Writing into memory can't fail, so any error is caused by exceeding the limit.
+    to_writer(&mut writer, value).map_err(|_| SizeLimitExceeded { limit: max_bytes })?;
+    Ok(String::from_utf8(writer.buf).expect("serialized JSON is valid UTF-8"))
+}
+
+/// Error returned by [`to_string_bounded`] if the serialized JSON would exceed the size limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SizeLimitExceeded {
+    /// The size limit in bytes.
+    pub limit: usize,
+}
+
+impl fmt::Display for SizeLimitExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "serialized JSON exceeds the limit of {} bytes",
+            self.limit
+        )
+    }
+}
+
+impl std::error::Error for SizeLimitExceeded {}
+
+/// Writes into a `Vec`, but fails if more than `max_bytes` would be written.
+struct BoundedWriter {
+    buf: Vec<u8>,
+    max_bytes: usize,
+}
+
+impl io::Write for BoundedWriter {
+    #[inline]
+    fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+        if self.buf.len() + data.len() > self.max_bytes {
+            return Err(io::Error::new(
+                io::ErrorKind::WriteZero,
+                "size limit exceeded",
+            ));
+        }
+        self.buf.extend_from_slice(data);
+        Ok(data.len())
+    }
+
+    #[inline]
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use super::*;
+    use crate::Value;
 
     #[test]
     fn serialize_json_test() {
@@ -52,6 +505,130 @@ mod tests {
 
         let val1: crate::Value = serde_json::from_str(json_obj).unwrap();
         let deser1: String = serde_json::to_string(&val1).unwrap();
+        #[cfg(not(feature = "btree"))]
         assert_eq!(deser1, json_obj);
+        #[cfg(feature = "btree")]
+        assert_eq!(
+            deser1,
+            r#"{"bool":true,"float":1.23,"i64":-123,"string_key":"string_val","u64":123}"#
+        );
+    }
+
+    #[test]
+    fn native_serializer_matches_serde_json() {
+        let jsons = [
+            r#"{"bool":true,"float":1.23,"i64":-123,"string_key":"string_val","u64":123}"#,
+            r#"[null,false,0,-0.0,1.0,-1.5e-300,18446744073709551615,-9223372036854775808]"#,
+            r#"["", "é\u0000\u001f\u007f\"\\\/\b\f\n\r\t", {"a\nb": {}}, [[]]]"#,
+            r#""😀""#,
+        ];
+        for json in jsons {
+            let value: serde_json::Value = serde_json::from_str(json).unwrap();
+            let val = Value::from(&value);
+            let expected = serde_json::to_string(&value).unwrap();
+            assert_eq!(to_string(&val), expected);
+            assert_eq!(to_vec(&val), expected.as_bytes());
+            assert_eq!(val.to_string(), expected);
+            let mut out = Vec::new();
+            to_writer(&mut out, &val).unwrap();
+            assert_eq!(out, expected.as_bytes());
+        }
+        // ryu doesn't write a `+` for positive exponents
+        let val = Value::from(vec![1e300, f64::NAN, f64::INFINITY]);
+        assert_eq!(to_string(&val), "[1e300,null,null]");
+    }
+
+    #[test]
+    fn serialize_deeply_nested() {
+        let depth = 100_000;
+        let mut value = Value::from("x");
+        for idx in 0..depth {
+            value = if idx % 2 == 0 {
+                Value::Array(vec![value])
+            } else {
+                Value::Object(vec![("k", value)].into())
+            };
+        }
+        let json = to_string(&value);
+        assert_eq!(
+            json.len(),
+            3 + depth / 2 * "[]".len() + depth / 2 * r#"{"k":}"#.len()
+        );
+        assert!(json.starts_with(r#"{"k":[{"k":["#));
+        assert!(json.contains(r#"{"k":["x"]}]}"#));
+        assert!(json.ends_with("]}]}"));
+        assert_eq!(value.to_string(), json);
+
+        // Dropping is recursive, so take the value apart iteratively.
+        loop {
+            value = match value {
+                Value::Array(mut arr) => arr.pop().unwrap(),
+                Value::Object(mut obj) => obj.0.pop().unwrap().1,
+                _ => break,
+            };
+        }
+    }
+
+    #[test]
+    fn to_writer_error() {
+        let val = Value::from("a long string");
+        let mut buf = [0u8; 4];
+        let err = to_writer(&mut buf[..], &val).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::WriteZero);
+    }
+
+    #[test]
+    fn to_string_pretty_test() {
+        let val: crate::Value =
+            serde_json::from_str(r#"{"a": [1, {"b": null}], "c": {}, "d": []}"#).unwrap();
+        let expected = "{\n  \"a\": [\n    1,\n    {\n      \"b\": null\n    }\n  ],\n  \"c\": \
+                        {},\n  \"d\": []\n}";
+        assert_eq!(to_string_pretty(&val), expected);
+        assert_eq!(format!("{val:#}"), expected);
+        assert_eq!(format!("{val:#4}"), expected.replace("  ", "    "));
+        assert_eq!(format!("{val:#1}"), expected.replace("  ", " "));
+        assert_eq!(format!("{val}"), r#"{"a":[1,{"b":null}],"c":{},"d":[]}"#);
+        assert_eq!(to_string_pretty(&crate::Value::from("x")), r#""x""#);
+    }
+
+    #[test]
+    fn float_trailing_zero_test() {
+        let val: crate::Value =
+            serde_json::from_str("[2.0, -0.0, 1.5, 1e20, 1e-7, 3, 18446744073709551616]").unwrap();
+        let options = SerializeOptions {
+            float_trailing_zero: false,
+            ..SerializeOptions::default()
+        };
+        assert_eq!(
+            options.to_string(&val),
+            "[2,-0,1.5,1e20,1e-7,3,1.8446744073709552e19]"
+        );
+        assert_eq!(
+            to_string(&val),
+            "[2.0,-0.0,1.5,1e20,1e-7,3,1.8446744073709552e19]"
+        );
+        let mut out = Vec::new();
+        options.to_writer(&mut out, &val).unwrap();
+        assert_eq!(out, options.to_string(&val).into_bytes());
+    }
+
+    #[test]
+    fn to_string_bounded_test() {
+        let json_obj = r#"{"bool":true,"array":[1,2,3],"nested":{"key":"val"}}"#;
+        let val: crate::Value = serde_json::from_str(json_obj).unwrap();
+
+        #[cfg(not(feature = "btree"))]
+        let expected = json_obj;
+        #[cfg(feature = "btree")]
+        let expected = r#"{"array":[1,2,3],"bool":true,"nested":{"key":"val"}}"#;
+        assert_eq!(to_string_bounded(&val, json_obj.len()).unwrap(), expected);
+        assert_eq!(
+            to_string_bounded(&val, json_obj.len() - 1),
+            Err(SizeLimitExceeded {
+                limit: json_obj.len() - 1
+            })
+        );
+        assert!(to_string_bounded(&val, 0).is_err());
+        assert_eq!(to_string_bounded(&crate::Value::Null, 4).unwrap(), "null");
     }
 }
@@ -0,0 +1,113 @@
+use crate::object_vec::key_str;
+use crate::value::type_name;
+use crate::Value;
+
+impl Value<'_> {
+    /// Returns a structural signature of the value, which contains the keys and types, but not
+    /// the values. Documents with the same shape have the same signature, which makes it useful
+    /// to group documents by shape.
+    ///
+    /// - Scalars are represented by their type: `null`, `boolean`, `number` or `string`.
+    /// - Objects are represented by their keys sorted and the signatures of their values, e.g.
+    ///   `{"a":number,"b":string}`. Keys are quoted, `"` and `\` in keys are escaped with `\`.
+    /// - Arrays are represented by the sorted and deduplicated signatures of their elements
+    ///   separated by `|`, e.g. `[number|string]`. So arrays of different length, but with the same
+    ///   element types, have the same signature.
+    ///
+    /// # Example
+    /// ```
+    /// use serde_json_borrow::Value;
+    ///
+    /// let a: Value = serde_json::from_str(r#"{"id": 1, "tags": ["x", "y"], "user": {"name": "a"}}"#).unwrap();
+    /// let b: Value = serde_json::from_str(r#"{"user": {"name": "b"}, "tags": [], "id": 2}"#).unwrap();
+    /// let c: Value = serde_json::from_str(r#"{"user": {"name": "b"}, "tags": ["z"], "id": 2}"#).unwrap();
+    ///
+    /// assert_eq!(a.signature(), r#"{"id":number,"tags":[string],"user":{"name":string}}"#);
+    /// assert_eq!(a.signature(), c.signature());
+    /// assert_ne!(a.signature(), b.signature());
+    /// ```
+    pub fn signature(&self) -> String {
+        let mut out = String::new();
+        write_signature(self, &mut out);
+        out
+    }
+}
+
+fn write_signature(value: &Value, out: &mut String) {
+    match value {
+        Value::Object(obj) => {
+            let mut entries: Vec<_> = obj.0.iter().collect();
+            entries.sort_unstable_by(|a, b| key_str(&a.0).cmp(key_str(&b.0)));
+            out.push('{');
+            for (idx, (key, value)) in entries.into_iter().enumerate() {
+                if idx != 0 {
+                    out.push(',');
+                }
+                out.push('"');
+                for c in key_str(key).chars() {
+                    if c == '"' || c == '\\' {
+                        out.push('\\');
+                    }
+                    out.push(c);
+                }
+                out.push_str("\":");
+                write_signature(value, out);
+            }
+            out.push('}');
+        }
+        Value::Array(arr) => {
+            let mut signatures: Vec<String> = arr.iter().map(Value::signature).collect();
+            signatures.sort_unstable();
+            signatures.dedup();
+            out.push('[');
+            out.push_str(&signatures.join("|"));
+            out.push(']');
+        }
+        _ => out.push_str(type_name(value)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Value;
+
+    fn signature(json: &str) -> String {
+        serde_json::from_str::<Value>(json).unwrap().signature()
+    }
+
+    #[test]
+    fn signature_scalars() {
+        assert_eq!(signature("null"), "null");
+        assert_eq!(signature("true"), "boolean");
+        assert_eq!(signature("1.5"), "number");
+        assert_eq!(signature(r#""a""#), "string");
+    }
+
+    #[test]
+    fn signature_nested() {
+        assert_eq!(signature("[]"), "[]");
+        assert_eq!(signature("{}"), "{}");
+        assert_eq!(
+            signature(r#"[1, "a", 2, {"b": null}, {"b": null}]"#),
+            r#"[number|string|{"b":null}]"#
+        );
+        assert_eq!(
+            signature(r#"{"z": [[]], "a": {"y": false, "x": 1}}"#),
+            r#"{"a":{"x":number,"y":boolean},"z":[[]]}"#
+        );
+        assert_eq!(
+            signature(r#"{"a": 1, "b": "x"}"#),
+            signature(r#"{"b": "y", "a": 2}"#)
+        );
+        assert_ne!(signature(r#"{"a": 1}"#), signature(r#"{"a": "1"}"#));
+    }
+
+    #[cfg(feature = "cowkeys")]
+    #[test]
+    fn signature_escaped_keys() {
+        assert_eq!(
+            signature(r#"{"a\"b": 1, "c\\": 2}"#),
+            r#"{"a\"b":number,"c\\":number}"#
+        );
+    }
+}
@@ -0,0 +1,155 @@
+use simd_json::StaticNode;
+
+use crate::{Number, Value};
+
+// Conversions between `Value` and the values of simd-json.
+//
+// Objects of simd-json are hash maps, so key order isn't preserved and only the last of
+// duplicate keys is kept when converting to simd-json. Conversions from simd-json require the
+// `cowkeys` feature flag, since keys may be owned.
+
+impl<'a> From<Value<'a>> for simd_json::BorrowedValue<'a> {
+    #[allow(clippy::useless_conversion)]
+    fn from(val: Value<'a>) -> Self {
+        use simd_json::BorrowedValue;
+        match val {
+            Value::Null => BorrowedValue::Static(StaticNode::Null),
+            Value::Bool(val) => BorrowedValue::Static(StaticNode::Bool(val)),
+            Value::Number(val) => BorrowedValue::Static(static_from_number(val)),
+            Value::Str(val) => BorrowedValue::String(val),
+            Value::Array(vals) => {
+                BorrowedValue::Array(vals.into_iter().map(|val| val.into()).collect())
+            }
+            Value::Object(vals) => BorrowedValue::Object(Box::new(
+                vals.0
+                    .into_iter()
+                    .map(|(key, val)| (key.into(), val.into()))
+                    .collect(),
+            )),
+            #[cfg(feature = "bytes")]
+            Value::Bytes(bytes) => bytes
+                .iter()
+                .map(|byte| BorrowedValue::Static(StaticNode::U64(u64::from(*byte))))
+                .collect(),
+        }
+    }
+}
+
+impl From<Value<'_>> for simd_json::OwnedValue {
+    fn from(val: Value) -> Self {
+        use simd_json::OwnedValue;
+        match val {
+            Value::Null => OwnedValue::Static(StaticNode::Null),
+            Value::Bool(val) => OwnedValue::Static(StaticNode::Bool(val)),
+            Value::Number(val) => OwnedValue::Static(static_from_number(val)),
+            Value::Str(val) => OwnedValue::String(val.into_owned()),
+            Value::Array(vals) => {
+                OwnedValue::Array(vals.into_iter().map(|val| val.into()).collect())
+            }
+            Value::Object(vals) => OwnedValue::Object(Box::new(
+                vals.into_vec()
+                    .into_iter()
+                    .map(|(key, val)| (key.into_owned(), val.into()))
+                    .collect(),
+            )),
+            #[cfg(feature = "bytes")]
+            Value::Bytes(bytes) => bytes
+                .iter()
+                .map(|byte| OwnedValue::Static(StaticNode::U64(u64::from(*byte))))
+                .collect(),
+        }
+    }
+}
+
+#[cfg(feature = "cowkeys")]
+impl<'a> From<simd_json::BorrowedValue<'a>> for Value<'a> {
+    fn from(val: simd_json::BorrowedValue<'a>) -> Self {
+        use simd_json::BorrowedValue;
+        match val {
+            BorrowedValue::Static(node) => node.into(),
+            BorrowedValue::String(val) => Value::Str(val),
+            BorrowedValue::Array(vals) => {
+                Value::Array(vals.into_iter().map(|val| val.into()).collect())
+            }
+            BorrowedValue::Object(vals) => Value::Object(crate::ObjectAsVec::from_entries(
+                vals.into_iter()
+                    .map(|(key, val)| (key, val.into()))
+                    .collect(),
+            )),
+        }
+    }
+}
+
+#[cfg(feature = "cowkeys")]
+impl From<simd_json::OwnedValue> for Value<'static> {
+    fn from(val: simd_json::OwnedValue) -> Self {
+        use simd_json::OwnedValue;
+        match val {
+            OwnedValue::Static(node) => node.into(),
+            OwnedValue::String(val) => Value::Str(val.into()),
+            OwnedValue::Array(vals) => {
+                Value::Array(vals.into_iter().map(|val| val.into()).collect())
+            }
+            OwnedValue::Object(vals) => Value::Object(crate::ObjectAsVec::from_entries(
+                vals.into_iter()
+                    .map(|(key, val)| (key.into(), val.into()))
+                    .collect(),
+            )),
+        }
+    }
+}
+
+fn static_from_number(val: Number) -> StaticNode {
+    if let Some(val) = val.as_u64() {
+        StaticNode::U64(val)
+    } else if let Some(val) = val.as_i64() {
+        StaticNode::I64(val)
+    } else {
+        StaticNode::F64(val.as_f64().unwrap_or_default())
+    }
+}
+
+impl From<StaticNode> for Value<'_> {
+    fn from(node: StaticNode) -> Self {
+        match node {
+            StaticNode::Null => Value::Null,
+            StaticNode::Bool(val) => Value::Bool(val),
+            StaticNode::I64(val) => Value::Number(val.into()),
+            StaticNode::U64(val) => Value::Number(val.into()),
+            StaticNode::F64(val) => Value::Number(val.into()),
+            // The 128 bit integers of the `128bit` feature flag of simd-json don't fit into
+            // `Number`.
+            #[allow(unreachable_patterns)]
+            node => {
+                use simd_json::prelude::ValueAsScalar;
+                Value::Number(node.cast_f64().unwrap_or_default().into())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn simd_convert_test() {
+        let json = r#"{"a": [1, -2, 1.5, 18446744073709551615], "b": "x\ny", "c": {"d": null}}"#;
+        let value: Value = serde_json::from_str(json).unwrap();
+
+        let borrowed: simd_json::BorrowedValue = value.clone().into();
+        let owned: simd_json::OwnedValue = value.clone().into();
+        let mut data = json.as_bytes().to_vec();
+        assert_eq!(borrowed, simd_json::to_borrowed_value(&mut data).unwrap());
+        let mut data = json.as_bytes().to_vec();
+        assert_eq!(owned, simd_json::to_owned_value(&mut data).unwrap());
+
+        #[cfg(feature = "cowkeys")]
+        {
+            // Objects of simd-json have no key order.
+            let to_serde = |value: Value| serde_json::Value::from(value);
+            assert_eq!(to_serde(Value::from(borrowed)), to_serde(value.clone()));
+            assert_eq!(to_serde(Value::from(owned)), to_serde(value));
+        }
+    }
+}
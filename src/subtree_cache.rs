@@ -0,0 +1,379 @@
+use core::fmt;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::io;
+use std::sync::Arc;
+
+use serde::de::{Deserialize, DeserializeSeed, Deserializer, Error, MapAccess, SeqAccess, Visitor};
+
+use crate::object_vec::KeyStrType;
+use crate::{RawValue, Value, ValueRef};
+
+/// A cache of parsed arrays and objects, keyed by their JSON text, to avoid parsing identical
+/// fragments of similar documents again.
+///
+/// Batched documents from the same producer often repeat the same blobs, e.g. a config object in
+/// every record. [`SubtreeCache::parse`] recognizes arrays and objects at the nesting depth of
+/// [`SubtreeCache::with_depth`], whose exact text was seen before, and returns the cached
+/// subtree as [`CachedValue::Shared`] instead of parsing it. The subtree is shared via `Arc`, so
+/// a hit neither parses nor copies it.
+///
+/// Only arrays and objects with a text of at least [`SubtreeCache::with_min_len`] bytes are
+/// cached. The cached values borrow from the input, so all parsed documents must outlive the
+/// cache, e.g. by being lines of the same buffer.
+///
+/// # Example
+/// ```
+/// use serde_json_borrow::{CachedValue, SubtreeCache};
+///
+/// let lines = r#"{"id": 1, "config": {"retries": 3, "endpoints": ["a", "b"]}}
+/// {"id": 2, "config": {"retries": 3, "endpoints": ["a", "b"]}}"#;
+///
+/// let mut cache = SubtreeCache::new().with_min_len(16);
+/// for line in lines.lines() {
+///     let value = cache.parse(line).unwrap();
+///     let config = value.get("config").and_then(CachedValue::as_value).unwrap();
+///     assert_eq!(config.get("retries"), &3_u64);
+/// }
+/// assert_eq!(cache.hits(), 1);
+/// ```
+#[derive(Debug, Clone)]
+pub struct SubtreeCache<'ctx> {
+    cache: HashMap<&'ctx str, Arc<Value<'ctx>>>,
+    depth: usize,
+    min_len: usize,
+    hits: usize,
+}
+
+/// A document parsed by [`SubtreeCache::parse`], whose cached subtrees are shared with other
+/// documents.
+///
+/// A `CachedValue` can be serialized via `Serialize` or `Display` like a [`ValueRef`], see
+/// [`CachedValue::as_value_ref`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum CachedValue<'ctx> {
+    /// A cached array or object, shared with all documents containing the same text.
+    Shared(Arc<Value<'ctx>>),
+    /// A value which is not cached, e.g. a string or a small object.
+    Value(Value<'ctx>),
+    /// An array above the cached depth.
+    Array(Vec<CachedValue<'ctx>>),
+    /// An object above the cached depth. The entries are in the order of [`crate::ObjectAsVec`],
+    /// i.e. sorted by key with the `btree` feature flag.
+    Object(Vec<(KeyStrType<'ctx>, CachedValue<'ctx>)>),
+}
+
+impl<'ctx> CachedValue<'ctx> {
+    /// Returns the value of the first entry with `key`, if this is an object above the cached
+    /// depth.
+    pub fn get(&self, key: &str) -> Option<&CachedValue<'ctx>> {
+        match self {
+            CachedValue::Object(entries) => entries
+                .iter()
+                .find(|(entry_key, _)| &**entry_key == key)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    /// Returns the element at `idx`, if this is an array above the cached depth.
+    pub fn get_idx(&self, idx: usize) -> Option<&CachedValue<'ctx>> {
+        match self {
+            CachedValue::Array(arr) => arr.get(idx),
+            _ => None,
+        }
+    }
+
+    /// Returns the value, if this is a cached or an uncached value.
+    pub fn as_value(&self) -> Option<&Value<'ctx>> {
+        match self {
+            CachedValue::Shared(value) => Some(value),
+            CachedValue::Value(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns a view of the whole document, without cloning any subtree.
+    pub fn as_value_ref(&self) -> ValueRef<'_, 'ctx> {
+        match self {
+            CachedValue::Shared(value) => ValueRef::Borrowed(value),
+            CachedValue::Value(value) => ValueRef::Borrowed(value),
+            CachedValue::Array(arr) => {
+                ValueRef::Array(arr.iter().map(CachedValue::as_value_ref).collect())
+            }
+            CachedValue::Object(entries) => ValueRef::Object(
+                entries
+                    .iter()
+                    .map(|(key, value)| (Cow::Borrowed(&**key), value.as_value_ref()))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+/// Formats the value as compact JSON.
+impl fmt::Display for CachedValue<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.as_value_ref(), f)
+    }
+}
+
+impl Default for SubtreeCache<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'ctx> SubtreeCache<'ctx> {
+    /// Creates an empty cache for values at depth 1 and with at least 64 bytes.
+    pub fn new() -> Self {
+        Self {
+            cache: HashMap::new(),
+            depth: 1,
+            min_len: 64,
+            hits: 0,
+        }
+    }
+
+    /// Sets the nesting depth of cached values. The document itself has depth 0, its members or
+    /// elements depth 1.
+    ///
+    /// The text of the values at this depth is scanned to look it up, and parsed on a miss. The
+    /// levels above are parsed as usual, so each byte is scanned at most twice.
+    pub fn with_depth(mut self, depth: usize) -> Self {
+        self.depth = depth;
+        self
+    }
+
+    /// Sets the minimum length of the text of cached values in bytes. Parsing smaller values is
+    /// cheaper than the lookup.
+    pub fn with_min_len(mut self, min_len: usize) -> Self {
+        self.min_len = min_len;
+        self
+    }
+
+    /// Parses `json`, reusing cached arrays and objects.
+    pub fn parse(&mut self, json: &'ctx str) -> io::Result<CachedValue<'ctx>> {
+        let mut deserializer = serde_json::Deserializer::from_str(json);
+        let value = CacheSeed {
+            cache: self,
+            depth: 0,
+        }
+        .deserialize(&mut deserializer)?;
+        deserializer.end()?;
+        Ok(value)
+    }
+
+    /// Returns the number of values, which were taken from the cache.
+    pub fn hits(&self) -> usize {
+        self.hits
+    }
+
+    /// Returns the number of cached values.
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    /// Returns true if no values are cached.
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+
+    /// Removes all cached values, e.g. to release memory between batches.
+    pub fn clear(&mut self) {
+        self.cache.clear();
+    }
+}
+
+/// Deserializes a value at `depth`, via the cache if it is at the cached depth.
+struct CacheSeed<'a, 'ctx> {
+    cache: &'a mut SubtreeCache<'ctx>,
+    depth: usize,
+}
+
+impl<'ctx> DeserializeSeed<'ctx> for CacheSeed<'_, 'ctx> {
+    type Value = CachedValue<'ctx>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where D: Deserializer<'ctx> {
+        if self.depth < self.cache.depth {
+            return deserializer.deserialize_any(ContainerVisitor {
+                cache: self.cache,
+                depth: self.depth,
+            });
+        }
+        let raw = RawValue::deserialize(deserializer)?.get();
+        if !raw.starts_with(['[', '{']) || raw.len() < self.cache.min_len {
+            return serde_json::from_str(raw)
+                .map(CachedValue::Value)
+                .map_err(D::Error::custom);
+        }
+        if let Some(value) = self.cache.cache.get(raw) {
+            self.cache.hits += 1;
+            return Ok(CachedValue::Shared(Arc::clone(value)));
+        }
+        let value = Arc::new(serde_json::from_str(raw).map_err(D::Error::custom)?);
+        self.cache.cache.insert(raw, Arc::clone(&value));
+        Ok(CachedValue::Shared(value))
+    }
+}
+
+/// Parses a value above the cached depth, the elements of arrays and objects are deserialized
+/// via the cache.
+struct ContainerVisitor<'a, 'ctx> {
+    cache: &'a mut SubtreeCache<'ctx>,
+    depth: usize,
+}
+
+impl<'ctx> ContainerVisitor<'_, 'ctx> {
+    fn scalar<E: Error>(value: impl Into<Value<'ctx>>) -> Result<CachedValue<'ctx>, E> {
+        Ok(CachedValue::Value(value.into()))
+    }
+}
+
+impl<'ctx> Visitor<'ctx> for ContainerVisitor<'_, 'ctx> {
+    type Value = CachedValue<'ctx>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("any valid JSON value")
+    }
+
+    fn visit_unit<E: Error>(self) -> Result<Self::Value, E> {
+        Ok(CachedValue::Value(Value::Null))
+    }
+
+    fn visit_bool<E: Error>(self, value: bool) -> Result<Self::Value, E> {
+        Self::scalar(value)
+    }
+
+    fn visit_i64<E: Error>(self, value: i64) -> Result<Self::Value, E> {
+        Self::scalar(value)
+    }
+
+    fn visit_u64<E: Error>(self, value: u64) -> Result<Self::Value, E> {
+        Self::scalar(value)
+    }
+
+    fn visit_f64<E: Error>(self, value: f64) -> Result<Self::Value, E> {
+        Self::scalar(value)
+    }
+
+    fn visit_borrowed_str<E: Error>(self, value: &'ctx str) -> Result<Self::Value, E> {
+        Self::scalar(value)
+    }
+
+    fn visit_str<E: Error>(self, value: &str) -> Result<Self::Value, E> {
+        Self::scalar(value.to_owned())
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where A: SeqAccess<'ctx> {
+        let mut arr = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(elem) = seq.next_element_seed(CacheSeed {
+            cache: &mut *self.cache,
+            depth: self.depth + 1,
+        })? {
+            arr.push(elem);
+        }
+        Ok(CachedValue::Array(arr))
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where A: MapAccess<'ctx> {
+        let mut values = Vec::with_capacity(map.size_hint().unwrap_or(0));
+        while let Some(key) = map.next_key::<KeyStrType>()? {
+            let value = map.next_value_seed(CacheSeed {
+                cache: &mut *self.cache,
+                depth: self.depth + 1,
+            })?;
+            values.push((key, value));
+        }
+        #[cfg(feature = "btree")]
+        values
+            .sort_by(|a, b| crate::object_vec::key_str(&a.0).cmp(crate::object_vec::key_str(&b.0)));
+        Ok(CachedValue::Object(values))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn subtree_cache_test() {
+        let lines = [
+            r#"{"a": {"b": [1, 2, 3]}, "c": [{"d": "e"}], "s": "x"}"#,
+            r#"{"c": [{"d": "e"}], "a": {"b": [1, 2, 3]}, "s": "y"}"#,
+            r#"[{"b": [1, 2, 3]}, {"b": [1, 2, 3]}, "z", null, 1.5, -1, true]"#,
+        ];
+        let mut cache = SubtreeCache::new().with_min_len(0);
+        for line in lines {
+            let value = cache.parse(line).unwrap();
+            assert_eq!(
+                value.as_value_ref().to_value(),
+                serde_json::from_str::<Value>(line).unwrap()
+            );
+            assert_eq!(
+                value.to_string(),
+                crate::to_string(&serde_json::from_str(line).unwrap())
+            );
+            assert_eq!(serde_json::to_string(&value).unwrap(), value.to_string());
+        }
+        // `{"b": [1, 2, 3]}` and `[{"d": "e"}]` in the second line, and `{"b": [1, 2, 3]}` twice
+        // in the third line.
+        assert_eq!(cache.hits(), 4);
+        // The two distinct arrays and objects at depth 1.
+        assert_eq!(cache.len(), 2);
+
+        let mut cache = SubtreeCache::new();
+        for line in lines {
+            cache.parse(line).unwrap();
+        }
+        assert_eq!(cache.hits(), 0);
+        assert!(cache.is_empty());
+
+        assert!(cache.parse(r#"{"a": [}"#).is_err());
+        assert!(cache.parse("1 2").is_err());
+        assert_eq!(
+            cache.parse("\"s\"").unwrap(),
+            CachedValue::Value("s".into())
+        );
+        cache.clear();
+    }
+
+    #[test]
+    fn subtree_cache_hit_is_shared() {
+        let lines = [
+            r#"{"id": 1, "config": {"retries": 3}}"#,
+            r#"{"id": 2, "config": {"retries": 3}}"#,
+        ];
+        let mut cache = SubtreeCache::new().with_min_len(0);
+        let first = cache.parse(lines[0]).unwrap();
+        let second = cache.parse(lines[1]).unwrap();
+        assert_eq!(cache.hits(), 1);
+
+        // The hit is the same allocation, so it was neither parsed nor copied again.
+        let (Some(CachedValue::Shared(a)), Some(CachedValue::Shared(b))) =
+            (first.get("config"), second.get("config"))
+        else {
+            panic!("expected shared subtrees");
+        };
+        assert!(Arc::ptr_eq(a, b));
+        // Referenced by the cache and both documents.
+        assert_eq!(Arc::strong_count(a), 3);
+        assert_eq!(second.get("id"), Some(&CachedValue::Value(2_u64.into())));
+        assert_eq!(a.get("retries"), &3_u64);
+
+        // The whole document is cached at depth 0.
+        let mut cache = SubtreeCache::new().with_depth(0).with_min_len(0);
+        cache.parse(lines[0]).unwrap();
+        let doc = cache.parse(lines[0]).unwrap();
+        assert_eq!(cache.hits(), 1);
+        assert_eq!(doc.as_value().unwrap().get("id"), &1_u64);
+        let arr = SubtreeCache::new().with_depth(2).parse("[[1]]").unwrap();
+        assert_eq!(
+            arr.get_idx(0).and_then(|arr| arr.get_idx(0)),
+            Some(&CachedValue::Value(1_u64.into()))
+        );
+    }
+}
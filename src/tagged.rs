@@ -0,0 +1,216 @@
+use std::collections::BTreeMap;
+
+use crate::Value;
+
+/// Identifies a node of a [`TaggedValue`] by its position in a pre-order traversal.
+///
+/// The root has the id 0, followed by its children and their descendants in order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NodeId(usize);
+
+impl NodeId {
+    /// Returns the position of the node in a pre-order traversal.
+    pub fn index(self) -> usize {
+        self.0
+    }
+}
+
+/// A `Value` with a side table of user metadata (tags) for its nodes.
+///
+/// Tags are stored separately from the `Value`, so the memory layout of `Value` doesn't change
+/// and all APIs of `Value` remain available via [`TaggedValue::value`]. Nodes are identified by
+/// their [`NodeId`]. The value can't be modified while it is tagged, as that would invalidate
+/// the ids.
+///
+/// # Example
+/// ```
+/// use serde_json_borrow::{TaggedValue, Value};
+///
+/// let value: Value = serde_json::from_str(r#"{"ids": [1, 2], "user": {"name": "John"}}"#).unwrap();
+/// let mut tagged = TaggedValue::new(value);
+///
+/// let name = tagged.node_id("/user/name").unwrap();
+/// tagged.set_tag(name, "pii");
+/// let numbers: Vec<_> = tagged.nodes().filter(|(_, value)| value.is_number()).map(|(id, _)| id).collect();
+/// for id in numbers {
+///     tagged.set_tag(id, "numeric");
+/// }
+///
+/// assert_eq!(tagged.tag(name), Some(&"pii"));
+/// let tags: Vec<_> = tagged.tags().map(|(id, tag)| (tagged.node(id).unwrap().to_string(), *tag)).collect();
+/// assert_eq!(tags, vec![("1".to_string(), "numeric"), ("2".to_string(), "numeric"), ("\"John\"".to_string(), "pii")]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct TaggedValue<'ctx, T> {
+    value: Value<'ctx>,
+    node_count: usize,
+    tags: BTreeMap<NodeId, T>,
+}
+
+impl<'ctx, T> TaggedValue<'ctx, T> {
+    /// Creates a `TaggedValue` without any tags.
+    pub fn new(value: Value<'ctx>) -> Self {
+        let node_count = count_nodes(&value);
+        Self {
+            value,
+            node_count,
+            tags: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the tagged value.
+    pub fn value(&self) -> &Value<'ctx> {
+        &self.value
+    }
+
+    /// Returns the number of nodes in the value.
+    pub fn node_count(&self) -> usize {
+        self.node_count
+    }
+
+    /// An iterator over all nodes of the value and their ids in pre-order.
+    pub fn nodes<'a>(&'a self) -> impl Iterator<Item = (NodeId, &'a Value<'ctx>)> + 'a {
+        let mut stack = vec![&self.value];
+        let mut next_id = 0;
+        std::iter::from_fn(move || {
+            let value = stack.pop()?;
+            match value {
+                Value::Array(arr) => stack.extend(arr.iter().rev()),
+                Value::Object(obj) => stack.extend(obj.0.iter().rev().map(|(_, v)| v)),
+                _ => {}
+            }
+            let id = NodeId(next_id);
+            next_id += 1;
+            Some((id, value))
+        })
+    }
+
+    /// Returns the node with the id `id`.
+    ///
+    /// ## Performance
+    /// This walks the value, so it is linear in the number of nodes.
+    pub fn node(&self, id: NodeId) -> Option<&Value<'ctx>> {
+        self.nodes().nth(id.0).map(|(_, value)| value)
+    }
+
+    /// Returns the id of the node at the JSON Pointer `pointer`.
+    ///
+    /// ## Performance
+    /// This walks the value, so it is linear in the number of nodes.
+    pub fn node_id(&self, pointer: &str) -> Option<NodeId> {
+        let target = self.value.pointer(pointer)?;
+        self.nodes()
+            .find(|(_, value)| std::ptr::eq(*value, target))
+            .map(|(id, _)| id)
+    }
+
+    /// Sets the tag of the node `id` and returns the previous tag.
+    ///
+    /// # Panics
+    /// Panics if `id` is not a node of this value.
+    pub fn set_tag(&mut self, id: NodeId, tag: T) -> Option<T> {
+        assert!(
+            id.0 < self.node_count,
+            "node {} does not exist, the value has {} nodes",
+            id.0,
+            self.node_count
+        );
+        self.tags.insert(id, tag)
+    }
+
+    /// Returns the tag of the node `id`.
+    pub fn tag(&self, id: NodeId) -> Option<&T> {
+        self.tags.get(&id)
+    }
+
+    /// Returns a mutable reference to the tag of the node `id`.
+    pub fn tag_mut(&mut self, id: NodeId) -> Option<&mut T> {
+        self.tags.get_mut(&id)
+    }
+
+    /// Removes the tag of the node `id` and returns it.
+    pub fn remove_tag(&mut self, id: NodeId) -> Option<T> {
+        self.tags.remove(&id)
+    }
+
+    /// An iterator over all tags and the ids of their nodes in pre-order.
+    pub fn tags(&self) -> impl Iterator<Item = (NodeId, &T)> {
+        self.tags.iter().map(|(id, tag)| (*id, tag))
+    }
+
+    /// Returns the value and the tags.
+    pub fn into_parts(self) -> (Value<'ctx>, BTreeMap<NodeId, T>) {
+        (self.value, self.tags)
+    }
+}
+
+fn count_nodes(value: &Value) -> usize {
+    1 + match value {
+        Value::Array(arr) => arr.iter().map(count_nodes).sum(),
+        Value::Object(obj) => obj.values().map(count_nodes).sum(),
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tagged_value_node_ids() {
+        let value: Value = serde_json::from_str(r#"{"a": [1, {"b": 2}], "c": 3}"#).unwrap();
+        let tagged: TaggedValue<()> = TaggedValue::new(value);
+
+        assert_eq!(tagged.node_count(), 6);
+        let ids: Vec<(usize, String)> = tagged
+            .nodes()
+            .map(|(id, value)| (id.index(), value.to_string()))
+            .collect();
+        assert_eq!(
+            ids,
+            vec![
+                (0, r#"{"a":[1,{"b":2}],"c":3}"#.to_string()),
+                (1, r#"[1,{"b":2}]"#.to_string()),
+                (2, "1".to_string()),
+                (3, r#"{"b":2}"#.to_string()),
+                (4, "2".to_string()),
+                (5, "3".to_string()),
+            ]
+        );
+        assert_eq!(tagged.node_id(""), Some(NodeId(0)));
+        assert_eq!(tagged.node_id("/a/1/b"), Some(NodeId(4)));
+        assert_eq!(tagged.node_id("/c"), Some(NodeId(5)));
+        assert_eq!(tagged.node_id("/d"), None);
+        assert_eq!(tagged.node(NodeId(3)), tagged.value().pointer("/a/1"));
+        assert_eq!(tagged.node(NodeId(6)), None);
+    }
+
+    #[test]
+    fn tagged_value_tags() {
+        let value: Value = serde_json::from_str(r#"[1, 2, 3]"#).unwrap();
+        let mut tagged = TaggedValue::new(value);
+
+        assert_eq!(tagged.set_tag(NodeId(2), 10), None);
+        assert_eq!(tagged.set_tag(NodeId(2), 20), Some(10));
+        tagged.set_tag(NodeId(0), 5);
+        *tagged.tag_mut(NodeId(0)).unwrap() += 1;
+        assert_eq!(tagged.tag(NodeId(0)), Some(&6));
+        assert_eq!(tagged.tag(NodeId(1)), None);
+        assert_eq!(
+            tagged.tags().collect::<Vec<_>>(),
+            vec![(NodeId(0), &6), (NodeId(2), &20)]
+        );
+        assert_eq!(tagged.remove_tag(NodeId(0)), Some(6));
+
+        let (value, tags) = tagged.into_parts();
+        assert_eq!(value.as_array().unwrap().len(), 3);
+        assert_eq!(tags.len(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "node 3 does not exist")]
+    fn tagged_value_invalid_id() {
+        let mut tagged = TaggedValue::new(Value::from(vec![1, 2]));
+        tagged.set_tag(NodeId(3), ());
+    }
+}
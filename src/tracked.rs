@@ -0,0 +1,245 @@
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::value::push_pointer_token;
+use crate::Value;
+
+/// Records which paths of a [`Value`] were accessed through [`TrackedValue`]s.
+///
+/// This allows to detect fields, which are sent by producers but never read, see
+/// [`AccessLog::unused_paths`].
+///
+/// Paths are JSON Pointers, with all array indices replaced by `*`, so the elements of an
+/// array are aggregated.
+///
+/// # Example
+/// ```
+/// use serde_json_borrow::{AccessLog, Value};
+///
+/// let value: Value = serde_json::from_str(
+///     r#"{"user": {"id": 1, "name": "John"}, "items": [{"sku": "a", "qty": 2}], "debug": {}}"#,
+/// )
+/// .unwrap();
+///
+/// let log = AccessLog::new();
+/// let doc = log.track(&value);
+/// let id = doc.get("user").get("id").value().as_u64();
+/// let skus: Vec<_> = doc.get("items").iter_array().map(|item| item.get("sku").value()).collect();
+/// assert_eq!((id, skus.len()), (Some(1), 1));
+///
+/// assert_eq!(
+///     log.accessed_paths(),
+///     ["/items", "/items/*", "/items/*/sku", "/user", "/user/id"]
+/// );
+/// assert_eq!(
+///     log.unused_paths(&value),
+///     ["/debug", "/items/*/qty", "/user/name"]
+/// );
+/// ```
+#[derive(Debug, Default)]
+pub struct AccessLog {
+    /// The accessed paths. The flag is set if the whole subtree was accessed via
+    /// [`TrackedValue::value`].
+    paths: RefCell<BTreeMap<String, bool>>,
+}
+
+impl AccessLog {
+    /// Creates an empty log.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Wraps `value`, so accesses through the returned [`TrackedValue`] are recorded in `self`.
+    pub fn track<'a, 'ctx>(&'a self, value: &'a Value<'ctx>) -> TrackedValue<'a, 'ctx> {
+        TrackedValue {
+            value,
+            path: String::new(),
+            log: self,
+        }
+    }
+
+    /// Returns the accessed paths in sorted order.
+    pub fn accessed_paths(&self) -> Vec<String> {
+        self.paths.borrow().keys().cloned().collect()
+    }
+
+    /// Returns the paths of object members in `value`, which were never accessed, in sorted
+    /// order. Members below an unused path are not reported separately.
+    ///
+    /// Array elements are aggregated like in [`AccessLog::accessed_paths`], so a member of an
+    /// array element counts as used if it was accessed in any element.
+    pub fn unused_paths(&self, value: &Value) -> Vec<String> {
+        let paths = self.paths.borrow();
+        let mut unused = BTreeSet::new();
+        collect_unused(value, &mut String::new(), &paths, &mut unused);
+        unused.into_iter().collect()
+    }
+
+    /// Removes all recorded paths.
+    pub fn clear(&self) {
+        self.paths.borrow_mut().clear();
+    }
+
+    fn record(&self, path: &str, whole: bool) {
+        let mut paths = self.paths.borrow_mut();
+        match paths.get_mut(path) {
+            Some(flag) => *flag |= whole,
+            None => {
+                paths.insert(path.to_string(), whole);
+            }
+        }
+    }
+}
+
+fn collect_unused(
+    value: &Value,
+    path: &mut String,
+    paths: &BTreeMap<String, bool>,
+    unused: &mut BTreeSet<String>,
+) {
+    if paths.get(path.as_str()) == Some(&true) {
+        return;
+    }
+    let len = path.len();
+    match value {
+        Value::Object(obj) => {
+            for (key, value) in obj.iter() {
+                push_pointer_token(path, key);
+                if paths.contains_key(path.as_str()) {
+                    collect_unused(value, path, paths, unused);
+                } else {
+                    unused.insert(path.clone());
+                }
+                path.truncate(len);
+            }
+        }
+        Value::Array(arr) => {
+            path.push_str("/*");
+            if paths.contains_key(path.as_str()) {
+                for value in arr {
+                    collect_unused(value, path, paths, unused);
+                }
+            } else if !arr.is_empty() {
+                unused.insert(path.clone());
+            }
+            path.truncate(len);
+        }
+        _ => {}
+    }
+}
+
+/// A reference into a [`Value`], which records the accessed paths in an [`AccessLog`].
+///
+/// Lookups via [`TrackedValue::get`], [`TrackedValue::get_index`] and the iterators record the
+/// path of the child. [`TrackedValue::value`] returns the untracked value and marks its whole
+/// subtree as accessed.
+#[derive(Debug, Clone)]
+pub struct TrackedValue<'a, 'ctx> {
+    value: &'a Value<'ctx>,
+    /// The JSON Pointer of `value`, with array indices replaced by `*`.
+    path: String,
+    log: &'a AccessLog,
+}
+
+impl<'a, 'ctx> TrackedValue<'a, 'ctx> {
+    /// Looks up `key` in an object, see [`Value::get`]. Returns a tracked `Value::Null` if the
+    /// key doesn't exist or the value is not an object. The access is recorded in both cases.
+    pub fn get(&self, key: &str) -> TrackedValue<'a, 'ctx> {
+        let mut path = self.path.clone();
+        push_pointer_token(&mut path, key);
+        self.child(self.value.get(key), path)
+    }
+
+    /// Looks up the element at `idx` in an array, see [`Value::get`].
+    pub fn get_index(&self, idx: usize) -> TrackedValue<'a, 'ctx> {
+        self.child(self.value.get(idx), self.path.clone() + "/*")
+    }
+
+    /// Iterates over the elements, if the value is an array. Otherwise the iterator is empty.
+    pub fn iter_array(&self) -> impl Iterator<Item = TrackedValue<'a, 'ctx>> + '_ {
+        let path = self.path.clone() + "/*";
+        let elements = self.value.as_array().unwrap_or_default();
+        elements
+            .iter()
+            .map(move |value| self.child(value, path.clone()))
+    }
+
+    /// Iterates over the members, if the value is an object. Otherwise the iterator is empty.
+    pub fn iter_object(&self) -> impl Iterator<Item = (&'a str, TrackedValue<'a, 'ctx>)> + '_ {
+        let members = self
+            .value
+            .as_object()
+            .into_iter()
+            .flat_map(|obj| obj.iter());
+        members.map(move |(key, value)| {
+            let mut path = self.path.clone();
+            push_pointer_token(&mut path, key);
+            (key, self.child(value, path))
+        })
+    }
+
+    /// Returns the value and records its whole subtree as accessed.
+    pub fn value(&self) -> &'a Value<'ctx> {
+        self.log.record(&self.path, true);
+        self.value
+    }
+
+    /// Returns the JSON Pointer of the value, with array indices replaced by `*`.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    fn child(&self, value: &'a Value<'ctx>, path: String) -> TrackedValue<'a, 'ctx> {
+        self.log.record(&path, false);
+        TrackedValue {
+            value,
+            path,
+            log: self.log,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn access_log_test() {
+        let value: Value = serde_json::from_str(
+            r#"{"a": {"b": 1, "c": [1, 2]}, "d/e": [{"x": 1}, {"y": 2}], "f": [], "g": 1}"#,
+        )
+        .unwrap();
+        let log = AccessLog::new();
+        let doc = log.track(&value);
+        assert!(doc.get("missing").value().is_null());
+        assert_eq!(doc.get("a").get("c").value(), &serde_json::json!([1, 2]));
+        for (key, member) in doc.get("d/e").get_index(1).iter_object() {
+            assert_eq!(key, "y");
+            assert_eq!(member.path(), "/d~1e/*/y");
+        }
+        doc.get("f");
+
+        assert_eq!(
+            log.accessed_paths(),
+            [
+                "/a",
+                "/a/c",
+                "/d~1e",
+                "/d~1e/*",
+                "/d~1e/*/y",
+                "/f",
+                "/missing"
+            ]
+        );
+        assert_eq!(log.unused_paths(&value), ["/a/b", "/d~1e/*/x", "/g"]);
+
+        doc.value();
+        assert!(log.unused_paths(&value).is_empty());
+        log.clear();
+        assert!(log.accessed_paths().is_empty());
+        assert_eq!(
+            log.unused_paths(&value),
+            ["/a", "/d~1e", "/f", "/g"].map(String::from)
+        );
+    }
+}
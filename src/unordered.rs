@@ -0,0 +1,152 @@
+use std::hash::{Hash, Hasher};
+
+use crate::object_vec::key_str;
+use crate::{ObjectAsVec, Value};
+
+/// Wraps a [`Value`] to compare and hash objects independent of the order of their entries.
+///
+/// The `PartialEq` and `Hash` impls of `Value` compare object entries in order, so
+/// `{"a": 1, "b": 2}` and `{"b": 2, "a": 1}` are different, unless the `btree` feature flag is
+/// enabled. `Unordered` treats them as equal, e.g. to deduplicate documents in a `HashSet`.
+/// Entries with duplicate keys are compared in their original order.
+///
+/// Like for `Value`, numbers are only equal if they have the same representation, so `1` and
+/// `1.0` are different.
+///
+/// # Example
+/// ```
+/// use std::collections::HashSet;
+///
+/// use serde_json_borrow::{Unordered, Value};
+///
+/// let docs = [r#"{"a": 1, "b": [{"c": 2, "d": 3}]}"#, r#"{"b": [{"d": 3, "c": 2}], "a": 1}"#];
+/// let unique: HashSet<Unordered> = docs
+///     .iter()
+///     .map(|doc| Unordered(serde_json::from_str::<Value>(doc).unwrap()))
+///     .collect();
+/// assert_eq!(unique.len(), 1);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Unordered<'ctx>(pub Value<'ctx>);
+
+impl PartialEq for Unordered<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        eq_unordered(&self.0, &other.0)
+    }
+}
+
+impl Eq for Unordered<'_> {}
+
+impl Hash for Unordered<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        hash_unordered(&self.0, state);
+    }
+}
+
+impl<'ctx> From<Value<'ctx>> for Unordered<'ctx> {
+    fn from(value: Value<'ctx>) -> Self {
+        Unordered(value)
+    }
+}
+
+/// Returns the entries sorted by key. The sort is stable, so duplicate keys keep their order.
+fn sorted_entries<'a, 'ctx>(obj: &'a ObjectAsVec<'ctx>) -> Vec<(&'a str, &'a Value<'ctx>)> {
+    let mut entries: Vec<_> = obj
+        .0
+        .iter()
+        .map(|(key, value)| (key_str(key), value))
+        .collect();
+    entries.sort_by_key(|(key, _)| *key);
+    entries
+}
+
+fn eq_unordered(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::Array(a), Value::Array(b)) => {
+            a.len() == b.len() && a.iter().zip(b).all(|(a, b)| eq_unordered(a, b))
+        }
+        (Value::Object(a), Value::Object(b)) => {
+            a.len() == b.len()
+                && sorted_entries(a)
+                    .into_iter()
+                    .zip(sorted_entries(b))
+                    .all(|((key_a, a), (key_b, b))| key_a == key_b && eq_unordered(a, b))
+        }
+        _ => a == b,
+    }
+}
+
+fn hash_unordered<H: Hasher>(value: &Value, state: &mut H) {
+    match value {
+        Value::Array(arr) => {
+            state.write_u8(0);
+            arr.len().hash(state);
+            for value in arr {
+                hash_unordered(value, state);
+            }
+        }
+        Value::Object(obj) => {
+            state.write_u8(1);
+            obj.len().hash(state);
+            for (key, value) in sorted_entries(obj) {
+                key.hash(state);
+                hash_unordered(value, state);
+            }
+        }
+        _ => {
+            state.write_u8(2);
+            value.hash(state);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::hash_map::DefaultHasher;
+
+    use super::*;
+
+    fn unordered(json: &str) -> Unordered<'_> {
+        Unordered(serde_json::from_str(json).unwrap())
+    }
+
+    fn hash(value: &Unordered) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn unordered_eq_and_hash() {
+        let a = unordered(r#"{"a": [1, {"x": null, "y": "s"}], "b": {}, "c": true}"#);
+        let b = unordered(r#"{"c": true, "b": {}, "a": [1, {"y": "s", "x": null}]}"#);
+        assert_eq!(a, b);
+        assert_eq!(hash(&a), hash(&b));
+
+        for other in [
+            r#"{"c": true, "b": {}, "a": [{"y": "s", "x": null}, 1]}"#,
+            r#"{"c": true, "b": {}, "a": [1, {"y": "s", "x": null}], "d": 1}"#,
+            r#"{"c": true, "b": [], "a": [1, {"y": "s", "x": null}]}"#,
+            r#"{"c": true, "b": {}, "a": [1.0, {"y": "s", "x": null}]}"#,
+        ] {
+            let other = unordered(other);
+            assert_ne!(a, other);
+            assert_ne!(hash(&a), hash(&other));
+        }
+    }
+
+    #[test]
+    fn unordered_constructed_values() {
+        let a = Unordered(Value::Object(
+            vec![("a", Value::from(1u64)), ("b", Value::Null)].into(),
+        ));
+        let b = Unordered(Value::Object(
+            vec![("b", Value::Null), ("a", Value::from(1u64))].into(),
+        ));
+        assert_eq!(a, b);
+        assert_eq!(hash(&a), hash(&b));
+        assert_eq!(unordered("[]"), Unordered(Value::Array(Vec::new())));
+        assert_ne!(unordered("[]"), unordered("{}"));
+        assert_ne!(unordered("null"), unordered("false"));
+    }
+}
@@ -1,13 +1,35 @@
+use core::cmp::Ordering;
 use core::fmt;
 use core::hash::{Hash, Hasher};
 use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt::{Debug, Display};
+use std::str::FromStr;
 
-use crate::index::Index;
+use serde::de;
+
+use crate::convert::TypeMismatch;
+use crate::index::{Index, Lookup};
 pub use crate::object_vec::ObjectAsVec;
+use crate::object_vec::{key_from_cow, KeyStrType};
+use crate::ser::{write_json, write_value, SerializeOptions};
 
 /// Represents any valid JSON value.
 ///
+/// # Ordering
+/// Values are totally ordered, so they can be used as sort keys or as keys of a `BTreeMap`. Values
+/// of different types are ordered `Null < Bool < Number < Str < Array < Object`. Numbers are
+/// compared by their numeric value, see [`Number`]. Strings are compared lexicographically by
+/// bytes, arrays and objects are compared lexicographically by their elements. Objects compare
+/// their `(key, value)` entries in insertion order, consistent with `Eq`.
+///
+/// # Nesting
+/// Parsing limits the nesting depth to 128 like serde_json, but values built in code can be
+/// nested deeper. Such values can be written with [`crate::to_string`] and the other `to_*`
+/// functions, which don't recurse. Dropping, cloning, comparing, hashing and the `Serialize` impl
+/// recurse per nesting level and can overflow the stack. An iterative `Drop` impl is not possible,
+/// since it would forbid moving out of the variants of `Value`.
+///
 /// # Example
 /// ```
 /// use std::io;
@@ -20,7 +42,8 @@ pub use crate::object_vec::ObjectAsVec;
 ///     Ok(())
 /// }
 /// ```
-#[derive(Clone, Eq, PartialEq, Hash, Default)]
+// The variant order defines the ordering between different types.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Default)]
 pub enum Value<'ctx> {
     /// Represents a JSON null value.
     ///
@@ -74,9 +97,49 @@ pub enum Value<'ctx> {
     /// let v = Value::Object([("key".into(), Value::Str("value".into()))].into_iter().collect::<Vec<_>>().into());
     /// ```
     Object(ObjectAsVec<'ctx>),
+
+    /// Represents binary data, for formats beyond JSON, e.g. MessagePack or CBOR.
+    ///
+    /// Only available with the `bytes` feature flag. Deserializers populate it via
+    /// `visit_bytes`, `serde_json` never does. When serializing to JSON, the bytes are written as
+    /// array of numbers, or as base64 string with [`crate::BytesEncoding::Base64`].
+    ///
+    /// ```
+    /// # use serde_json_borrow::Value;
+    /// #
+    /// let v = Value::Bytes([1, 2, 3].as_slice().into());
+    /// assert_eq!(v.to_string(), "[1,2,3]");
+    /// ```
+    #[cfg(feature = "bytes")]
+    Bytes(Cow<'ctx, [u8]>),
 }
 
 impl<'ctx> Value<'ctx> {
+    /// A `null` value, which can be used in constant expressions.
+    pub const NULL: Value<'ctx> = Value::Null;
+
+    /// Creates a string value borrowing `text`. Unlike `Value::from`, this can be used in
+    /// constant expressions, e.g. to define values in statics without lazy initialization.
+    ///
+    /// # Example
+    /// ```
+    /// use serde_json_borrow::{Number, Value};
+    ///
+    /// static DEFAULTS: [(&str, Value<'static>); 3] = [
+    ///     ("name", Value::const_str("unknown")),
+    ///     ("retries", Value::Number(Number::from_u64(3))),
+    ///     ("parent", Value::NULL),
+    /// ];
+    ///
+    /// assert_eq!(DEFAULTS[0].1.as_str(), Some("unknown"));
+    /// assert_eq!(DEFAULTS[1].1.as_u64(), Some(3));
+    /// assert!(DEFAULTS[2].1.is_null());
+    /// ```
+    #[inline]
+    pub const fn const_str(text: &'ctx str) -> Value<'ctx> {
+        Value::Str(Cow::Borrowed(text))
+    }
+
     /// Index into a `serde_json_borrow::Value` using the syntax `value.get(0)` or
     /// `value.get("k")`.
     ///
@@ -108,11 +171,430 @@ impl<'ctx> Value<'ctx> {
     /// assert_eq!(data.get("a").get("b"), &Value::Null);
     /// ```
     #[inline]
-    pub fn get<I: Index<'ctx>>(&'ctx self, index: I) -> &'ctx Value<'ctx> {
+    pub fn get<I: Index<'ctx>>(&self, index: I) -> &Value<'ctx> {
         static NULL: Value = Value::Null;
         index.index_into(self).unwrap_or(&NULL)
     }
 
+    /// Index into a JSON array or map like [`Value::get`], but returns why the value was not
+    /// found, see [`Lookup`].
+    ///
+    /// This distinguishes a missing value from a `null` value that is present, without a
+    /// separate `contains_key` scan.
+    #[inline]
+    pub fn lookup<I: Index<'ctx>>(&self, index: I) -> Lookup<'_, 'ctx> {
+        index.lookup_into(self)
+    }
+
+    /// Returns the variant name and the value of an internally tagged union, i.e. an object with
+    /// a string discriminator like `{"type": "click", "x": 1}`. The returned value is `self`,
+    /// including the tag.
+    ///
+    /// Returns None if the value is not an object or the tag is missing or not a string.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use serde_json_borrow::Value;
+    /// #
+    /// let event: Value = serde_json::from_str(r#"{"type": "click", "x": 1}"#).unwrap();
+    ///
+    /// match event.variant_by("type") {
+    ///     Some(("click", payload)) => assert_eq!(payload.get("x"), &1_u64),
+    ///     _ => panic!("unexpected variant"),
+    /// }
+    /// ```
+    pub fn variant_by(&self, tag: &str) -> Option<(&str, &Value<'ctx>)> {
+        Some((self.get(tag).as_str()?, self))
+    }
+
+    /// Returns the variant name and the content of an adjacently tagged union, i.e. an object
+    /// like `{"type": "click", "data": {"x": 1}}`. The content is `Value::Null` if it is
+    /// missing, like for unit variants.
+    ///
+    /// Returns None if the value is not an object or the tag is missing or not a string.
+    pub fn variant_by_adjacent(&self, tag: &str, content: &str) -> Option<(&str, &Value<'ctx>)> {
+        Some((self.get(tag).as_str()?, self.get(content)))
+    }
+
+    /// Dispatches on the variant of an internally tagged union, see [`Value::variant_by`].
+    ///
+    /// `f` receives the variant name and the value, and typically deserializes the value with
+    /// the `Deserialize` impl of the variant's payload. Returns an error if the tag is missing.
+    ///
+    /// Enums with `#[serde(tag = "...")]` can also be deserialized from `&Value` directly, as
+    /// their derived `Deserialize` impls don't need enum support of the deserializer. Adjacently
+    /// tagged enums, i.e. `#[serde(tag = "...", content = "...")]`, need it for the tag, so
+    /// dispatch on the tag with this method and deserialize `value.get(content)` in `f` instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use serde_json_borrow::Value;
+    /// use serde::de::{Deserialize, Error};
+    ///
+    /// enum Event {
+    ///     Click(Vec<u64>),
+    ///     Key(String),
+    /// }
+    ///
+    /// let event: Value =
+    ///     serde_json::from_str(r#"{"type": "click", "pos": [1, 2]}"#).unwrap();
+    /// let event = event
+    ///     .deserialize_variant("type", |variant, value| match variant {
+    ///         "click" => Vec::deserialize(value.get("pos")).map(Event::Click),
+    ///         "key" => String::deserialize(value.get("key")).map(Event::Key),
+    ///         other => Err(Error::unknown_variant(other, &["click", "key"])),
+    ///     })
+    ///     .unwrap();
+    /// assert!(matches!(event, Event::Click(pos) if pos == [1, 2]));
+    /// ```
+    pub fn deserialize_variant<'a, T, F>(&'a self, tag: &str, f: F) -> Result<T, de::value::Error>
+    where F: FnOnce(&'a str, &'a Value<'ctx>) -> Result<T, de::value::Error> {
+        let (variant, value) = self
+            .variant_by(tag)
+            .ok_or_else(|| de::Error::custom(format_args!("missing tag `{tag}`")))?;
+        f(variant, value)
+    }
+
+    /// Looks up a value by a dotted path like `"a.b.2.c"`.
+    ///
+    /// Each segment is an object key, or an array index if the current value is an array. A `.`
+    /// which is part of a key can be escaped as `\.`, a `\` as `\\`. The empty path returns
+    /// the value itself.
+    ///
+    /// Like [`Value::get`], this returns `Value::Null` if the path doesn't exist.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use serde_json_borrow::Value;
+    /// #
+    /// let data: Value =
+    ///     serde_json::from_str(r#"{"a": {"b": [1, 2, {"c": true}]}, "x.y": 3}"#).unwrap();
+    ///
+    /// assert_eq!(data.get_path("a.b.2.c"), &Value::Bool(true));
+    /// assert_eq!(data.get_path("a.b.1"), &Value::from(2u64));
+    /// assert_eq!(data.get_path(r"x\.y"), &Value::from(3u64));
+    /// assert_eq!(data.get_path("a.b.3"), &Value::Null);
+    /// ```
+    pub fn get_path(&self, path: &str) -> &Value<'ctx> {
+        static NULL: Value = Value::Null;
+        if path.is_empty() {
+            return self;
+        }
+        PathSegments { rest: Some(path) }
+            .try_fold(self, |target, segment| match target {
+                Value::Object(map) => map.get(&segment),
+                Value::Array(list) => parse_index(&segment).and_then(|idx| list.get(idx)),
+                _ => None,
+            })
+            .unwrap_or(&NULL)
+    }
+
+    /// Mutably index into a `serde_json_borrow::Value` using the syntax `value.get_mut(0)` or
+    /// `value.get_mut("k")`.
+    ///
+    /// Returns `None` if the type of `self` does not match the type of the
+    /// index, if the given key does not exist in the map or the given index is
+    /// not within the bounds of the array.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use serde_json_borrow::Value;
+    /// #
+    /// let mut data: Value = serde_json::from_str(r#"{"x": {"y": ["z", "zz"]}}"#).unwrap();
+    ///
+    /// if let Some(val) = data.get_mut("x").and_then(|x| x.get_mut("y")).and_then(|y| y.get_mut(0)) {
+    ///     *val = Value::Str("a".into());
+    /// }
+    /// assert_eq!(data.get("x").get("y").get(0), &Value::Str("a".into()));
+    /// assert!(data.get_mut("x").and_then(|x| x.get_mut(0)).is_none());
+    /// ```
+    #[inline]
+    pub fn get_mut<I: Index<'ctx>>(&mut self, index: I) -> Option<&mut Value<'ctx>> {
+        index.index_into_mut(self)
+    }
+
+    /// Looks up a value by a JSON Pointer.
+    ///
+    /// JSON Pointer defines a string syntax for identifying a specific value
+    /// within a JavaScript Object Notation (JSON) document.
+    ///
+    /// A Pointer is a Unicode string with the reference tokens separated by `/`.
+    /// Inside tokens `/` is replaced by `~1` and `~` is replaced by `~0`. The
+    /// addressed value is returned and if there is no such value `None` is
+    /// returned.
+    ///
+    /// For more information read [RFC6901](https://tools.ietf.org/html/rfc6901).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use serde_json_borrow::Value;
+    /// #
+    /// let data: Value = serde_json::from_str(r#"{"x": {"y": ["z", "zz"]}}"#).unwrap();
+    ///
+    /// assert_eq!(data.pointer("/x/y/1"), Some(&Value::Str("zz".into())));
+    /// assert_eq!(data.pointer("/a/b/c"), None);
+    /// ```
+    pub fn pointer(&self, pointer: &str) -> Option<&Value<'ctx>> {
+        if pointer.is_empty() {
+            return Some(self);
+        }
+        if !pointer.starts_with('/') {
+            return None;
+        }
+        pointer.split('/').skip(1).map(unescape_token).try_fold(
+            self,
+            |target, token| match target {
+                Value::Object(map) => map.get(&token),
+                Value::Array(list) => parse_index(&token).and_then(|idx| list.get(idx)),
+                _ => None,
+            },
+        )
+    }
+
+    /// Looks up a value by a JSON Pointer and returns a mutable reference to
+    /// that value.
+    ///
+    /// See [`Value::pointer`] for the pointer syntax.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use serde_json_borrow::Value;
+    /// #
+    /// let mut data: Value = serde_json::from_str(r#"{"x": 1.0, "y": 2.0}"#).unwrap();
+    ///
+    /// if let Some(val) = data.pointer_mut("/x") {
+    ///     *val = Value::from(1.5);
+    /// }
+    /// assert_eq!(data.get("x"), &Value::from(1.5));
+    /// ```
+    pub fn pointer_mut(&mut self, pointer: &str) -> Option<&mut Value<'ctx>> {
+        if pointer.is_empty() {
+            return Some(self);
+        }
+        if !pointer.starts_with('/') {
+            return None;
+        }
+        pointer.split('/').skip(1).map(unescape_token).try_fold(
+            self,
+            |target, token| match target {
+                Value::Object(map) => map.get_mut(&token),
+                Value::Array(list) => parse_index(&token).and_then(move |idx| list.get_mut(idx)),
+                _ => None,
+            },
+        )
+    }
+
+    /// Looks up a value by a JSON Pointer like [`Value::pointer_mut`], but creates missing
+    /// values along the way.
+    ///
+    /// - `Value::Null` is replaced by an empty array before descending into it, if the token is `0`
+    ///   or `-`, otherwise by an empty object.
+    /// - Missing object keys are inserted with `Value::Null`.
+    /// - An array index equal to the length of the array, or `-`, appends `Value::Null`.
+    ///
+    /// Returns `None` if the pointer is malformed, points through a scalar value, or addresses an
+    /// array index past the end of the array.
+    /// Without the `cowkeys` feature flag, keys containing escape sequences (`~0`, `~1`) can't be
+    /// inserted and `None` is returned as well.
+    /// The whole pointer is checked before anything is inserted, so the value is unchanged if
+    /// `None` is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use serde_json_borrow::Value;
+    /// #
+    /// let mut data = Value::Null;
+    ///
+    /// *data.pointer_mut_or_insert("/a/b").unwrap() = Value::from("c");
+    /// *data.pointer_mut_or_insert("/a/list").unwrap() = Value::Array(Vec::new());
+    /// *data.pointer_mut_or_insert("/a/list/-").unwrap() = Value::from(true);
+    /// *data.pointer_mut_or_insert("/a/new/0").unwrap() = Value::from(1u64);
+    ///
+    /// assert_eq!(data.to_string(), r#"{"a":{"b":"c","list":[true],"new":[1]}}"#);
+    /// ```
+    pub fn pointer_mut_or_insert(&mut self, pointer: &'ctx str) -> Option<&mut Value<'ctx>> {
+        if pointer.is_empty() {
+            return Some(self);
+        }
+        if !pointer.starts_with('/') {
+            return None;
+        }
+        let tokens: Vec<Cow<'ctx, str>> = pointer.split('/').skip(1).map(unescape_token).collect();
+        // Escaped keys are owned, which are only supported as keys with `cowkeys`.
+        let insertable_key =
+            |token: &Cow<str>| cfg!(feature = "cowkeys") || matches!(token, Cow::Borrowed(_));
+        let starts_array = |token: &str| token == "0" || token == "-";
+
+        // Walk the existing values first, so nothing is inserted for an invalid pointer.
+        let mut existing = Some(&*self);
+        for token in &tokens {
+            existing = match existing {
+                Some(Value::Object(map)) => match map.get(token) {
+                    Some(value) => Some(value),
+                    None if insertable_key(token) => None,
+                    None => return None,
+                },
+                Some(Value::Array(list)) => {
+                    let idx = if token == "-" {
+                        list.len()
+                    } else {
+                        parse_index(token)?
+                    };
+                    if idx > list.len() {
+                        return None;
+                    }
+                    list.get(idx)
+                }
+                Some(Value::Null) | None if starts_array(token) || insertable_key(token) => None,
+                _ => return None,
+            };
+        }
+
+        tokens.into_iter().try_fold(self, |target, token| {
+            if target.is_null() {
+                *target = if starts_array(&token) {
+                    Value::Array(Vec::new())
+                } else {
+                    Value::Object(ObjectAsVec::default())
+                };
+            }
+            match target {
+                Value::Object(map) => {
+                    Some(map.insert_key_or_get_mut(key_from_cow(token)?, Value::Null))
+                }
+                Value::Array(list) => {
+                    let idx = if token == "-" {
+                        list.len()
+                    } else {
+                        parse_index(&token)?
+                    };
+                    if idx == list.len() {
+                        list.push(Value::Null);
+                    }
+                    list.get_mut(idx)
+                }
+                _ => None,
+            }
+        })
+    }
+
+    /// Takes the value out of the `Value`, leaving a `Value::Null` in its place.
+    ///
+    /// No data is copied, borrowed strings stay borrowed.
+    ///
+    /// ```
+    /// # use serde_json_borrow::Value;
+    /// #
+    /// let mut data: Value = serde_json::from_str(r#"{"x": ["a", "b"]}"#).unwrap();
+    /// let x = data.pointer_mut("/x").unwrap().take();
+    ///
+    /// assert_eq!(x.get(0), &Value::Str("a".into()));
+    /// assert_eq!(data.get("x"), &Value::Null);
+    /// ```
+    #[inline]
+    pub fn take(&mut self) -> Value<'ctx> {
+        std::mem::take(self)
+    }
+
+    /// Replaces the value with `value` and returns the old value.
+    ///
+    /// ```
+    /// # use serde_json_borrow::Value;
+    /// #
+    /// let mut data = Value::from("old");
+    /// let old = data.replace(Value::from(true));
+    ///
+    /// assert_eq!(old, Value::from("old"));
+    /// assert_eq!(data, Value::Bool(true));
+    /// ```
+    #[inline]
+    pub fn replace(&mut self, value: Value<'ctx>) -> Value<'ctx> {
+        std::mem::replace(self, value)
+    }
+
+    /// Converts the value into a `Value<'static>` by copying all borrowed strings and keys.
+    ///
+    /// This allows to keep a (small) part of a document beyond the lifetime of the input.
+    ///
+    /// Requires the `cowkeys` feature flag, as borrowed `&str` keys can't be copied into owned
+    /// keys otherwise.
+    ///
+    /// # Example
+    /// ```
+    /// use serde_json_borrow::Value;
+    ///
+    /// fn detach(json: &str) -> Value<'static> {
+    ///     let mut value: Value = serde_json::from_str(json).unwrap();
+    ///     value.get_mut("user").map(Value::take).unwrap_or_default().into_static()
+    /// }
+    ///
+    /// let user = detach(&String::from(r#"{"user": {"name": "John"}, "payload": [1, 2, 3]}"#));
+    /// assert_eq!(user.get("name"), "John");
+    /// ```
+    #[cfg(feature = "cowkeys")]
+    pub fn into_static(self) -> Value<'static> {
+        match self {
+            Value::Null => Value::Null,
+            Value::Bool(b) => Value::Bool(b),
+            Value::Number(n) => Value::Number(n),
+            Value::Str(text) => Value::Str(Cow::Owned(text.into_owned())),
+            #[cfg(feature = "bytes")]
+            Value::Bytes(bytes) => Value::Bytes(Cow::Owned(bytes.into_owned())),
+            Value::Array(arr) => Value::Array(arr.into_iter().map(Value::into_static).collect()),
+            Value::Object(obj) => Value::Object(ObjectAsVec(
+                obj.0
+                    .into_iter()
+                    .map(|(key, value)| (Cow::Owned(key.into_owned()), value.into_static()))
+                    .collect(),
+            )),
+        }
+    }
+
+    /// Releases unused capacity of all arrays, objects and owned strings, including nested ones.
+    ///
+    /// Parsing over-allocates arrays and objects while they grow. For documents which are kept
+    /// for a long time, e.g. in a cache, this releases the spare capacity. Like
+    /// [`Vec::shrink_to_fit`], the allocator may still keep some spare capacity.
+    ///
+    /// # Example
+    /// ```
+    /// use serde_json_borrow::Value;
+    ///
+    /// let mut value: Value = serde_json::from_str(r#"{"a": [1, 2, 3, 4, 5]}"#).unwrap();
+    /// value.shrink_to_fit();
+    /// let arr = value.get_mut("a").and_then(Value::as_array_mut).unwrap();
+    /// assert!(arr.capacity() >= arr.len());
+    /// ```
+    pub fn shrink_to_fit(&mut self) {
+        match self {
+            Value::Str(Cow::Owned(text)) => text.shrink_to_fit(),
+            Value::Array(arr) => {
+                arr.shrink_to_fit();
+                for value in arr {
+                    value.shrink_to_fit();
+                }
+            }
+            Value::Object(obj) => {
+                obj.shrink_to_fit();
+                for (_key, value) in obj.0.iter_mut() {
+                    #[cfg(feature = "cowkeys")]
+                    if let Cow::Owned(key) = _key {
+                        key.shrink_to_fit();
+                    }
+                    value.shrink_to_fit();
+                }
+            }
+            _ => {}
+        }
+    }
+
     /// Returns true if `Value` is Value::Null.
     pub fn is_null(&self) -> bool {
         matches!(self, Value::Null)
@@ -187,6 +669,50 @@ impl<'ctx> Value<'ctx> {
         }
     }
 
+    /// If the Value is an Array, returns an iterator over its numbers as `f64`. Elements, which are
+    /// not numbers, are skipped.
+    ///
+    /// # Example
+    /// ```
+    /// use serde_json_borrow::Value;
+    ///
+    /// let value: Value = serde_json::from_str(r#"[1, 2.5, null, -3]"#).unwrap();
+    /// let sum: f64 = value.iter_f64().unwrap().sum();
+    /// assert_eq!(sum, 0.5);
+    /// let ints: Vec<i64> = value.iter_i64().unwrap().collect();
+    /// assert_eq!(ints, [1, -3]);
+    /// ```
+    pub fn iter_f64(&self) -> Option<impl Iterator<Item = f64> + '_> {
+        Some(self.as_array()?.iter().filter_map(Value::as_f64))
+    }
+
+    /// If the Value is an Array, returns an iterator over its integers as `i64`. Elements, which
+    /// are not integers representable as `i64`, are skipped.
+    pub fn iter_i64(&self) -> Option<impl Iterator<Item = i64> + '_> {
+        Some(self.as_array()?.iter().filter_map(Value::as_i64))
+    }
+
+    /// Like [`Value::iter_f64`], but returns an error for elements, which are not numbers,
+    /// instead of skipping them.
+    ///
+    /// # Example
+    /// ```
+    /// use serde_json_borrow::Value;
+    ///
+    /// let value: Value = serde_json::from_str(r#"[1, 2.5, "3"]"#).unwrap();
+    /// let err = value.try_iter_f64().unwrap().sum::<Result<f64, _>>().unwrap_err();
+    /// assert_eq!(err.to_string(), "expected number, found string");
+    /// ```
+    pub fn try_iter_f64(&self) -> Option<impl Iterator<Item = Result<f64, TypeMismatch>> + '_> {
+        Some(self.as_array()?.iter().map(f64::try_from))
+    }
+
+    /// Like [`Value::iter_i64`], but returns an error for elements, which are not integers
+    /// representable as `i64`, instead of skipping them.
+    pub fn try_iter_i64(&self) -> Option<impl Iterator<Item = Result<i64, TypeMismatch>> + '_> {
+        Some(self.as_array()?.iter().map(i64::try_from))
+    }
+
     /// If the Value is an Array, returns the associated Array. Returns None otherwise.
     pub fn as_array(&self) -> Option<&[Value<'ctx>]> {
         match self {
@@ -203,6 +729,33 @@ impl<'ctx> Value<'ctx> {
         }
     }
 
+    /// If the Value is an Array, returns a mutable reference to the associated Vec. Returns None
+    /// otherwise.
+    pub fn as_array_mut(&mut self) -> Option<&mut Vec<Value<'ctx>>> {
+        match self {
+            Value::Array(arr) => Some(arr),
+            _ => None,
+        }
+    }
+
+    /// If the Value is an Object, returns a mutable reference to the associated Object. Returns
+    /// None otherwise.
+    pub fn as_object_mut(&mut self) -> Option<&mut ObjectAsVec<'ctx>> {
+        match self {
+            Value::Object(obj) => Some(obj),
+            _ => None,
+        }
+    }
+
+    /// If the Value is a String, returns a mutable reference to the associated `Cow<str>`.
+    /// Returns None otherwise.
+    pub fn as_str_mut(&mut self) -> Option<&mut Cow<'ctx, str>> {
+        match self {
+            Value::Str(text) => Some(text),
+            _ => None,
+        }
+    }
+
     /// If the Value is a Boolean, returns the associated bool. Returns None otherwise.
     pub fn as_bool(&self) -> Option<bool> {
         match self {
@@ -211,6 +764,15 @@ impl<'ctx> Value<'ctx> {
         }
     }
 
+    /// If the Value is binary data, returns the associated bytes. Returns None otherwise.
+    #[cfg(feature = "bytes")]
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        match self {
+            Value::Bytes(bytes) => Some(bytes),
+            _ => None,
+        }
+    }
+
     /// If the Value is a String, returns the associated str. Returns None otherwise.
     pub fn as_str(&self) -> Option<&str> {
         match self {
@@ -235,42 +797,339 @@ impl<'ctx> Value<'ctx> {
         }
     }
 
-    /// If the Value is a number, represent it as f64 if possible. Returns None otherwise.
-    pub fn as_f64(&self) -> Option<f64> {
-        match self {
-            Value::Number(n) => n.as_f64(),
-            _ => None,
-        }
+    /// If the Value is a number, represent it as f64 if possible. Returns None otherwise.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Value::Number(n) => n.as_f64(),
+            _ => None,
+        }
+    }
+
+    /// Like [`Value::as_i64`], but also accepts floats without fractional part and strings
+    /// containing a JSON number, e.g. `"123"` or `"1e3"`. Whitespace around the number is
+    /// ignored.
+    ///
+    /// This is useful for data, where the type of a field varies between documents.
+    ///
+    /// # Example
+    /// ```
+    /// use serde_json_borrow::Value;
+    ///
+    /// let value: Value = serde_json::from_str(r#"[5, 5.0, "5", " 5e0 ", 5.5, "five"]"#).unwrap();
+    /// let ints: Vec<_> = value.iter_array().unwrap().map(Value::as_i64_lossy).collect();
+    /// assert_eq!(ints, [Some(5), Some(5), Some(5), Some(5), None, None]);
+    /// ```
+    pub fn as_i64_lossy(&self) -> Option<i64> {
+        let n = self.as_number_lossy()?;
+        match n.n {
+            // `i64::MAX as f64` is 2^63, which is out of range.
+            N::Float(f) if f.fract() == 0.0 && f >= i64::MIN as f64 && f < i64::MAX as f64 => {
+                Some(f as i64)
+            }
+            _ => n.as_i64(),
+        }
+    }
+
+    /// Like [`Value::as_f64`], but also accepts strings containing a JSON number, e.g. `"1.5"`.
+    /// Whitespace around the number is ignored.
+    pub fn as_f64_lossy(&self) -> Option<f64> {
+        self.as_number_lossy()?.as_f64()
+    }
+
+    /// Like [`Value::as_bool`], but also accepts the strings `"true"` and `"false"`.
+    /// Whitespace around the string is ignored.
+    pub fn as_bool_lossy(&self) -> Option<bool> {
+        match self {
+            Value::Bool(b) => Some(*b),
+            Value::Str(text) => match text.trim() {
+                "true" => Some(true),
+                "false" => Some(false),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    fn as_number_lossy(&self) -> Option<Number> {
+        match self {
+            Value::Number(n) => Some(*n),
+            Value::Str(text) => parse_number(text.trim()),
+            _ => None,
+        }
+    }
+
+    /// Returns the value at `index` if it is a string, e.g. `value.get_str("key")`. Returns None
+    /// if the value doesn't exist or has a different type.
+    #[inline]
+    pub fn get_str<I: Index<'ctx>>(&self, index: I) -> Option<&str> {
+        self.get(index).as_str()
+    }
+
+    /// Returns the value at `index` if it is an integer representable as i64, e.g.
+    /// `value.get_i64("key")`. Returns None if the value doesn't exist or has a different type.
+    #[inline]
+    pub fn get_i64<I: Index<'ctx>>(&self, index: I) -> Option<i64> {
+        self.get(index).as_i64()
+    }
+
+    /// Returns the value at `index` if it is an integer representable as u64, e.g.
+    /// `value.get_u64("key")`. Returns None if the value doesn't exist or has a different type.
+    #[inline]
+    pub fn get_u64<I: Index<'ctx>>(&self, index: I) -> Option<u64> {
+        self.get(index).as_u64()
+    }
+
+    /// Returns the value at `index` if it is a number, represented as f64, e.g.
+    /// `value.get_f64("key")`. Returns None if the value doesn't exist or has a different type.
+    #[inline]
+    pub fn get_f64<I: Index<'ctx>>(&self, index: I) -> Option<f64> {
+        self.get(index).as_f64()
+    }
+
+    /// Returns the value at `index` if it is a boolean, e.g. `value.get_bool("key")`. Returns None
+    /// if the value doesn't exist or has a different type.
+    #[inline]
+    pub fn get_bool<I: Index<'ctx>>(&self, index: I) -> Option<bool> {
+        self.get(index).as_bool()
+    }
+
+    /// Returns the value at `index` if it is an array, e.g. `value.get_array("key")`. Returns None
+    /// if the value doesn't exist or has a different type.
+    #[inline]
+    pub fn get_array<I: Index<'ctx>>(&self, index: I) -> Option<&[Value<'ctx>]> {
+        self.get(index).as_array()
+    }
+
+    /// Returns the value at `index` if it is an object, e.g. `value.get_object("key")`. Returns
+    /// None if the value doesn't exist or has a different type.
+    #[inline]
+    pub fn get_object<I: Index<'ctx>>(&self, index: I) -> Option<&ObjectAsVec<'ctx>> {
+        self.get(index).as_object()
+    }
+}
+
+/// Unescapes a JSON Pointer reference token, `~1` becomes `/` and `~0` becomes `~`.
+pub(crate) fn unescape_token(token: &str) -> Cow<'_, str> {
+    if token.contains('~') {
+        Cow::Owned(token.replace("~1", "/").replace("~0", "~"))
+    } else {
+        Cow::Borrowed(token)
+    }
+}
+
+/// Splits a dotted path into its segments, unescaping `\.` and `\\`.
+struct PathSegments<'a> {
+    rest: Option<&'a str>,
+}
+
+impl<'a> Iterator for PathSegments<'a> {
+    type Item = Cow<'a, str>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let rest = self.rest?;
+        let mut escaped = false;
+        let mut end = rest.len();
+        let mut bytes = rest.bytes().enumerate();
+        while let Some((idx, byte)) = bytes.next() {
+            match byte {
+                b'\\' => {
+                    escaped = true;
+                    bytes.next();
+                }
+                b'.' => {
+                    end = idx;
+                    break;
+                }
+                _ => {}
+            }
+        }
+        let segment = &rest[..end];
+        self.rest = rest.get(end + 1..);
+        if !escaped {
+            return Some(Cow::Borrowed(segment));
+        }
+        let mut unescaped = String::with_capacity(segment.len());
+        let mut chars = segment.chars();
+        while let Some(c) = chars.next() {
+            if c == '\\' {
+                unescaped.extend(chars.next());
+            } else {
+                unescaped.push(c);
+            }
+        }
+        Some(Cow::Owned(unescaped))
+    }
+}
+
+/// Appends `/` and the escaped `token` to a JSON Pointer.
+pub(crate) fn push_pointer_token(pointer: &mut String, token: &str) {
+    pointer.push('/');
+    for c in token.chars() {
+        match c {
+            '~' => pointer.push_str("~0"),
+            '/' => pointer.push_str("~1"),
+            c => pointer.push(c),
+        }
+    }
+}
+
+/// Returns the name of the JSON type of `value`, e.g. for error messages.
+pub(crate) fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::Str(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+        #[cfg(feature = "bytes")]
+        Value::Bytes(_) => "bytes",
+    }
+}
+
+/// Parses an array index of a JSON Pointer. Leading zeros and signs are not allowed.
+pub(crate) fn parse_index(token: &str) -> Option<usize> {
+    if token.starts_with('+') || (token.starts_with('0') && token.len() != 1) {
+        return None;
+    }
+    token.parse().ok()
+}
+
+impl From<bool> for Value<'_> {
+    fn from(val: bool) -> Self {
+        Value::Bool(val)
+    }
+}
+
+impl<'a> From<&'a str> for Value<'a> {
+    fn from(val: &'a str) -> Self {
+        Value::Str(Cow::Borrowed(val))
+    }
+}
+
+impl From<String> for Value<'_> {
+    fn from(val: String) -> Self {
+        Value::Str(Cow::Owned(val))
+    }
+}
+
+impl<'a> From<Cow<'a, str>> for Value<'a> {
+    fn from(val: Cow<'a, str>) -> Self {
+        Value::Str(val)
+    }
+}
+
+/// Converts `()` into `Value::Null`, like in serde_json.
+impl From<()> for Value<'_> {
+    fn from((): ()) -> Self {
+        Value::Null
+    }
+}
+
+impl<'a, T: Into<Value<'a>>> From<Vec<T>> for Value<'a> {
+    fn from(val: Vec<T>) -> Self {
+        Value::Array(val.into_iter().map(Into::into).collect())
+    }
+}
+
+impl<'a, T: Clone + Into<Value<'a>>> From<&[T]> for Value<'a> {
+    fn from(val: &[T]) -> Self {
+        Value::Array(val.iter().map(Clone::clone).map(Into::into).collect())
+    }
+}
+
+/// Creates an object from key-value pairs, e.g. `Value::from(&[("env", "prod")][..])`.
+///
+/// Keys can be `&str`, or with the `cowkeys` feature flag also `String` and `Cow<str>`. All
+/// entries are kept, also for duplicate keys.
+impl<'a, K, V> From<&[(K, V)]> for Value<'a>
+where
+    K: Clone + Into<KeyStrType<'a>>,
+    V: Clone + Into<Value<'a>>,
+{
+    fn from(val: &[(K, V)]) -> Self {
+        val.iter().cloned().collect()
+    }
+}
+
+/// Creates an object from key-value pairs, e.g. `Value::from([("env", "prod")])`.
+impl<'a, K, V, const N: usize> From<[(K, V); N]> for Value<'a>
+where
+    K: Into<KeyStrType<'a>>,
+    V: Into<Value<'a>>,
+{
+    fn from(val: [(K, V); N]) -> Self {
+        val.into_iter().collect()
     }
 }
 
-impl From<bool> for Value<'_> {
-    fn from(val: bool) -> Self {
-        Value::Bool(val)
+/// Creates an object from a map. The entries are in the iteration order of the map, or sorted by
+/// key with the `btree` feature flag.
+impl<'a, K, V, S> From<HashMap<K, V, S>> for Value<'a>
+where
+    K: Into<KeyStrType<'a>>,
+    V: Into<Value<'a>>,
+{
+    fn from(val: HashMap<K, V, S>) -> Self {
+        val.into_iter().collect()
     }
 }
 
-impl<'a> From<&'a str> for Value<'a> {
-    fn from(val: &'a str) -> Self {
-        Value::Str(Cow::Borrowed(val))
+/// Creates an object from a map, the entries are sorted by key.
+impl<'a, K, V> From<BTreeMap<K, V>> for Value<'a>
+where
+    K: Into<KeyStrType<'a>>,
+    V: Into<Value<'a>>,
+{
+    fn from(val: BTreeMap<K, V>) -> Self {
+        val.into_iter().collect()
     }
 }
 
-impl From<String> for Value<'_> {
-    fn from(val: String) -> Self {
-        Value::Str(Cow::Owned(val))
+/// Converts `None` to `Value::Null`.
+impl<'a, T: Into<Value<'a>>> From<Option<T>> for Value<'a> {
+    fn from(val: Option<T>) -> Self {
+        val.map_or(Value::Null, Into::into)
     }
 }
 
-impl<'a, T: Into<Value<'a>>> From<Vec<T>> for Value<'a> {
-    fn from(val: Vec<T>) -> Self {
-        Value::Array(val.into_iter().map(Into::into).collect())
+/// Collects values into an array.
+///
+/// # Example
+/// ```
+/// use serde_json_borrow::Value;
+///
+/// let value: Value = ["a", "b"].into_iter().map(Value::from).collect();
+/// assert_eq!(value, serde_json::json!(["a", "b"]));
+/// ```
+impl<'a, T: Into<Value<'a>>> FromIterator<T> for Value<'a> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Value::Array(iter.into_iter().map(Into::into).collect())
     }
 }
 
-impl<'a, T: Clone + Into<Value<'a>>> From<&[T]> for Value<'a> {
-    fn from(val: &[T]) -> Self {
-        Value::Array(val.iter().map(Clone::clone).map(Into::into).collect())
+/// Collects key-value pairs into an object.
+///
+/// Keys can be `&str`, or with the `cowkeys` feature flag also `String` and `Cow<str>`.
+///
+/// # Example
+/// ```
+/// use serde_json_borrow::Value;
+///
+/// let value: Value = ["a", "b"].into_iter().zip([Some(1u64), None]).collect();
+/// assert_eq!(value, serde_json::json!({"a": 1, "b": null}));
+/// ```
+impl<'a, K, V> FromIterator<(K, V)> for Value<'a>
+where
+    K: Into<KeyStrType<'a>>,
+    V: Into<Value<'a>>,
+{
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        Value::Object(ObjectAsVec::from_entries(
+            iter.into_iter()
+                .map(|(key, value)| (key.into(), value.into()))
+                .collect(),
+        ))
     }
 }
 
@@ -293,19 +1152,37 @@ impl Debug for Value<'_> {
                 formatter.write_str("Object ")?;
                 Debug::fmt(map, formatter)
             }
+            #[cfg(feature = "bytes")]
+            Value::Bytes(bytes) => write!(formatter, "Bytes({:?})", bytes),
         }
     }
 }
 
-// We just convert to serde_json::Value to Display
+/// Formats the value as compact JSON. The alternate flag `{:#}` formats it as indented JSON,
+/// with the width as the number of spaces per level, e.g. `{:#4}`. The default is 2 spaces.
 impl Display for Value<'_> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", serde_json::Value::from(self.clone()))
+        if f.alternate() {
+            let indent = " ".repeat(f.width().unwrap_or(2));
+            let options = SerializeOptions {
+                indent: Some(&indent),
+                ..SerializeOptions::default()
+            };
+            write_json(self, f, &options)
+        } else {
+            write_value(self, f)
+        }
     }
 }
 
 /// Represents a JSON number, whether integer or floating point.
-#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+///
+/// # Ordering
+/// Numbers are ordered by their exact numeric value, independent of their representation. Numbers
+/// with the same value, but different representations (e.g. `1` and `1.0`) are not equal and
+/// ordered integer before float. `NaN` is equal to itself and greater than all other numbers, so
+/// the order is total.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Number {
     pub(crate) n: N,
 }
@@ -316,16 +1193,37 @@ impl From<N> for Number {
     }
 }
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug)]
 pub(crate) enum N {
     PosInt(u64),
     /// Always less than zero.
     NegInt(i64),
-    /// Always finite.
+    /// Usually finite. Non-finite values can be created with `From<f64>` or by parsing the
+    /// non-standard tokens, see [`crate::NonFiniteMode`].
     Float(f64),
 }
 
 impl Number {
+    /// Creates a `Number` from an `u64`, which can be used in constant expressions.
+    #[inline]
+    pub const fn from_u64(val: u64) -> Self {
+        Self { n: N::PosInt(val) }
+    }
+
+    /// Creates a `Number` from an `i64`, which can be used in constant expressions.
+    ///
+    /// Non-negative values are stored like values created with [`Number::from_u64`].
+    #[inline]
+    pub const fn from_i64(val: i64) -> Self {
+        if val < 0 {
+            Self { n: N::NegInt(val) }
+        } else {
+            Self {
+                n: N::PosInt(val as u64),
+            }
+        }
+    }
+
     /// If the `Number` is an integer, represent it as i64 if possible. Returns
     /// None otherwise.
     pub fn as_u64(&self) -> Option<u64> {
@@ -378,6 +1276,55 @@ impl Number {
             N::Float(_) => false,
         }
     }
+
+    /// Adds two numbers. Integers are added exactly and the result is `None` if it is out of
+    /// range of `u64` and `i64`. If one of the numbers is a float, the result is a float and
+    /// `None` if it is not finite.
+    ///
+    /// # Example
+    /// ```
+    /// use serde_json_borrow::Number;
+    ///
+    /// let sum = Number::from(u64::MAX).checked_add(Number::from(-1i64)).unwrap();
+    /// assert_eq!(sum.as_u64(), Some(u64::MAX - 1));
+    /// assert_eq!(Number::from(1u64).checked_add(Number::from(0.5)).unwrap().as_f64(), Some(1.5));
+    /// assert!(Number::from(u64::MAX).checked_add(Number::from(1u64)).is_none());
+    /// ```
+    pub fn checked_add(self, other: Number) -> Option<Number> {
+        self.checked_op(other, i128::checked_add, |a, b| a + b)
+    }
+
+    /// Subtracts `other` from the number, see [`Number::checked_add`] for the rules.
+    pub fn checked_sub(self, other: Number) -> Option<Number> {
+        self.checked_op(other, i128::checked_sub, |a, b| a - b)
+    }
+
+    /// Multiplies two numbers, see [`Number::checked_add`] for the rules.
+    pub fn checked_mul(self, other: Number) -> Option<Number> {
+        self.checked_op(other, i128::checked_mul, |a, b| a * b)
+    }
+
+    fn checked_op(
+        self,
+        other: Number,
+        int_op: impl FnOnce(i128, i128) -> Option<i128>,
+        float_op: impl FnOnce(f64, f64) -> f64,
+    ) -> Option<Number> {
+        match (self.n, other.n) {
+            (N::Float(_), _) | (_, N::Float(_)) => {
+                let result = float_op(self.as_f64()?, other.as_f64()?);
+                result.is_finite().then(|| result.into())
+            }
+            (a, b) => {
+                let result = int_op(a.as_i128(), b.as_i128())?;
+                if let Ok(v) = u64::try_from(result) {
+                    Some(v.into())
+                } else {
+                    i64::try_from(result).ok().map(Number::from)
+                }
+            }
+        }
+    }
 }
 
 impl PartialEq for N {
@@ -385,15 +1332,86 @@ impl PartialEq for N {
         match (self, other) {
             (N::PosInt(a), N::PosInt(b)) => a == b,
             (N::NegInt(a), N::NegInt(b)) => a == b,
-            (N::Float(a), N::Float(b)) => a == b,
+            (N::Float(a), N::Float(b)) => a == b || (a.is_nan() && b.is_nan()),
             _ => false,
         }
     }
 }
 
-// Implementing Eq is fine since any float values are always finite.
+// Implementing Eq is fine since `NaN` is treated as equal to itself.
 impl Eq for N {}
 
+impl PartialOrd for N {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for N {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Numerically equal values of different variants are ordered by the variant, to be
+        // consistent with `Eq`.
+        fn rank(n: &N) -> u8 {
+            match n {
+                N::PosInt(_) => 0,
+                N::NegInt(_) => 1,
+                N::Float(_) => 2,
+            }
+        }
+        self.cmp_numeric(other)
+            .then_with(|| rank(self).cmp(&rank(other)))
+    }
+}
+
+impl N {
+    /// Compares the numeric values exactly, ignoring the variant, e.g. `1` and `1.0` are equal.
+    ///
+    /// `NaN` is equal to itself and greater than all other numbers. Unlike `f64::total_cmp`, `0.0`
+    /// and `-0.0` are equal, to be consistent with `Eq` and `Hash`.
+    pub(crate) fn cmp_numeric(&self, other: &Self) -> Ordering {
+        match (*self, *other) {
+            (N::Float(a), N::Float(b)) => match (a.is_nan(), b.is_nan()) {
+                (true, true) => Ordering::Equal,
+                (true, false) => Ordering::Greater,
+                (false, true) => Ordering::Less,
+                (false, false) => a.partial_cmp(&b).expect("only NaN is unordered"),
+            },
+            (N::Float(a), b) => cmp_float_int(a, b.as_i128()),
+            (a, N::Float(b)) => cmp_float_int(b, a.as_i128()).reverse(),
+            (a, b) => a.as_i128().cmp(&b.as_i128()),
+        }
+    }
+
+    /// Only valid for integers.
+    fn as_i128(self) -> i128 {
+        match self {
+            N::PosInt(v) => v as i128,
+            N::NegInt(v) => v as i128,
+            N::Float(_) => unreachable!(),
+        }
+    }
+}
+
+/// Compares a float with an integer exactly. `NaN` is greater than all integers.
+fn cmp_float_int(float: f64, int: i128) -> Ordering {
+    if float.is_nan() {
+        return Ordering::Greater;
+    }
+    // The cast saturates, which is fine since integers are in the range of i64 and u64. Infinities
+    // saturate to the bounds of i128, so they compare correctly as well.
+    let trunc = float.trunc() as i128;
+    trunc.cmp(&int).then_with(|| {
+        let fract = float.fract();
+        if fract > 0.0 {
+            Ordering::Greater
+        } else if fract < 0.0 {
+            Ordering::Less
+        } else {
+            Ordering::Equal
+        }
+    })
+}
+
 impl Hash for N {
     fn hash<H: Hasher>(&self, h: &mut H) {
         match *self {
@@ -405,6 +1423,9 @@ impl Hash for N {
                     // compare equal but have different bits. We use the +0 hash
                     // for both so that hash(+0) == hash(-0).
                     0.0f64.to_bits().hash(h);
+                } else if f.is_nan() {
+                    // All NaNs are equal, independent of sign and payload.
+                    f64::NAN.to_bits().hash(h);
                 } else {
                     f.to_bits().hash(h);
                 }
@@ -413,6 +1434,30 @@ impl Hash for N {
     }
 }
 
+macro_rules! from_integer {
+    ($($ty:ident => $via:ident),*) => {
+        $(
+            impl From<$ty> for Value<'_> {
+                fn from(val: $ty) -> Self {
+                    Value::Number((val as $via).into())
+                }
+            }
+
+            impl From<$ty> for Number {
+                fn from(val: $ty) -> Self {
+                    (val as $via).into()
+                }
+            }
+        )*
+    };
+}
+
+from_integer! {
+    i8 => i64, i16 => i64, i32 => i64, isize => i64,
+    u8 => u64, u16 => u64, u32 => u64, usize => u64,
+    f32 => f64
+}
+
 impl From<u64> for Value<'_> {
     fn from(val: u64) -> Self {
         Value::Number(val.into())
@@ -437,9 +1482,10 @@ impl From<u64> for Number {
     }
 }
 
+/// Non-negative values are stored like `u64` values, so they are equal to parsed numbers.
 impl From<i64> for Number {
     fn from(val: i64) -> Self {
-        Self { n: N::NegInt(val) }
+        Self::from_i64(val)
     }
 }
 
@@ -449,46 +1495,259 @@ impl From<f64> for Number {
     }
 }
 
+/// Parses a JSON number token, e.g. `-1.5e3`.
+///
+/// Integers are stored as `u64` or `i64` if they fit, all other numbers as `f64`, like in
+/// serde_json. Floats are always correctly rounded, while serde_json without its
+/// `float_roundtrip` feature flag may be off by one ULP. Only the JSON number syntax is accepted,
+/// without surrounding whitespace, a leading `+` or leading zeros. Parsing never depends on the
+/// locale, `.` is always the decimal separator.
+///
+/// Returns `None` if `text` is not a valid JSON number or out of range of `f64`.
+///
+/// # Example
+/// ```
+/// use serde_json_borrow::{parse_number, Value};
+///
+/// let value: Value = serde_json::from_str(r#"{"price": "12.50"}"#).unwrap();
+/// let price = value.get_str("price").and_then(parse_number);
+/// assert_eq!(price.and_then(|n| n.as_f64()), Some(12.5));
+/// assert!(parse_number("12,50").is_none());
+/// ```
+pub fn parse_number(text: &str) -> Option<Number> {
+    let bytes = text.as_bytes();
+    let negative = bytes.first() == Some(&b'-');
+    let mut pos = usize::from(negative);
+    match bytes.get(pos) {
+        Some(b'0') => pos += 1,
+        Some(b'1'..=b'9') => {
+            while bytes.get(pos).is_some_and(u8::is_ascii_digit) {
+                pos += 1;
+            }
+        }
+        _ => return None,
+    }
+    let integer_end = pos;
+    if bytes.get(pos) == Some(&b'.') {
+        pos += 1;
+        let start = pos;
+        while bytes.get(pos).is_some_and(u8::is_ascii_digit) {
+            pos += 1;
+        }
+        if pos == start {
+            return None;
+        }
+    }
+    if matches!(bytes.get(pos), Some(b'e' | b'E')) {
+        pos += 1;
+        if matches!(bytes.get(pos), Some(b'+' | b'-')) {
+            pos += 1;
+        }
+        let start = pos;
+        while bytes.get(pos).is_some_and(u8::is_ascii_digit) {
+            pos += 1;
+        }
+        if pos == start {
+            return None;
+        }
+    }
+    if pos != bytes.len() {
+        return None;
+    }
+
+    if integer_end == bytes.len() {
+        if negative {
+            // `-0` is a float, like in serde_json
+            if let Ok(v) = text.parse::<i64>() {
+                if v != 0 {
+                    return Some(N::NegInt(v).into());
+                }
+            }
+        } else if let Ok(v) = text.parse::<u64>() {
+            return Some(N::PosInt(v).into());
+        }
+    }
+    // Rust's float parsing is locale independent and correctly rounded.
+    let v: f64 = text.parse().ok()?;
+    v.is_finite().then(|| N::Float(v).into())
+}
+
+/// Parses a JSON number, see [`parse_number`].
+impl FromStr for Number {
+    type Err = InvalidNumber;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        parse_number(text).ok_or(InvalidNumber)
+    }
+}
+
+/// Error returned by `Number::from_str` if the text is not a valid JSON number or out of range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidNumber;
+
+impl Display for InvalidNumber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("invalid JSON number")
+    }
+}
+
+impl std::error::Error for InvalidNumber {}
+
+impl Number {
+    /// Converts the number into a `serde_json::Number`.
+    ///
+    /// Returns an error if the number is a non-finite float, e.g. created via
+    /// `Number::from(f64::NAN)`, since it can't be represented in JSON.
+    pub fn try_to_serde(&self) -> Result<serde_json::Number, NonFiniteNumber> {
+        match self.n {
+            N::PosInt(n) => Ok(n.into()),
+            N::NegInt(n) => Ok(n.into()),
+            N::Float(n) => serde_json::Number::from_f64(n).ok_or(NonFiniteNumber {
+                path: String::new(),
+                value: n,
+            }),
+        }
+    }
+
+    /// Like `try_to_serde`, but non-finite floats become `null`, like in `serde_json`.
+    fn to_serde_value(self) -> serde_json::Value {
+        self.try_to_serde()
+            .map_or(serde_json::Value::Null, serde_json::Value::Number)
+    }
+}
+
+/// Non-finite float numbers can't be represented by `serde_json::Number`, they saturate like an
+/// `as` cast instead: `NaN` becomes `0` and the infinities become `f64::MAX` and `f64::MIN`.
+/// Use [`Number::try_to_serde`] to detect them.
 impl From<Number> for serde_json::value::Number {
     fn from(num: Number) -> Self {
-        match num.n {
-            N::PosInt(n) => n.into(),
-            N::NegInt(n) => n.into(),
-            N::Float(n) => serde_json::value::Number::from_f64(n).unwrap(),
-        }
+        num.try_to_serde().unwrap_or_else(|err| {
+            let saturated = if err.value.is_nan() {
+                0.0
+            } else {
+                err.value.clamp(f64::MIN, f64::MAX)
+            };
+            serde_json::Number::from_f64(saturated).expect("saturated float is finite")
+        })
     }
 }
 
+/// Non-finite float numbers are converted to `null`, use [`Value::try_to_serde`] to detect them.
 impl From<Value<'_>> for serde_json::Value {
     fn from(val: Value) -> Self {
         match val {
             Value::Null => serde_json::Value::Null,
             Value::Bool(val) => serde_json::Value::Bool(val),
-            Value::Number(val) => serde_json::Value::Number(val.into()),
+            Value::Number(val) => val.to_serde_value(),
             Value::Str(val) => serde_json::Value::String(val.to_string()),
             Value::Array(vals) => {
                 serde_json::Value::Array(vals.into_iter().map(|val| val.into()).collect())
             }
             Value::Object(vals) => serde_json::Value::Object(vals.into()),
+            #[cfg(feature = "bytes")]
+            Value::Bytes(bytes) => bytes.iter().copied().collect(),
         }
     }
 }
 
+/// Non-finite float numbers are converted to `null`, use [`Value::try_to_serde`] to detect them.
 impl From<&Value<'_>> for serde_json::Value {
     fn from(val: &Value) -> Self {
         match val {
             Value::Null => serde_json::Value::Null,
             Value::Bool(val) => serde_json::Value::Bool(*val),
-            Value::Number(val) => serde_json::Value::Number((*val).into()),
+            Value::Number(val) => val.to_serde_value(),
             Value::Str(val) => serde_json::Value::String(val.to_string()),
             Value::Array(vals) => {
                 serde_json::Value::Array(vals.iter().map(|val| val.into()).collect())
             }
             Value::Object(vals) => serde_json::Value::Object(vals.into()),
+            #[cfg(feature = "bytes")]
+            Value::Bytes(bytes) => bytes.iter().copied().collect(),
+        }
+    }
+}
+
+impl Value<'_> {
+    /// Converts the value into a `serde_json::Value`.
+    ///
+    /// Unlike the `From` conversion, which converts non-finite float numbers to `null`, this
+    /// returns an error with the JSON Pointer of the first non-finite number.
+    ///
+    /// `TryFrom` can't be implemented for this, since it would conflict with the `From`
+    /// conversion.
+    ///
+    /// # Example
+    /// ```
+    /// use serde_json_borrow::Value;
+    ///
+    /// let value = Value::from(vec![Value::from(1.5), Value::from(f64::NAN)]);
+    /// let err = value.try_to_serde().unwrap_err();
+    /// assert_eq!(err.path, "/1");
+    /// assert_eq!(serde_json::Value::from(&value), serde_json::json!([1.5, null]));
+    /// ```
+    pub fn try_to_serde(&self) -> Result<serde_json::Value, NonFiniteNumber> {
+        fn convert(val: &Value, path: &mut String) -> Result<serde_json::Value, NonFiniteNumber> {
+            let len = path.len();
+            let converted = match val {
+                Value::Number(n) => {
+                    n.try_to_serde()
+                        .map(serde_json::Value::Number)
+                        .map_err(|err| NonFiniteNumber {
+                            path: path.clone(),
+                            ..err
+                        })?
+                }
+                Value::Array(vals) => serde_json::Value::Array(
+                    vals.iter()
+                        .enumerate()
+                        .map(|(idx, val)| {
+                            push_pointer_token(path, &idx.to_string());
+                            let val = convert(val, path);
+                            path.truncate(len);
+                            val
+                        })
+                        .collect::<Result<_, _>>()?,
+                ),
+                Value::Object(vals) => serde_json::Value::Object(
+                    vals.iter()
+                        .map(|(key, val)| {
+                            push_pointer_token(path, key);
+                            let val = convert(val, path);
+                            path.truncate(len);
+                            Ok((key.to_string(), val?))
+                        })
+                        .collect::<Result<_, _>>()?,
+                ),
+                val => val.into(),
+            };
+            Ok(converted)
         }
+        convert(self, &mut String::new())
+    }
+}
+
+/// Error returned when converting a non-finite float number to `serde_json`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NonFiniteNumber {
+    /// The JSON Pointer of the number, empty for the root.
+    pub path: String,
+    /// The non-finite value.
+    pub value: f64,
+}
+
+impl Display for NonFiniteNumber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "number {} at {:?} can't be represented in JSON",
+            self.value, self.path
+        )
     }
 }
 
+impl std::error::Error for NonFiniteNumber {}
+
 impl<'ctx> From<&'ctx serde_json::Value> for Value<'ctx> {
     fn from(value: &'ctx serde_json::Value) -> Self {
         match value {
@@ -525,6 +1784,8 @@ impl<'ctx> From<&'ctx serde_json::Value> for Value<'ctx> {
 mod tests {
     use std::io;
 
+    use serde::Deserialize;
+
     use super::*;
 
     #[test]
@@ -544,6 +1805,152 @@ mod tests {
         assert_eq!(value.get("d").get("e"), &Value::Str("alo".into()));
     }
 
+    #[test]
+    fn pointer_test() {
+        let data = r#"{"a": {"b/c": [1, {"d~e": true}]}, "": 0}"#;
+        let value: Value = serde_json::from_str(data).unwrap();
+        assert_eq!(value.pointer(""), Some(&value));
+        assert_eq!(value.pointer("/"), Some(&Value::Number(0u64.into())));
+        assert_eq!(
+            value.pointer("/a/b~1c/0"),
+            Some(&Value::Number(1u64.into()))
+        );
+        assert_eq!(value.pointer("/a/b~1c/1/d~0e"), Some(&Value::Bool(true)));
+        assert_eq!(value.pointer("/a/b~1c/01"), None);
+        assert_eq!(value.pointer("/a/b~1c/2"), None);
+        assert_eq!(value.pointer("a"), None);
+    }
+
+    #[test]
+    fn pointer_mut_test() {
+        let data = r#"{"a": {"b": [1, 2]}}"#;
+        let mut value: Value = serde_json::from_str(data).unwrap();
+        *value.pointer_mut("/a/b/1").unwrap() = Value::Bool(false);
+        assert_eq!(value.get("a").get("b").get(1), &Value::Bool(false));
+        assert!(value.pointer_mut("/a/c").is_none());
+        assert!(value.pointer_mut("/a/b/2").is_none());
+    }
+
+    #[test]
+    fn pointer_mut_or_insert_test() {
+        let mut value = Value::Null;
+        *value.pointer_mut_or_insert("/a/b").unwrap() = Value::Array(vec![]);
+        *value.pointer_mut_or_insert("/a/b/0").unwrap() = Value::Bool(true);
+        *value.pointer_mut_or_insert("/a/b/-/c").unwrap() = Value::Str("d".into());
+        assert_eq!(value.to_string(), r#"{"a":{"b":[true,{"c":"d"}]}}"#);
+
+        // existing values are returned
+        assert_eq!(
+            value.pointer_mut_or_insert("/a/b/0"),
+            Some(&mut Value::Bool(true))
+        );
+        // can't descend into scalars or skip array elements
+        assert!(value.pointer_mut_or_insert("/a/b/0/x").is_none());
+        assert!(value.pointer_mut_or_insert("/a/b/5").is_none());
+
+        // `0` and `-` create arrays
+        let mut value = Value::Null;
+        *value.pointer_mut_or_insert("/0/10").unwrap() = Value::Bool(false);
+        *value.pointer_mut_or_insert("/0/a").unwrap() = Value::Bool(true);
+        *value.pointer_mut_or_insert("/0/list/-").unwrap() = Value::from(1u64);
+        *value.pointer_mut_or_insert("/0/list/-").unwrap() = Value::from(2u64);
+        assert_eq!(value.to_string(), r#"[{"10":false,"a":true,"list":[1,2]}]"#);
+
+        // invalid pointers don't insert anything
+        let mut value: Value = serde_json::from_str(r#"{"a": [true], "s": "x"}"#).unwrap();
+        let expected = value.clone();
+        assert!(value.pointer_mut_or_insert("/a/2").is_none());
+        assert!(value.pointer_mut_or_insert("/a/01").is_none());
+        assert!(value.pointer_mut_or_insert("/a/0/x").is_none());
+        assert!(value.pointer_mut_or_insert("/s/x").is_none());
+        assert_eq!(value, expected);
+    }
+
+    #[cfg(not(feature = "cowkeys"))]
+    #[test]
+    fn pointer_mut_or_insert_escaped_key_without_cowkeys_test() {
+        let mut value = Value::Null;
+        assert!(value.pointer_mut_or_insert("/a/b~1c/d").is_none());
+        assert!(value.pointer_mut_or_insert("/a/-/b~0c").is_none());
+        assert_eq!(value, Value::Null);
+        *value.pointer_mut_or_insert("/a/b").unwrap() = Value::Bool(true);
+        assert!(value.pointer_mut_or_insert("/a/c~1d").is_none());
+        assert_eq!(value.to_string(), r#"{"a":{"b":true}}"#);
+    }
+
+    #[cfg(feature = "cowkeys")]
+    #[test]
+    fn pointer_mut_or_insert_escaped_key_test() {
+        let mut value = Value::Null;
+        *value.pointer_mut_or_insert("/a~1b").unwrap() = Value::Bool(true);
+        assert_eq!(value.get("a/b"), &Value::Bool(true));
+    }
+
+    #[test]
+    fn get_mut_test() {
+        let data = r#"{"a": [{"b": 1}], "c": true}"#;
+        let mut value: Value = serde_json::from_str(data).unwrap();
+
+        *value
+            .get_mut("a")
+            .and_then(|v| v.get_mut(0))
+            .and_then(|v| v.get_mut("b"))
+            .unwrap() = Value::Null;
+        assert_eq!(value.get("a").get(0).get("b"), &Value::Null);
+
+        assert!(value.get_mut("missing").is_none());
+        assert!(value.get_mut(0).is_none());
+        assert!(value.get_mut("a").and_then(|v| v.get_mut(1)).is_none());
+        assert!(value.get_mut("c").and_then(|v| v.get_mut("d")).is_none());
+    }
+
+    #[test]
+    fn take_replace_test() {
+        let data = r#"{"a": {"b": "c"}, "d": [1]}"#;
+        let mut value: Value = serde_json::from_str(data).unwrap();
+
+        let a = value.pointer_mut("/a").unwrap().take();
+        assert!(matches!(a.get("b"), Value::Str(Cow::Borrowed("c"))));
+        assert_eq!(value.get("a"), &Value::Null);
+
+        let old = value
+            .pointer_mut("/d/0")
+            .unwrap()
+            .replace(Value::Bool(true));
+        assert_eq!(old, Value::Number(1u64.into()));
+        assert_eq!(value.get("d").get(0), &Value::Bool(true));
+    }
+
+    #[test]
+    fn as_mut_test() {
+        let data = r#"{"arr": [1], "obj": {}, "str": "text"}"#;
+        let mut value: Value = serde_json::from_str(data).unwrap();
+
+        let obj = value.as_object_mut().unwrap();
+        obj.get_mut("arr")
+            .unwrap()
+            .as_array_mut()
+            .unwrap()
+            .push(Value::Bool(true));
+        obj.get_mut("obj")
+            .unwrap()
+            .as_object_mut()
+            .unwrap()
+            .insert("key", Value::Null);
+        obj.get_mut("str")
+            .unwrap()
+            .as_str_mut()
+            .unwrap()
+            .to_mut()
+            .push('!');
+
+        assert_eq!(value.get("arr").get(1), &Value::Bool(true));
+        assert_eq!(value.get("obj").get("key"), &Value::Null);
+        assert_eq!(value.get("str"), &Value::Str("text!".into()));
+        assert!(value.as_array_mut().is_none());
+        assert!(value.as_str_mut().is_none());
+    }
+
     #[test]
     fn number_test() -> io::Result<()> {
         let data = r#"{"val1": 123.5, "val2": 123, "val3": -123}"#;
@@ -574,4 +1981,537 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn number_ordering() {
+        let numbers: Vec<Number> = vec![
+            N::Float(-1.5).into(),
+            N::NegInt(i64::MIN).into(),
+            N::PosInt(u64::MAX).into(),
+            N::Float(1.0).into(),
+            N::PosInt(1).into(),
+            N::Float(0.5).into(),
+            N::NegInt(-1).into(),
+            N::Float(1e300).into(),
+            N::Float(18446744073709551615.0).into(),
+        ];
+        let mut sorted = numbers.clone();
+        sorted.sort();
+        let sorted: Vec<f64> = sorted.iter().map(|n| n.as_f64().unwrap()).collect();
+        assert_eq!(
+            sorted,
+            vec![
+                i64::MIN as f64,
+                -1.5,
+                -1.0,
+                0.5,
+                1.0,
+                1.0,
+                u64::MAX as f64,
+                18446744073709551615.0,
+                1e300
+            ]
+        );
+
+        // 1 and 1.0 are numerically equal, but not equal
+        let int = Number::from(N::PosInt(1));
+        let float = Number::from(N::Float(1.0));
+        assert_eq!(int.cmp(&float), Ordering::Less);
+        assert!(int != float);
+        // u64::MAX is smaller than its float representation 2^64
+        assert_eq!(
+            Number::from(N::PosInt(u64::MAX)).cmp(&N::Float(18446744073709551615.0).into()),
+            Ordering::Less
+        );
+        assert_eq!(
+            Number::from(N::Float(0.0)).cmp(&N::Float(-0.0).into()),
+            Ordering::Equal
+        );
+    }
+
+    #[test]
+    fn number_ordering_non_finite() {
+        let nan = Number::from(f64::NAN);
+        let mut numbers: Vec<Number> = vec![
+            nan,
+            f64::INFINITY.into(),
+            1.5.into(),
+            u64::MAX.into(),
+            (-f64::NAN).into(),
+            f64::NEG_INFINITY.into(),
+            i64::MIN.into(),
+        ];
+        numbers.sort();
+        let sorted: Vec<f64> = numbers.iter().map(|n| n.as_f64().unwrap()).collect();
+        assert_eq!(
+            sorted[..5],
+            [
+                f64::NEG_INFINITY,
+                i64::MIN as f64,
+                1.5,
+                u64::MAX as f64,
+                f64::INFINITY
+            ]
+        );
+        assert!(sorted[5..].iter().all(|v| v.is_nan()));
+
+        assert!(nan == nan);
+        assert!(nan == Number::from(-f64::NAN));
+        assert_eq!(nan.cmp(&u64::MAX.into()), Ordering::Greater);
+        assert_eq!(Number::from(1_u64).cmp(&nan), Ordering::Less);
+        assert_eq!(nan.cmp(&f64::INFINITY.into()), Ordering::Greater);
+        assert_eq!(
+            Value::from(f64::NAN).cmp(&Value::from(1.5)),
+            Ordering::Greater
+        );
+
+        let hash = |n: Number| {
+            let mut hasher = std::collections::hash_map::DefaultHasher::new();
+            n.hash(&mut hasher);
+            hasher.finish()
+        };
+        assert_eq!(hash(nan), hash((-f64::NAN).into()));
+    }
+
+    #[test]
+    fn const_constructors() {
+        const STR: Value<'static> = Value::const_str("text");
+        const NEG: Number = Number::from_i64(-5);
+        const POS: Number = Number::from_i64(5);
+        assert_eq!(STR, Value::from("text"));
+        assert_eq!(Value::NULL, Value::Null);
+        assert_eq!(NEG.as_i64(), Some(-5));
+        assert!(NEG.as_u64().is_none());
+        assert!(POS == Number::from_u64(5));
+        assert!(POS == Number::from(5u64));
+    }
+
+    #[test]
+    fn try_to_serde_test() {
+        let value: Value = serde_json::from_str(r#"{"a": [1, -2, 1.5], "b": null}"#).unwrap();
+        assert_eq!(
+            value.try_to_serde().unwrap(),
+            serde_json::Value::from(&value)
+        );
+
+        let mut value = Value::Object(ObjectAsVec::from(vec![(
+            "a~b",
+            Value::from(vec![Value::from(1u64), Value::from(f64::INFINITY)]),
+        )]));
+        let err = value.try_to_serde().unwrap_err();
+        assert_eq!(err.path, "/a~0b/1");
+        assert_eq!(err.value, f64::INFINITY);
+        assert_eq!(
+            err.to_string(),
+            r#"number inf at "/a~0b/1" can't be represented in JSON"#
+        );
+        assert_eq!(
+            serde_json::Value::from(value.clone()),
+            serde_json::json!({"a~b": [1, null]})
+        );
+
+        value = Value::from(f64::NAN);
+        assert_eq!(value.try_to_serde().unwrap_err().path, "");
+        assert!(Number::from(f64::NAN).try_to_serde().is_err());
+        assert_eq!(
+            Number::from(2.5).try_to_serde().unwrap(),
+            serde_json::Number::from_f64(2.5).unwrap()
+        );
+
+        let convert = |val: f64| serde_json::Number::from(Number::from(val)).as_f64();
+        assert_eq!(convert(2.5), Some(2.5));
+        assert_eq!(convert(f64::NAN), Some(0.0));
+        assert_eq!(convert(f64::INFINITY), Some(f64::MAX));
+        assert_eq!(convert(f64::NEG_INFINITY), Some(f64::MIN));
+    }
+
+    #[test]
+    fn get_path_test() {
+        // Converted from `serde_json::Value`, so the escaped key is borrowed without `cowkeys`
+        let json: serde_json::Value = serde_json::from_str(
+            r#"{"a": {"b": [1, {"c": "x"}], "0": "key"}, "d.e": {"f\\g": 1}, "": {"": 2}}"#,
+        )
+        .unwrap();
+        let value = Value::from(&json);
+        assert_eq!(value.get_path("a.b.1.c"), &Value::from("x"));
+        assert_eq!(value.get_path("a.0"), &Value::from("key"));
+        assert_eq!(value.get_path("a.b.01"), &Value::Null);
+        assert_eq!(value.get_path("a.b.2"), &Value::Null);
+        assert_eq!(value.get_path("a.b.1.c.d"), &Value::Null);
+        assert_eq!(value.get_path(r"d\.e.f\\g").as_i64(), Some(1));
+        assert_eq!(value.get_path("d.e"), &Value::Null);
+        assert_eq!(value.get_path(".").as_i64(), Some(2));
+        assert_eq!(value.get_path(""), &value);
+    }
+
+    #[test]
+    fn typed_getters() {
+        let value: Value = serde_json::from_str(
+            r#"{"s": "x", "n": -1, "u": 1, "f": 1.5, "b": true, "a": [{"o": {}}]}"#,
+        )
+        .unwrap();
+        assert_eq!(value.get_str("s"), Some("x"));
+        assert_eq!(value.get_str("n"), None);
+        assert_eq!(value.get_i64("n"), Some(-1));
+        assert_eq!(value.get_u64("u"), Some(1));
+        assert_eq!(value.get_u64("n"), None);
+        assert_eq!(value.get_f64("f"), Some(1.5));
+        assert_eq!(value.get_bool("b"), Some(true));
+        assert_eq!(value.get_array("a").map(|arr| arr.len()), Some(1));
+        assert!(value.get_object(("a", 0, "o")).unwrap().is_empty());
+        assert_eq!(value.get_object("missing"), None);
+        assert_eq!(value.get_str(0), None);
+    }
+
+    #[test]
+    fn object_from_pairs() {
+        let labels = [("env", "prod"), ("region", "eu")];
+        let value = Value::from(&labels[..]);
+        assert_eq!(value, serde_json::json!({"env": "prod", "region": "eu"}));
+
+        let value = Value::from([("retries", 3u64), ("timeout", 30u64)]);
+        assert_eq!(value, serde_json::json!({"retries": 3, "timeout": 30}));
+
+        let map: HashMap<&str, bool> = [("a", true), ("b", false)].into_iter().collect();
+        let value = Value::from(map);
+        assert_eq!(value, serde_json::json!({"a": true, "b": false}));
+    }
+
+    #[test]
+    fn from_collections_and_option() {
+        let map: BTreeMap<&str, Vec<u64>> = [("b", vec![1]), ("a", vec![])].into_iter().collect();
+        let value = Value::from(map);
+        assert_eq!(
+            serde_json::to_string(&value).unwrap(),
+            r#"{"a":[],"b":[1]}"#
+        );
+
+        assert_eq!(Value::from(None::<bool>), Value::Null);
+        assert_eq!(Value::from(Some("x")), Value::from("x"));
+        assert_eq!(
+            Value::from(vec![Some(1u64), None]),
+            serde_json::json!([1, null])
+        );
+
+        let value: Value = (0..2u64)
+            .map(|idx| (["a", "b"][idx as usize], idx))
+            .collect();
+        assert_eq!(value, serde_json::json!({"a": 0, "b": 1}));
+    }
+
+    #[test]
+    fn collect_values() {
+        let value: Value = (1..4u64).map(Value::from).collect();
+        assert_eq!(value, serde_json::json!([1, 2, 3]));
+        let value: Value = vec![Some(true), None].into_iter().collect();
+        assert_eq!(value, serde_json::json!([true, null]));
+        let value: Value = std::iter::empty::<Value>().collect();
+        assert_eq!(value, serde_json::json!([]));
+    }
+
+    #[cfg(feature = "cowkeys")]
+    #[test]
+    fn from_collections_owned_keys() {
+        let map: HashMap<String, Option<&str>> =
+            [("a".to_string(), Some("x")), ("b".to_string(), None)]
+                .into_iter()
+                .collect();
+        assert_eq!(Value::from(map), serde_json::json!({"a": "x", "b": null}));
+        let value: Value = (0..2u64).map(|idx| (format!("k{idx}"), idx)).collect();
+        assert_eq!(value, serde_json::json!({"k0": 0, "k1": 1}));
+        let value: Value = [
+            (Cow::Owned("k".to_string()), Value::Null),
+            (Cow::Borrowed("a"), Value::from(1u64)),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(value, serde_json::json!({"k": null, "a": 1}));
+    }
+
+    #[test]
+    fn value_ordering() {
+        let mut values: Vec<Value> = serde_json::from_str(
+            r#"[{"a": 1}, [1, 2], "b", 2, true, null, [1], "a", false, {"a": 0}, -1]"#,
+        )
+        .unwrap();
+        values.sort();
+        let expected: Vec<Value> = serde_json::from_str(
+            r#"[null, false, true, -1, 2, "a", "b", [1], [1, 2], {"a": 0}, {"a": 1}]"#,
+        )
+        .unwrap();
+        assert_eq!(values, expected);
+
+        let mut map = BTreeMap::new();
+        map.insert(Value::from("key"), 1);
+        map.insert(Value::Null, 2);
+        assert_eq!(map.keys().next(), Some(&Value::Null));
+    }
+
+    #[cfg(feature = "cowkeys")]
+    #[test]
+    fn into_static_test() {
+        let json = String::from(r#"{"a": ["text", 1, null], "b": {"c": true}}"#);
+        let value: Value = serde_json::from_str(&json).unwrap();
+        let expected: serde_json::Value = serde_json::from_str(&json).unwrap();
+        let value: Value<'static> = value.into_static();
+        drop(json);
+
+        assert_eq!(value, expected);
+        assert!(matches!(value.get("a").get(0), Value::Str(Cow::Owned(text)) if text == "text"));
+        let entries = value.as_object().unwrap().as_vec();
+        assert!(entries.iter().all(|(key, _)| matches!(key, Cow::Owned(_))));
+    }
+
+    #[test]
+    fn shrink_to_fit_test() {
+        /// Collects `(len, capacity)` of all owned strings, arrays and objects in order.
+        fn capacities(value: &Value, out: &mut Vec<(usize, usize)>) {
+            match value {
+                Value::Str(Cow::Owned(text)) => out.push((text.len(), text.capacity())),
+                Value::Array(arr) => {
+                    out.push((arr.len(), arr.capacity()));
+                    arr.iter().for_each(|val| capacities(val, out));
+                }
+                Value::Object(obj) => {
+                    out.push((obj.len(), obj.0.capacity()));
+                    obj.values().for_each(|val| capacities(val, out));
+                }
+                _ => {}
+            }
+        }
+
+        let mut text = String::with_capacity(64);
+        text.push_str("owned");
+        let mut inner = Vec::with_capacity(16);
+        inner.push(Value::Str(Cow::Owned(text)));
+        let mut obj = ObjectAsVec(Vec::with_capacity(16));
+        obj.insert("a", Value::Array(inner));
+        let mut value = Value::Array(vec![Value::Object(obj), Value::from("borrowed")]);
+        let expected = value.clone();
+
+        let mut before = Vec::new();
+        capacities(&value, &mut before);
+        value.shrink_to_fit();
+        let mut after = Vec::new();
+        capacities(&value, &mut after);
+        assert_eq!(before.len(), after.len());
+        for ((_, prev_capacity), (len, capacity)) in before.into_iter().zip(after) {
+            assert!(capacity >= len);
+            assert!(capacity <= prev_capacity);
+        }
+        assert_eq!(value, expected);
+    }
+
+    #[test]
+    fn number_from_test() {
+        let parsed: Value = serde_json::from_str("[1, -1, 1.5]").unwrap();
+        assert_eq!(
+            parsed,
+            Value::from(vec![Value::from(1i64), (-1i64).into(), 1.5.into()])
+        );
+        assert_eq!(Value::Number(Number::from(1i64)), Value::from(1u64));
+        assert_eq!(Value::Number(Number::from(0i64)), Value::from(0u64));
+        assert!(Number::from(-1i8).is_i64() && !Number::from(-1i8).is_u64());
+
+        assert_eq!(Value::Number(Number::from(7u8)), Value::from(7u64));
+        assert_eq!(Value::Number(Number::from(7u16)), Value::from(7u64));
+        assert_eq!(Value::Number(Number::from(7u32)), Value::from(7u64));
+        assert_eq!(Value::Number(Number::from(7usize)), Value::from(7u64));
+        assert_eq!(Value::Number(Number::from(-7i16)), Value::from(-7i64));
+        assert_eq!(Value::Number(Number::from(-7i32)), Value::from(-7i64));
+        assert_eq!(Value::Number(Number::from(-7isize)), Value::from(-7i64));
+        assert_eq!(Value::Number(Number::from(0.5f32)), Value::from(0.5f64));
+    }
+
+    #[test]
+    fn parse_number_test() {
+        let number = |text| parse_number(text).map(|n| n.n);
+        assert!(matches!(number("0"), Some(N::PosInt(0))));
+        assert!(matches!(number("-1"), Some(N::NegInt(-1))));
+        assert!(matches!(number("-0"), Some(N::Float(v)) if v == 0.0 && v.is_sign_negative()));
+        assert!(matches!(number("1.5e2"), Some(N::Float(v)) if v == 150.0));
+        assert!(matches!(number("18446744073709551616"), Some(N::Float(_))));
+        assert!(number("+1").is_none());
+        assert!(number("1.0.0").is_none());
+        assert!(number(" 1").is_none());
+        assert!(number("NaN").is_none());
+        assert!(number("inf").is_none());
+        assert!(number("01").is_none());
+        assert!(number("1,5").is_none());
+        assert!(number("1e400").is_none());
+
+        for text in [
+            "0",
+            "-0",
+            "1e2",
+            "1E-2",
+            "-9223372036854775808",
+            "0.1",
+            "1.7976931348623157e308",
+        ] {
+            let expected: Value = serde_json::from_str(text).unwrap();
+            assert_eq!(
+                Value::Number(parse_number(text).unwrap()),
+                expected,
+                "{text}"
+            );
+        }
+
+        // serde_json without `float_roundtrip` is off by one ULP here.
+        let text = "1.024842486562917e-8";
+        let parsed = parse_number(text).unwrap().as_f64().unwrap();
+        assert_eq!(parsed, text.parse::<f64>().unwrap());
+        let serde: f64 = serde_json::from_str(text).unwrap();
+        assert!(parsed.to_bits().abs_diff(serde.to_bits()) <= 1);
+    }
+
+    #[test]
+    fn number_from_str_test() {
+        let parse = |text: &str| text.parse::<Number>().map(Value::Number);
+        assert_eq!(parse("42"), Ok(Value::from(42u64)));
+        assert_eq!(parse("-42"), Ok(Value::from(-42i64)));
+        assert_eq!(parse("1.5e1"), Ok(Value::from(15.0)));
+        assert_eq!(parse(" 1"), Err(InvalidNumber));
+        assert_eq!(
+            parse("1e999").unwrap_err().to_string(),
+            "invalid JSON number"
+        );
+    }
+
+    #[test]
+    fn number_checked_arithmetic() {
+        let num = |v: Option<Number>| v.map(Value::Number);
+        let (pos, neg, float) = (Number::from(3u64), Number::from(-5i64), Number::from(0.5));
+        assert_eq!(num(pos.checked_add(neg)), Some(Value::from(-2i64)));
+        assert_eq!(num(neg.checked_sub(neg)), Some(Value::from(0u64)));
+        assert_eq!(num(pos.checked_mul(neg)), Some(Value::from(-15i64)));
+        assert_eq!(num(pos.checked_mul(float)), Some(Value::from(1.5)));
+        assert_eq!(num(float.checked_sub(pos)), Some(Value::from(-2.5)));
+
+        let max = Number::from(u64::MAX);
+        let min = Number::from(i64::MIN);
+        assert_eq!(
+            num(max.checked_add(min)),
+            Some(Value::from(i64::MAX as u64))
+        );
+        assert!(max.checked_mul(max).is_none());
+        assert!(min.checked_sub(pos).is_none());
+        assert!(Number::from(f64::MAX).checked_mul(pos).is_none());
+
+        assert!(neg < pos && float < pos && neg < float);
+        assert!(Number::from(1u64) < Number::from(1.5) && Number::from(2.0) > Number::from(1u64));
+        assert!(Number::from(-1e300) < min);
+    }
+
+    #[test]
+    fn variant_by() {
+        let value: Value = serde_json::from_str(
+            r#"[{"type": "a", "x": 1}, {"type": 1}, {"t": "b", "c": [true]}, {"t": "unit"}]"#,
+        )
+        .unwrap();
+        let (variant, payload) = value.get(0).variant_by("type").unwrap();
+        assert_eq!(variant, "a");
+        assert_eq!(payload.get("x"), &1_u64);
+        assert!(value.get(1).variant_by("type").is_none());
+        assert!(value.variant_by("type").is_none());
+
+        let (variant, content) = value.get(2).variant_by_adjacent("t", "c").unwrap();
+        assert_eq!((variant, content.get(0)), ("b", &Value::Bool(true)));
+        let (variant, content) = value.get(3).variant_by_adjacent("t", "c").unwrap();
+        assert_eq!((variant, content), ("unit", &Value::Null));
+
+        let x = value
+            .get(0)
+            .deserialize_variant("type", |_, value| u64::deserialize(value.get("x")));
+        assert_eq!(x.unwrap(), 1);
+        let err = value
+            .get(2)
+            .deserialize_variant("type", |_, _| Ok(()))
+            .unwrap_err();
+        assert_eq!(err.to_string(), "missing tag `type`");
+    }
+
+    #[test]
+    fn deserialize_internally_tagged_enum() {
+        #[derive(Debug, PartialEq, serde::Deserialize)]
+        #[serde(tag = "type")]
+        enum Internal<'a> {
+            Click { x: u64 },
+            Key { key: &'a str },
+            Unit,
+        }
+
+        let value: Value = serde_json::from_str(
+            r#"[{"type": "Click", "x": 1}, {"key": "a", "type": "Key"}, {"type": "Unit"}]"#,
+        )
+        .unwrap();
+        let events = Vec::<Internal>::deserialize(&value).unwrap();
+        assert_eq!(
+            events,
+            [
+                Internal::Click { x: 1 },
+                Internal::Key { key: "a" },
+                Internal::Unit
+            ]
+        );
+        assert!(Internal::deserialize(&crate::json!({"x": 1})).is_err());
+    }
+
+    #[test]
+    fn numeric_iterators() {
+        let value: Value =
+            serde_json::from_str(r#"[1, -2.5, "3", 18446744073709551615, null]"#).unwrap();
+        let floats: Vec<_> = value.iter_f64().unwrap().collect();
+        assert_eq!(floats, [1.0, -2.5, u64::MAX as f64]);
+        let ints: Vec<_> = value.iter_i64().unwrap().collect();
+        assert_eq!(ints, [1]);
+
+        let floats: Vec<_> = value.try_iter_f64().unwrap().collect();
+        assert_eq!(floats[1], Ok(-2.5));
+        assert_eq!(floats[2].unwrap_err().actual, "string");
+        let ints: Result<Vec<_>, _> = value.try_iter_i64().unwrap().collect();
+        assert_eq!(ints.unwrap_err().to_string(), "expected i64, found number");
+
+        assert!(Value::from("x").iter_f64().is_none());
+        assert!(Value::Null.try_iter_i64().is_none());
+    }
+
+    #[test]
+    fn lossy_accessors() {
+        let value: Value = serde_json::from_str(
+            r#"[1, -2.0, "3", "-4.5", " 5 ", 1e19, "1e19", "x", true, " false", "True", null]"#,
+        )
+        .unwrap();
+        let values = value.as_array().unwrap();
+        let ints: Vec<_> = values.iter().map(Value::as_i64_lossy).collect();
+        assert_eq!(
+            ints,
+            [
+                Some(1),
+                Some(-2),
+                Some(3),
+                None,
+                Some(5),
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None
+            ]
+        );
+        let floats: Vec<_> = values.iter().map(Value::as_f64_lossy).collect();
+        assert_eq!(
+            &floats[..7],
+            [1.0, -2.0, 3.0, -4.5, 5.0, 1e19, 1e19].map(Some)
+        );
+        assert!(floats[7..].iter().all(Option::is_none));
+        let bools: Vec<_> = values.iter().map(Value::as_bool_lossy).collect();
+        assert_eq!(&bools[8..], [Some(true), Some(false), None, None]);
+        assert!(bools[..8].iter().all(Option::is_none));
+
+        assert_eq!(Value::from(i64::MIN as f64).as_i64_lossy(), Some(i64::MIN));
+        assert_eq!(Value::from(i64::MAX as f64).as_i64_lossy(), None);
+    }
 }
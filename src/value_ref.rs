@@ -0,0 +1,168 @@
+use std::borrow::Cow;
+use std::fmt;
+
+use crate::ser::{write_escaped_str, write_value};
+use crate::{ObjectAsVec, Value};
+
+/// A JSON tree, whose subtrees can be references into existing [`Value`]s.
+///
+/// This allows to build re-arranged projections of documents, e.g. a new top-level object
+/// referencing existing subtrees, without deep cloning them. A `ValueRef` can be serialized via
+/// `Serialize` or `Display`, or converted into a `Value` with [`ValueRef::to_value`].
+///
+/// # Example
+/// ```
+/// use serde_json_borrow::{Value, ValueRef};
+///
+/// let value: Value =
+///     serde_json::from_str(r#"{"user": {"name": "John"}, "items": [1, 2], "debug": true}"#)
+///         .unwrap();
+///
+/// let response = ValueRef::Object(vec![
+///     ("data".into(), value.get("items").into()),
+///     ("owner".into(), value.get("user").into()),
+/// ]);
+/// assert_eq!(response.to_string(), r#"{"data":[1,2],"owner":{"name":"John"}}"#);
+///
+/// let mut shallow = value.clone_shallow();
+/// if let ValueRef::Object(entries) = &mut shallow {
+///     entries.retain(|(key, _)| key != "debug");
+/// }
+/// assert_eq!(shallow.to_value(), serde_json::json!({"user": {"name": "John"}, "items": [1, 2]}));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValueRef<'a, 'ctx> {
+    /// A reference to an existing value.
+    Borrowed(&'a Value<'ctx>),
+    /// An array with the given elements.
+    Array(Vec<ValueRef<'a, 'ctx>>),
+    /// An object with the given entries. Keys are not checked for duplicates.
+    Object(Vec<(Cow<'a, str>, ValueRef<'a, 'ctx>)>),
+}
+
+impl<'ctx> Value<'ctx> {
+    /// Clones the first level of the value. The elements of arrays and the values of objects
+    /// are references into `self`.
+    ///
+    /// The result can be rearranged cheaply before serializing it, see [`ValueRef`].
+    pub fn clone_shallow(&self) -> ValueRef<'_, 'ctx> {
+        match self {
+            Value::Array(arr) => ValueRef::Array(arr.iter().map(ValueRef::Borrowed).collect()),
+            Value::Object(obj) => ValueRef::Object(
+                obj.iter()
+                    .map(|(key, value)| (Cow::Borrowed(key), ValueRef::Borrowed(value)))
+                    .collect(),
+            ),
+            _ => ValueRef::Borrowed(self),
+        }
+    }
+}
+
+impl ValueRef<'_, '_> {
+    /// Converts into a `Value` by cloning the referenced values. Keys and strings borrow from
+    /// `self`, so no strings are copied.
+    #[allow(clippy::useless_conversion)]
+    pub fn to_value(&self) -> Value<'_> {
+        match self {
+            ValueRef::Borrowed(value) => (*value).clone(),
+            ValueRef::Array(arr) => Value::Array(arr.iter().map(ValueRef::to_value).collect()),
+            ValueRef::Object(entries) => Value::Object(ObjectAsVec::from_entries(
+                entries
+                    .iter()
+                    .map(|(key, value)| (key.as_ref().into(), value.to_value()))
+                    .collect(),
+            )),
+        }
+    }
+}
+
+impl<'a, 'ctx> From<&'a Value<'ctx>> for ValueRef<'a, 'ctx> {
+    fn from(value: &'a Value<'ctx>) -> Self {
+        ValueRef::Borrowed(value)
+    }
+}
+
+/// Formats the value as compact JSON.
+impl fmt::Display for ValueRef<'_, '_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValueRef::Borrowed(value) => write_value(value, f),
+            ValueRef::Array(arr) => {
+                f.write_str("[")?;
+                for (idx, value) in arr.iter().enumerate() {
+                    if idx != 0 {
+                        f.write_str(",")?;
+                    }
+                    fmt::Display::fmt(value, f)?;
+                }
+                f.write_str("]")
+            }
+            ValueRef::Object(entries) => {
+                f.write_str("{")?;
+                for (idx, (key, value)) in entries.iter().enumerate() {
+                    if idx != 0 {
+                        f.write_str(",")?;
+                    }
+                    write_escaped_str(key, f)?;
+                    f.write_str(":")?;
+                    fmt::Display::fmt(value, f)?;
+                }
+                f.write_str("}")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clone_shallow_test() {
+        let value: Value = serde_json::from_str(r#"{"a": [1, {"b": "x"}], "c": null}"#).unwrap();
+        let shallow = value.clone_shallow();
+        let ValueRef::Object(entries) = &shallow else {
+            panic!("expected object");
+        };
+        assert!(matches!(
+            &entries[..],
+            [(_, ValueRef::Borrowed(_)), (_, ValueRef::Borrowed(_))]
+        ));
+        assert_eq!(shallow.to_value(), value);
+        assert_eq!(shallow.to_string(), value.to_string());
+        assert_eq!(serde_json::to_string(&shallow).unwrap(), value.to_string());
+
+        let arr = value.get("a").clone_shallow();
+        assert_eq!(
+            arr,
+            ValueRef::Array(vec![
+                value.get("a").get(0).into(),
+                value.get(("a", 1)).into()
+            ])
+        );
+        assert_eq!(
+            Value::Null.clone_shallow(),
+            ValueRef::Borrowed(&Value::Null)
+        );
+    }
+
+    #[test]
+    fn value_ref_projection() {
+        let value: Value = serde_json::from_str(r#"{"x": {"y": [true]}}"#).unwrap();
+        let key = String::from("k\"ey");
+        let projection = ValueRef::Array(vec![
+            ValueRef::Object(vec![
+                (key.as_str().into(), value.get("x").into()),
+                ("empty".into(), ValueRef::Array(Vec::new())),
+            ]),
+            value.get(("x", "y", 0)).into(),
+        ]);
+        let expected = serde_json::json!([{"k\"ey": {"y": [true]}, "empty": []}, true]);
+        assert_eq!(projection.to_value(), expected);
+        assert_eq!(
+            projection.to_string(),
+            r#"[{"k\"ey":{"y":[true]},"empty":[]},true]"#
+        );
+        assert_eq!(serde_json::to_value(&projection).unwrap(), expected);
+    }
+}
@@ -0,0 +1,341 @@
+use std::borrow::{Borrow, Cow};
+use std::hash::Hash;
+use std::io::{self, Write};
+
+use simd_json::prelude::{
+    ObjectMut, ObjectTrait, TypedCustomValue, TypedValue, ValueAsContainer, ValueAsMutContainer,
+    ValueAsScalar, ValueBuilder, ValueIntoContainer, ValueIntoString, Writable,
+};
+use simd_json::ValueType;
+
+use crate::object_vec::KeyStrType;
+use crate::{ObjectAsVec, Value};
+
+// Implements the value access traits of simd-json, so generic code written against them accepts
+// `Value`. The traits follow the semantics of simd-json, e.g. `ValueAsScalar::as_f64` returns
+// None for integers, use `cast_f64` instead. The inherent methods of `Value` take precedence, so
+// the traits have to be called with their path, e.g. `ValueAsScalar::as_f64(&value)`.
+//
+// `ValueBuilder` together with `MutableArray` and `MutableObject` allows parsers and decoders
+// written against the traits to build a `Value` directly, without going through serde.
+
+impl TypedValue for Value<'_> {
+    /// `Value::Bytes` has no JSON representation and is reported as
+    /// `ValueType::Extended(ExtendedValueType::None)`.
+    fn value_type(&self) -> ValueType {
+        match self {
+            Value::Null => ValueType::Null,
+            Value::Bool(_) => ValueType::Bool,
+            Value::Number(n) if n.is_f64() => ValueType::F64,
+            Value::Number(n) if n.is_u64() => ValueType::U64,
+            Value::Number(_) => ValueType::I64,
+            Value::Str(_) => ValueType::String,
+            #[cfg(feature = "bytes")]
+            Value::Bytes(_) => ValueType::Extended(simd_json::ExtendedValueType::None),
+            Value::Array(_) => ValueType::Array,
+            Value::Object(_) => ValueType::Object,
+        }
+    }
+}
+
+impl TypedCustomValue for Value<'_> {}
+
+impl ValueAsScalar for Value<'_> {
+    #[inline]
+    fn as_null(&self) -> Option<()> {
+        self.is_null().then_some(())
+    }
+
+    #[inline]
+    fn as_bool(&self) -> Option<bool> {
+        Value::as_bool(self)
+    }
+
+    #[inline]
+    fn as_i64(&self) -> Option<i64> {
+        Value::as_i64(self)
+    }
+
+    #[inline]
+    fn as_u64(&self) -> Option<u64> {
+        Value::as_u64(self)
+    }
+
+    #[inline]
+    fn as_f64(&self) -> Option<f64> {
+        match self {
+            Value::Number(n) if n.is_f64() => n.as_f64(),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    fn as_str(&self) -> Option<&str> {
+        Value::as_str(self)
+    }
+}
+
+impl<'ctx> ValueAsContainer for Value<'ctx> {
+    type Array = Vec<Value<'ctx>>;
+    type Object = ObjectAsVec<'ctx>;
+
+    #[inline]
+    fn as_array(&self) -> Option<&Vec<Value<'ctx>>> {
+        match self {
+            Value::Array(arr) => Some(arr),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    fn as_object(&self) -> Option<&ObjectAsVec<'ctx>> {
+        Value::as_object(self)
+    }
+}
+
+impl<'ctx> ValueAsMutContainer for Value<'ctx> {
+    type Array = Vec<Value<'ctx>>;
+    type Object = ObjectAsVec<'ctx>;
+
+    #[inline]
+    fn as_array_mut(&mut self) -> Option<&mut Vec<Value<'ctx>>> {
+        Value::as_array_mut(self)
+    }
+
+    #[inline]
+    fn as_object_mut(&mut self) -> Option<&mut ObjectAsVec<'ctx>> {
+        Value::as_object_mut(self)
+    }
+}
+
+impl<'ctx> ValueBuilder<'ctx> for Value<'ctx> {
+    #[inline]
+    fn array_with_capacity(capacity: usize) -> Self {
+        Value::Array(Vec::with_capacity(capacity))
+    }
+
+    #[inline]
+    fn object_with_capacity(capacity: usize) -> Self {
+        Value::Object(ObjectAsVec(Vec::with_capacity(capacity)))
+    }
+
+    #[inline]
+    fn null() -> Self {
+        Value::Null
+    }
+}
+
+impl<'ctx> ValueIntoString for Value<'ctx> {
+    type String = Cow<'ctx, str>;
+
+    #[inline]
+    fn into_string(self) -> Option<Self::String> {
+        match self {
+            Value::Str(text) => Some(text),
+            _ => None,
+        }
+    }
+}
+
+impl<'ctx> ValueIntoContainer for Value<'ctx> {
+    type Array = Vec<Value<'ctx>>;
+    type Object = ObjectAsVec<'ctx>;
+
+    #[inline]
+    fn into_array(self) -> Option<Vec<Value<'ctx>>> {
+        match self {
+            Value::Array(arr) => Some(arr),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    fn into_object(self) -> Option<ObjectAsVec<'ctx>> {
+        match self {
+            Value::Object(obj) => Some(obj),
+            _ => None,
+        }
+    }
+}
+
+impl Writable for Value<'_> {
+    fn encode(&self) -> String {
+        crate::to_string(self)
+    }
+
+    fn encode_pp(&self) -> String {
+        crate::to_string_pretty(self)
+    }
+
+    fn write<'writer, W>(&self, w: &mut W) -> io::Result<()>
+    where W: 'writer + Write {
+        crate::to_writer(w, self)
+    }
+
+    fn write_pp<'writer, W>(&self, w: &mut W) -> io::Result<()>
+    where W: 'writer + Write {
+        w.write_all(self.encode_pp().as_bytes())
+    }
+}
+
+impl<'ctx> ObjectTrait for ObjectAsVec<'ctx> {
+    type Key = str;
+    type Element = Value<'ctx>;
+
+    /// Searches linearly, as the key can't be viewed as `str`. Prefer [`ObjectAsVec::get`].
+    fn get<Q>(&self, k: &Q) -> Option<&Self::Element>
+    where
+        Self::Key: Borrow<Q> + Hash + Eq,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        self.iter()
+            .find(|(key, _)| <str as Borrow<Q>>::borrow(key) == k)
+            .map(|(_, value)| value)
+    }
+
+    fn iter<'i>(&'i self) -> Box<dyn Iterator<Item = (&'i str, &'i Value<'ctx>)> + 'i> {
+        Box::new(ObjectAsVec::iter(self))
+    }
+
+    fn keys<'i>(&'i self) -> Box<dyn Iterator<Item = &'i str> + 'i> {
+        Box::new(ObjectAsVec::keys(self))
+    }
+
+    fn values<'i>(&'i self) -> Box<dyn Iterator<Item = &'i Value<'ctx>> + 'i> {
+        Box::new(ObjectAsVec::values(self))
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        ObjectAsVec::len(self)
+    }
+}
+
+impl<'ctx> ObjectMut for ObjectAsVec<'ctx> {
+    type Key = KeyStrType<'ctx>;
+    type Element = Value<'ctx>;
+
+    /// Searches linearly, as the key can't be viewed as `str`. Prefer [`ObjectAsVec::get_mut`].
+    fn get_mut<Q>(&mut self, k: &Q) -> Option<&mut Value<'ctx>>
+    where
+        Self::Key: Borrow<Q> + Hash + Eq,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        self.0
+            .iter_mut()
+            .find(|(key, _)| key.borrow() == k)
+            .map(|(_, value)| value)
+    }
+
+    /// Same as [`ObjectAsVec::insert`], an existing value of the key is replaced.
+    fn insert<K, V>(&mut self, k: K, v: V) -> Option<Value<'ctx>>
+    where
+        Self::Key: From<K> + Hash + Eq,
+        V: Into<Value<'ctx>>,
+    {
+        self.insert_key(KeyStrType::from(k), v.into())
+    }
+
+    /// Searches linearly, as the key can't be viewed as `str`. Prefer [`ObjectAsVec::remove`].
+    fn remove<Q>(&mut self, k: &Q) -> Option<Value<'ctx>>
+    where
+        Self::Key: Borrow<Q>,
+        Q: ?Sized + Hash + Eq + Ord,
+    {
+        let pos = self.0.iter().position(|(key, _)| key.borrow() == k)?;
+        Some(self.0.remove(pos).1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use simd_json::prelude::*;
+    use simd_json::StaticNode;
+
+    use super::*;
+
+    /// Generic code written against the traits of simd-json.
+    fn describe<V>(value: &V) -> String
+    where V: ValueAsScalar + ValueAsContainer + TypedValue {
+        match value.value_type() {
+            ValueType::Array => format!("array of {}", value.as_array().unwrap().len()),
+            ValueType::Object => format!("object of {}", value.as_object().unwrap().len()),
+            ValueType::String => format!("string {}", value.as_str().unwrap()),
+            ty => format!("{ty}"),
+        }
+    }
+
+    /// A decoder written against the traits of simd-json, e.g. of an alternative parser.
+    fn build<'v, V>(key: &'v str) -> V
+    where
+        V: ValueBuilder<'v> + MutableArray<Target = V> + MutableObject<Target = V>,
+        <V as MutableObject>::Key: From<&'v str> + Hash + Eq,
+    {
+        let mut arr = V::array_with_capacity(2);
+        arr.push(V::from(1_u8)).unwrap();
+        arr.push(V::null()).unwrap();
+        let mut obj = V::object();
+        obj.insert(key, V::from("x")).unwrap();
+        obj.insert("arr", arr).unwrap();
+        obj.insert("f", V::from(1.5)).unwrap();
+        assert!(obj.insert(key, V::from(true)).unwrap().is_some());
+        obj
+    }
+
+    #[test]
+    fn value_builder_test() {
+        let key = String::from("key");
+        let mut value: Value = build(&key);
+        let simd: simd_json::BorrowedValue = build(&key);
+        assert_eq!(simd_json::BorrowedValue::from(value.clone()), simd);
+        assert_eq!(
+            value,
+            serde_json::json!({"key": true, "arr": [1, null], "f": 1.5})
+        );
+
+        *MutableObject::get_mut(&mut value, "f").unwrap() = Value::from(());
+        assert_eq!(
+            MutableObject::remove(&mut value, "f"),
+            Ok(Some(Value::Null))
+        );
+        assert_eq!(MutableObject::remove(&mut value, "f"), Ok(None));
+        assert!(MutableObject::remove(&mut Value::null(), "f").is_err());
+        assert_eq!(Value::from(StaticNode::I64(-1)), Value::from(-1_i64));
+    }
+
+    #[test]
+    fn value_trait_test() {
+        let json = r#"{"a": [1, -2, 1.5], "b": "x", "c": null, "d": true}"#;
+        let value: Value = serde_json::from_str(json).unwrap();
+        let mut data = json.as_bytes().to_vec();
+        let simd = simd_json::to_borrowed_value(&mut data).unwrap();
+        assert_eq!(describe(&value), describe(&simd));
+        assert_eq!(describe(&value), "object of 4");
+        assert_eq!(describe(value.get("b")), "string x");
+        assert_eq!(describe(value.get("c")), "null");
+        assert_eq!(describe(value.get("a").get(1)), "i64");
+
+        assert_eq!(ValueObjectAccess::get(&value, "b"), Some(&Value::from("x")));
+        assert!(ValueObjectAccess::contains_key(&value, "c"));
+        assert_eq!(value.get_idx(0), None);
+        let a = ValueObjectAccess::get(&value, "a").unwrap();
+        assert_eq!(ValueAsScalar::as_u64(a.get_idx(0).unwrap()), Some(1));
+        assert_eq!(ValueAsScalar::as_f64(a.get_idx(0).unwrap()), None);
+        assert_eq!(a.get_idx(0).unwrap().cast_f64(), Some(1.0));
+        assert_eq!(a.get_idx(2).unwrap().try_as_f64(), Ok(1.5));
+        assert!(value.get("d").try_as_str().is_err());
+        assert!(TypedScalarValue::is_bool(value.get("d")));
+        assert_eq!(ObjectTrait::keys(value.as_object().unwrap()).count(), 4);
+
+        let mut value = value;
+        MutableArray::push(value.get_mut("a").unwrap(), true).unwrap();
+        assert_eq!(value.get("a").as_array().unwrap().len(), 4);
+        assert!(MutableArray::push(value.get_mut("b").unwrap(), true).is_err());
+        assert_eq!(value.encode(), value.to_string());
+        let mut out = Vec::new();
+        value.write_pp(&mut out).unwrap();
+        assert_eq!(out, value.encode_pp().into_bytes());
+        assert!(value.as_object_mut().is_some());
+        assert_eq!(Value::from("s").into_string(), Some(Cow::Borrowed("s")));
+    }
+}
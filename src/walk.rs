@@ -0,0 +1,260 @@
+use crate::{ObjectAsVec, Value};
+
+/// Controls how [`Value::walk`] continues after a callback of a [`ValueVisitor`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WalkControl {
+    /// Continue the traversal.
+    #[default]
+    Continue,
+    /// Don't descend into the children of the current node. The corresponding `leave_*`
+    /// callback is still called. Returned from other callbacks, this is the same as `Continue`.
+    SkipChildren,
+    /// Stop the traversal immediately, no further callbacks are called.
+    Stop,
+}
+
+/// Callbacks for a depth-first traversal of a [`Value`] with [`Value::walk`].
+///
+/// All callbacks have a default implementation, which continues the traversal, so only the
+/// relevant ones need to be implemented. The lifetime `'a` allows the visitor to keep
+/// references to the visited nodes.
+pub trait ValueVisitor<'a, 'ctx> {
+    /// Called for an object, before its members.
+    fn enter_object(&mut self, _object: &'a ObjectAsVec<'ctx>) -> WalkControl {
+        WalkControl::Continue
+    }
+
+    /// Called for the key of an object member, before its value. `SkipChildren` skips the value.
+    fn visit_key(&mut self, _key: &'a str) -> WalkControl {
+        WalkControl::Continue
+    }
+
+    /// Called for an object, after its members.
+    fn leave_object(&mut self, _object: &'a ObjectAsVec<'ctx>) -> WalkControl {
+        WalkControl::Continue
+    }
+
+    /// Called for an array, before its elements.
+    fn enter_array(&mut self, _array: &'a [Value<'ctx>]) -> WalkControl {
+        WalkControl::Continue
+    }
+
+    /// Called for an array, after its elements.
+    fn leave_array(&mut self, _array: &'a [Value<'ctx>]) -> WalkControl {
+        WalkControl::Continue
+    }
+
+    /// Called for `null`, booleans, numbers and strings.
+    fn visit_scalar(&mut self, _value: &'a Value<'ctx>) -> WalkControl {
+        WalkControl::Continue
+    }
+}
+
+impl<'ctx> Value<'ctx> {
+    /// Traverses the value depth-first and calls the callbacks of `visitor` for every node.
+    ///
+    /// Returns `false` if the traversal was stopped by [`WalkControl::Stop`].
+    ///
+    /// # Example
+    /// ```
+    /// use serde_json_borrow::{ObjectAsVec, Value, ValueVisitor, WalkControl};
+    ///
+    /// /// Collects all strings, but doesn't descend into `"secret"` members.
+    /// #[derive(Default)]
+    /// struct Strings<'a> {
+    ///     strings: Vec<&'a str>,
+    /// }
+    ///
+    /// impl<'a, 'ctx> ValueVisitor<'a, 'ctx> for Strings<'a> {
+    ///     fn visit_key(&mut self, key: &'a str) -> WalkControl {
+    ///         if key == "secret" {
+    ///             WalkControl::SkipChildren
+    ///         } else {
+    ///             WalkControl::Continue
+    ///         }
+    ///     }
+    ///
+    ///     fn visit_scalar(&mut self, value: &'a Value<'ctx>) -> WalkControl {
+    ///         self.strings.extend(value.as_str());
+    ///         WalkControl::Continue
+    ///     }
+    /// }
+    ///
+    /// let value: Value =
+    ///     serde_json::from_str(r#"{"a": ["x", 1, {"secret": "y"}], "b": "z"}"#).unwrap();
+    /// let mut visitor = Strings::default();
+    /// assert!(value.walk(&mut visitor));
+    /// assert_eq!(visitor.strings, vec!["x", "z"]);
+    /// ```
+    pub fn walk<'a, V: ValueVisitor<'a, 'ctx> + ?Sized>(&'a self, visitor: &mut V) -> bool {
+        walk_value(self, visitor) != WalkControl::Stop
+    }
+}
+
+/// Returns `Stop` if the traversal was stopped, `Continue` otherwise.
+fn walk_value<'a, 'ctx, V: ValueVisitor<'a, 'ctx> + ?Sized>(
+    value: &'a Value<'ctx>,
+    visitor: &mut V,
+) -> WalkControl {
+    match value {
+        Value::Object(object) => {
+            let control = visitor.enter_object(object);
+            if control == WalkControl::Stop {
+                return WalkControl::Stop;
+            }
+            if control != WalkControl::SkipChildren {
+                for (key, value) in object.iter() {
+                    match visitor.visit_key(key) {
+                        WalkControl::Stop => return WalkControl::Stop,
+                        WalkControl::SkipChildren => continue,
+                        WalkControl::Continue => {}
+                    }
+                    if walk_value(value, visitor) == WalkControl::Stop {
+                        return WalkControl::Stop;
+                    }
+                }
+            }
+            stop_only(visitor.leave_object(object))
+        }
+        Value::Array(array) => {
+            let control = visitor.enter_array(array);
+            if control == WalkControl::Stop {
+                return WalkControl::Stop;
+            }
+            if control != WalkControl::SkipChildren {
+                for value in array {
+                    if walk_value(value, visitor) == WalkControl::Stop {
+                        return WalkControl::Stop;
+                    }
+                }
+            }
+            stop_only(visitor.leave_array(array))
+        }
+        scalar => stop_only(visitor.visit_scalar(scalar)),
+    }
+}
+
+/// `SkipChildren` has no effect after the children have been visited.
+fn stop_only(control: WalkControl) -> WalkControl {
+    match control {
+        WalkControl::Stop => WalkControl::Stop,
+        _ => WalkControl::Continue,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Records all callbacks.
+    #[derive(Default)]
+    struct Recorder {
+        events: Vec<String>,
+        stop_at: Option<&'static str>,
+        skip: Option<&'static str>,
+    }
+
+    impl Recorder {
+        fn record(&mut self, event: String) -> WalkControl {
+            let control = if Some(event.as_str()) == self.stop_at {
+                WalkControl::Stop
+            } else if Some(event.as_str()) == self.skip {
+                WalkControl::SkipChildren
+            } else {
+                WalkControl::Continue
+            };
+            self.events.push(event);
+            control
+        }
+    }
+
+    impl<'a, 'ctx> ValueVisitor<'a, 'ctx> for Recorder {
+        fn enter_object(&mut self, object: &'a ObjectAsVec<'ctx>) -> WalkControl {
+            self.record(format!("enter_object {}", object.len()))
+        }
+        fn visit_key(&mut self, key: &'a str) -> WalkControl {
+            self.record(format!("key {key}"))
+        }
+        fn leave_object(&mut self, object: &'a ObjectAsVec<'ctx>) -> WalkControl {
+            self.record(format!("leave_object {}", object.len()))
+        }
+        fn enter_array(&mut self, array: &'a [Value<'ctx>]) -> WalkControl {
+            self.record(format!("enter_array {}", array.len()))
+        }
+        fn leave_array(&mut self, array: &'a [Value<'ctx>]) -> WalkControl {
+            self.record(format!("leave_array {}", array.len()))
+        }
+        fn visit_scalar(&mut self, value: &'a Value<'ctx>) -> WalkControl {
+            self.record(format!("scalar {value}"))
+        }
+    }
+
+    fn walk(json: &str, stop_at: Option<&'static str>, skip: Option<&'static str>) -> Vec<String> {
+        let value: Value = serde_json::from_str(json).unwrap();
+        let mut recorder = Recorder {
+            events: Vec::new(),
+            stop_at,
+            skip,
+        };
+        assert_eq!(value.walk(&mut recorder), stop_at.is_none());
+        recorder.events
+    }
+
+    #[test]
+    fn walk_order() {
+        assert_eq!(
+            walk(r#"{"a": [1, null], "b": {"c": "x"}}"#, None, None),
+            vec![
+                "enter_object 2",
+                "key a",
+                "enter_array 2",
+                "scalar 1",
+                "scalar null",
+                "leave_array 2",
+                "key b",
+                "enter_object 1",
+                "key c",
+                "scalar \"x\"",
+                "leave_object 1",
+                "leave_object 2",
+            ]
+        );
+        assert_eq!(walk("true", None, None), vec!["scalar true"]);
+    }
+
+    #[test]
+    fn walk_skip_children() {
+        assert_eq!(
+            walk(r#"[[1, 2], 3]"#, None, Some("enter_array 2")),
+            vec!["enter_array 2", "leave_array 2"]
+        );
+        assert_eq!(
+            walk(r#"{"a": [1], "b": 2}"#, None, Some("key a")),
+            vec![
+                "enter_object 2",
+                "key a",
+                "key b",
+                "scalar 2",
+                "leave_object 2"
+            ]
+        );
+    }
+
+    #[test]
+    fn walk_stop() {
+        assert_eq!(
+            walk(r#"[[1, 2], 3]"#, Some("scalar 1"), None),
+            vec!["enter_array 2", "enter_array 2", "scalar 1"]
+        );
+        assert_eq!(
+            walk(r#"{"a": [1], "b": 2}"#, Some("leave_array 1"), None),
+            vec![
+                "enter_object 2",
+                "key a",
+                "enter_array 1",
+                "scalar 1",
+                "leave_array 1"
+            ]
+        );
+    }
+}
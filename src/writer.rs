@@ -0,0 +1,278 @@
+use std::fmt::Write;
+use std::io;
+
+use crate::ser::{to_writer, write_escaped_str, write_io};
+use crate::Value;
+
+/// Writes a JSON document incrementally into an `io::Write`, without building a [`crate::Value`]
+/// for the whole document.
+///
+/// Objects and arrays are written with nested writers, which borrow their parent, so the
+/// document is always valid JSON once all writers are finished. Writers close their object or
+/// array when they are dropped; call `end` to get notified about write errors.
+///
+/// A document has a single top-level value, starting a second one returns an error. Use a
+/// `DocWriter` per document to write multiple documents, e.g. as NDJSON.
+///
+/// Values are written like [`crate::to_writer`], which only fails if `writer` fails. The output
+/// is incomplete in that case, and the document shouldn't be written further.
+///
+/// # Example
+/// ```
+/// use serde_json_borrow::{DocWriter, Value};
+///
+/// let mut doc = DocWriter::new(Vec::new());
+/// let mut event = doc.object().unwrap();
+/// event.field("name", &Value::from("login")).unwrap();
+/// event.field("attempt", &Value::from(2u64)).unwrap();
+/// let mut tags = event.array("tags").unwrap();
+/// tags.value(&Value::from("a")).unwrap();
+/// tags.value(&Value::Null).unwrap();
+/// tags.end().unwrap();
+/// event.object("user").unwrap().field("id", &Value::from(7u64)).unwrap();
+/// event.end().unwrap();
+///
+/// assert_eq!(
+///     String::from_utf8(doc.into_inner()).unwrap(),
+///     r#"{"name":"login","attempt":2,"tags":["a",null],"user":{"id":7}}"#
+/// );
+/// ```
+#[derive(Debug)]
+pub struct DocWriter<W> {
+    writer: W,
+    started: bool,
+}
+
+impl<W: io::Write> DocWriter<W> {
+    /// Creates a writer, which writes into `writer`.
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            started: false,
+        }
+    }
+
+    fn start(&mut self) -> io::Result<()> {
+        if self.started {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "the document already has a top-level value",
+            ));
+        }
+        self.started = true;
+        Ok(())
+    }
+
+    /// Starts an object as the top-level value.
+    pub fn object(&mut self) -> io::Result<ObjectWriter<'_, W>> {
+        self.start()?;
+        ObjectWriter::start(&mut self.writer)
+    }
+
+    /// Starts an array as the top-level value.
+    pub fn array(&mut self) -> io::Result<ArrayWriter<'_, W>> {
+        self.start()?;
+        ArrayWriter::start(&mut self.writer)
+    }
+
+    /// Writes a complete value as the top-level value.
+    pub fn value(&mut self, value: &Value) -> io::Result<()> {
+        self.start()?;
+        to_writer(&mut self.writer, value)
+    }
+
+    /// Returns the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+/// Writes the members of a JSON object, see [`DocWriter`].
+#[derive(Debug)]
+pub struct ObjectWriter<'a, W: io::Write> {
+    writer: &'a mut W,
+    empty: bool,
+    ended: bool,
+}
+
+impl<'a, W: io::Write> ObjectWriter<'a, W> {
+    fn start(writer: &'a mut W) -> io::Result<Self> {
+        writer.write_all(b"{")?;
+        Ok(Self {
+            writer,
+            empty: true,
+            ended: false,
+        })
+    }
+
+    fn key(&mut self, key: &str) -> io::Result<()> {
+        if !self.empty {
+            self.writer.write_all(b",")?;
+        }
+        self.empty = false;
+        write_io(&mut *self.writer, |out| {
+            write_escaped_str(key, out)?;
+            out.write_str(":")
+        })
+    }
+
+    /// Writes a member with a complete value.
+    ///
+    /// Keys are not checked for duplicates.
+    pub fn field(&mut self, key: &str, value: &Value) -> io::Result<()> {
+        self.key(key)?;
+        to_writer(&mut *self.writer, value)
+    }
+
+    /// Starts a member, whose value is an object.
+    pub fn object(&mut self, key: &str) -> io::Result<ObjectWriter<'_, W>> {
+        self.key(key)?;
+        ObjectWriter::start(self.writer)
+    }
+
+    /// Starts a member, whose value is an array.
+    pub fn array(&mut self, key: &str) -> io::Result<ArrayWriter<'_, W>> {
+        self.key(key)?;
+        ArrayWriter::start(self.writer)
+    }
+
+    /// Closes the object.
+    pub fn end(mut self) -> io::Result<()> {
+        self.ended = true;
+        self.writer.write_all(b"}")
+    }
+}
+
+impl<W: io::Write> Drop for ObjectWriter<'_, W> {
+    fn drop(&mut self) {
+        if !self.ended {
+            // Errors can't be reported here, `end` reports them.
+            let _ = self.writer.write_all(b"}");
+        }
+    }
+}
+
+/// Writes the elements of a JSON array, see [`DocWriter`].
+#[derive(Debug)]
+pub struct ArrayWriter<'a, W: io::Write> {
+    writer: &'a mut W,
+    empty: bool,
+    ended: bool,
+}
+
+impl<'a, W: io::Write> ArrayWriter<'a, W> {
+    fn start(writer: &'a mut W) -> io::Result<Self> {
+        writer.write_all(b"[")?;
+        Ok(Self {
+            writer,
+            empty: true,
+            ended: false,
+        })
+    }
+
+    fn separator(&mut self) -> io::Result<()> {
+        if !self.empty {
+            self.writer.write_all(b",")?;
+        }
+        self.empty = false;
+        Ok(())
+    }
+
+    /// Writes a complete element.
+    pub fn value(&mut self, value: &Value) -> io::Result<()> {
+        self.separator()?;
+        to_writer(&mut *self.writer, value)
+    }
+
+    /// Starts an element, which is an object.
+    pub fn object(&mut self) -> io::Result<ObjectWriter<'_, W>> {
+        self.separator()?;
+        ObjectWriter::start(self.writer)
+    }
+
+    /// Starts an element, which is an array.
+    pub fn array(&mut self) -> io::Result<ArrayWriter<'_, W>> {
+        self.separator()?;
+        ArrayWriter::start(self.writer)
+    }
+
+    /// Closes the array.
+    pub fn end(mut self) -> io::Result<()> {
+        self.ended = true;
+        self.writer.write_all(b"]")
+    }
+}
+
+impl<W: io::Write> Drop for ArrayWriter<'_, W> {
+    fn drop(&mut self) {
+        if !self.ended {
+            // Errors can't be reported here, `end` reports them.
+            let _ = self.writer.write_all(b"]");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Value;
+
+    fn written(doc: DocWriter<Vec<u8>>) -> String {
+        String::from_utf8(doc.into_inner()).unwrap()
+    }
+
+    #[test]
+    fn doc_writer_nested() {
+        let mut doc = DocWriter::new(Vec::new());
+        {
+            let mut arr = doc.array().unwrap();
+            arr.value(&Value::from(1u64)).unwrap();
+            {
+                let mut obj = arr.object().unwrap();
+                obj.field("a\"b", &Value::from(vec![true])).unwrap();
+                obj.object("empty").unwrap();
+                obj.array("nested").unwrap().array().unwrap();
+            }
+            arr.array().unwrap().end().unwrap();
+        }
+        let json = written(doc);
+        assert_eq!(json, r#"[1,{"a\"b":[true],"empty":{},"nested":[[]]},[]]"#);
+        let _: serde_json::Value = serde_json::from_str(&json).unwrap();
+    }
+
+    #[test]
+    fn doc_writer_value() {
+        let mut doc = DocWriter::new(Vec::new());
+        let value: Value = serde_json::from_str(r#"{"a": [1, "x"]}"#).unwrap();
+        doc.value(&value).unwrap();
+        assert_eq!(written(doc), r#"{"a":[1,"x"]}"#);
+    }
+
+    #[test]
+    fn doc_writer_single_top_level_value() {
+        let mut doc = DocWriter::new(Vec::new());
+        doc.object().unwrap().end().unwrap();
+        let err = doc.array().unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        assert!(doc.value(&Value::Null).is_err());
+        assert!(doc.object().is_err());
+        assert_eq!(written(doc), "{}");
+    }
+
+    #[test]
+    fn doc_writer_non_finite() {
+        let mut doc = DocWriter::new(Vec::new());
+        let mut obj = doc.object().unwrap();
+        obj.field("nan", &Value::from(f64::NAN)).unwrap();
+        obj.end().unwrap();
+        assert_eq!(written(doc), r#"{"nan":null}"#);
+    }
+
+    #[test]
+    fn doc_writer_error() {
+        let mut buf = [0u8; 4];
+        let mut doc = DocWriter::new(&mut buf[..]);
+        let mut obj = doc.object().unwrap();
+        assert!(obj.field("long key", &Value::from(1u64)).is_err());
+    }
+}